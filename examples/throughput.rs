@@ -0,0 +1,98 @@
+use aes_rs::aes::{
+    authenticated_encrypt_gcm,
+    backend::Backend,
+    config::{AESConfig, CFBSegmentSize, OperationMode},
+    decrypt, encrypt,
+    key::Key,
+};
+use std::time::Instant;
+
+// run with `cargo run --release --example throughput`
+const DATA_SIZE: usize = 4 * 1024 * 1024;
+// report the best of several runs to reduce the noise
+const RUNS: usize = 5;
+
+fn main() -> Result<(), String> {
+    let plaintext: Vec<u8> = (0..DATA_SIZE).map(|i| (i % 251) as u8).collect();
+    let iv = [0xf0; 16];
+
+    for backend in [
+        Backend::ColMatrix,
+        Backend::TTable,
+        Backend::Bitsliced,
+        Backend::AesNi,
+    ] {
+        let key = Key::from([0x2b; 16]).with_backend(backend);
+
+        for (name, mode) in [
+            ("ECB", OperationMode::ECB),
+            ("CTR", OperationMode::CTR { iv }),
+            ("CBC", OperationMode::CBC { iv }),
+            // the single-block modes, every block depends on the previous one
+            (
+                "CFB",
+                OperationMode::CFB {
+                    iv,
+                    seg_size: CFBSegmentSize::Bit128,
+                },
+            ),
+            ("OFB", OperationMode::OFB { iv }),
+        ] {
+            let config = AESConfig::new(key.clone(), mode);
+
+            let mut ciphertext = Vec::with_capacity(DATA_SIZE + 16);
+            report(backend, name, "encrypt", || {
+                ciphertext.clear();
+                encrypt(&mut plaintext.as_slice(), &mut ciphertext, &config)
+            })?;
+
+            let mut decrypted = Vec::with_capacity(DATA_SIZE);
+            report(backend, name, "decrypt", || {
+                decrypted.clear();
+                decrypt(&mut ciphertext.as_slice(), &mut decrypted, &config)
+            })?;
+
+            assert_eq!(decrypted, plaintext);
+        }
+
+        let mut ciphertext = Vec::with_capacity(DATA_SIZE);
+        report(backend, "GCM", "encrypt", || {
+            ciphertext.clear();
+            authenticated_encrypt_gcm(
+                &mut plaintext.as_slice(),
+                &mut ciphertext,
+                &key,
+                &iv[..12],
+                &[],
+            )
+            .map(|(bytes_written, _)| bytes_written)
+        })?;
+    }
+
+    Ok(())
+}
+
+fn report(
+    backend: Backend,
+    mode: &str,
+    operation: &str,
+    mut run: impl FnMut() -> Result<usize, String>,
+) -> Result<(), String> {
+    let mut seconds = f64::MAX;
+    for _ in 0..RUNS {
+        let start = Instant::now();
+        run()?;
+        seconds = seconds.min(start.elapsed().as_secs_f64());
+    }
+
+    let mib = DATA_SIZE as f64 / (1024.0 * 1024.0);
+    println!(
+        "{:<10} {:<4} {:<8} {:>8.1} MiB/s",
+        format!("{:?}", backend),
+        mode,
+        operation,
+        mib / seconds
+    );
+
+    Ok(())
+}
@@ -0,0 +1,159 @@
+use crate::aes::{
+    constants::{BLOCK_SIZE, ENCRYPTION_ROUNDS_AES256},
+    datastructures::{block::Block, word::Word},
+};
+use std::arch::x86_64::{
+    __m128i, _mm_aesdec_si128, _mm_aesdeclast_si128, _mm_aesenc_si128, _mm_aesenclast_si128,
+    _mm_aesimc_si128, _mm_aeskeygenassist_si128, _mm_cvtsi128_si32, _mm_cvtsi32_si128,
+    _mm_loadu_si128, _mm_setzero_si128, _mm_shuffle_epi32, _mm_storeu_si128, _mm_xor_si128,
+};
+use std::sync::atomic::{compiler_fence, Ordering};
+
+const MAX_ROUND_KEYS: usize = ENCRYPTION_ROUNDS_AES256 + 1;
+// AESENC has a latency of several cycles but can be issued every cycle,
+// interleave 8 independent blocks to keep the pipeline busy
+const LANES: usize = 8;
+
+pub fn is_supported() -> bool {
+    is_x86_feature_detected!("aes")
+}
+
+// round keys in the register layout expected by the AES-NI instructions, the
+// decryption keys are in reverse order and transformed with AESIMC (InvMixColumns).
+// zeroized on drop
+#[derive(Clone)]
+pub struct KeySchedule {
+    rounds: usize,
+    enc: [__m128i; MAX_ROUND_KEYS],
+    dec: [__m128i; MAX_ROUND_KEYS],
+}
+
+impl KeySchedule {
+    // must only be called if is_supported() returned true
+    pub fn new(round_keys: &[Block]) -> Self {
+        unsafe { load_schedule(round_keys) }
+    }
+}
+
+impl Drop for KeySchedule {
+    fn drop(&mut self) {
+        for round_key in self.enc.iter_mut().chain(self.dec.iter_mut()) {
+            // SAFETY: round_key is a valid, aligned reference, zeroing needs no CPU feature
+            unsafe { std::ptr::write_volatile(round_key, _mm_setzero_si128()) };
+        }
+        compiler_fence(Ordering::SeqCst);
+    }
+}
+
+pub fn encrypt_block(block: Block, schedule: &KeySchedule) -> Block {
+    let mut blocks = [block];
+    unsafe { encrypt(&mut blocks, schedule) };
+    blocks[0]
+}
+
+pub fn decrypt_block(block: Block, schedule: &KeySchedule) -> Block {
+    let mut blocks = [block];
+    unsafe { decrypt(&mut blocks, schedule) };
+    blocks[0]
+}
+
+pub fn encrypt_blocks(blocks: &mut [Block], schedule: &KeySchedule) {
+    let mut chunks = blocks.chunks_exact_mut(LANES);
+    for chunk in &mut chunks {
+        unsafe { encrypt::<LANES>(chunk.try_into().unwrap(), schedule) }
+    }
+    for block in chunks.into_remainder() {
+        *block = encrypt_block(*block, schedule);
+    }
+}
+
+pub fn decrypt_blocks(blocks: &mut [Block], schedule: &KeySchedule) {
+    let mut chunks = blocks.chunks_exact_mut(LANES);
+    for chunk in &mut chunks {
+        unsafe { decrypt::<LANES>(chunk.try_into().unwrap(), schedule) }
+    }
+    for block in chunks.into_remainder() {
+        *block = decrypt_block(*block, schedule);
+    }
+}
+
+// SubWord through AESKEYGENASSIST (with a round constant of 0): the third dword of the
+// result is SubWord applied to the fourth dword of the input.
+// must only be called if is_supported() returned true
+pub fn sub_word(word: Word) -> Word {
+    unsafe { keygen_sub_word(word) }
+}
+
+#[target_feature(enable = "aes")]
+unsafe fn load_schedule(round_keys: &[Block]) -> KeySchedule {
+    let rounds = round_keys.len() - 1;
+    let mut enc = [_mm_cvtsi32_si128(0); MAX_ROUND_KEYS];
+    let mut dec = enc;
+
+    for (round, round_key) in round_keys.iter().enumerate() {
+        enc[round] = load(*round_key);
+    }
+
+    dec[0] = enc[rounds];
+    for round in 1..rounds {
+        dec[round] = _mm_aesimc_si128(enc[rounds - round]);
+    }
+    dec[rounds] = enc[0];
+
+    KeySchedule { rounds, enc, dec }
+}
+
+#[target_feature(enable = "aes")]
+unsafe fn encrypt<const N: usize>(blocks: &mut [Block; N], schedule: &KeySchedule) {
+    let rounds = schedule.rounds;
+    let mut states = [_mm_cvtsi32_si128(0); N];
+
+    for (state, block) in states.iter_mut().zip(blocks.iter()) {
+        *state = _mm_xor_si128(load(*block), schedule.enc[0]);
+    }
+    for round_key in &schedule.enc[1..rounds] {
+        for state in states.iter_mut() {
+            *state = _mm_aesenc_si128(*state, *round_key);
+        }
+    }
+    for (state, block) in states.iter().zip(blocks.iter_mut()) {
+        *block = store(_mm_aesenclast_si128(*state, schedule.enc[rounds]));
+    }
+}
+
+#[target_feature(enable = "aes")]
+unsafe fn decrypt<const N: usize>(blocks: &mut [Block; N], schedule: &KeySchedule) {
+    let rounds = schedule.rounds;
+    let mut states = [_mm_cvtsi32_si128(0); N];
+
+    for (state, block) in states.iter_mut().zip(blocks.iter()) {
+        *state = _mm_xor_si128(load(*block), schedule.dec[0]);
+    }
+    for round_key in &schedule.dec[1..rounds] {
+        for state in states.iter_mut() {
+            *state = _mm_aesdec_si128(*state, *round_key);
+        }
+    }
+    for (state, block) in states.iter().zip(blocks.iter_mut()) {
+        *block = store(_mm_aesdeclast_si128(*state, schedule.dec[rounds]));
+    }
+}
+
+#[target_feature(enable = "aes")]
+unsafe fn keygen_sub_word(word: Word) -> Word {
+    // the instructions work on little endian dwords, the word's first byte is its MSB
+    let input = _mm_shuffle_epi32::<0x00>(_mm_cvtsi32_si128(word.0.swap_bytes() as i32));
+    let output = _mm_shuffle_epi32::<0xaa>(_mm_aeskeygenassist_si128::<0>(input));
+    Word::new((_mm_cvtsi128_si32(output) as u32).swap_bytes())
+}
+
+// the first byte of the block is stored at the lowest address
+unsafe fn load(block: Block) -> __m128i {
+    _mm_loadu_si128(block.bytes().as_ptr() as *const __m128i)
+}
+
+unsafe fn store(state: __m128i) -> Block {
+    let mut bytes = [0; BLOCK_SIZE];
+    _mm_storeu_si128(bytes.as_mut_ptr() as *mut __m128i, state);
+    bytes.into()
+}
@@ -0,0 +1,425 @@
+use crate::aes::{
+    constants::{BLOCK_SIZE, ENCRYPTION_ROUNDS_AES256},
+    datastructures::block::Block,
+    zeroize::Zeroize,
+};
+use std::ops::{BitAnd, BitOr, BitXor, Not, Shl, Shr};
+
+const MAX_ROUND_KEYS: usize = ENCRYPTION_ROUNDS_AES256 + 1;
+
+// number of blocks processed in parallel, each byte position of the state
+// occupies one 8-bit lane (one bit per block) in every bit plane
+const LANES: usize = 8;
+
+// plane i holds bit i of every state byte: bit (p * LANES + b) is bit i of byte p of block b.
+// Batches use u128 planes with 8 lanes, a single block uses u16 planes with 1-bit lanes
+// instead of computing 7 unused lanes
+type State<P> = [P; 8];
+
+trait Plane:
+    Copy
+    + BitAnd<Output = Self>
+    + BitOr<Output = Self>
+    + BitXor<Output = Self>
+    + Not<Output = Self>
+    + Shl<usize, Output = Self>
+    + Shr<usize, Output = Self>
+{
+    // bits per state byte, one per block
+    const LANES: usize;
+
+    // the low bits of the value, the masks below are built as u128
+    fn truncate(value: u128) -> Self;
+    fn rotate_right(self, n: u32) -> Self;
+    fn rotate_left(self, n: u32) -> Self;
+}
+
+macro_rules! impl_plane {
+    ($ty:ty, $lanes:expr) => {
+        impl Plane for $ty {
+            const LANES: usize = $lanes;
+
+            fn truncate(value: u128) -> Self {
+                value as $ty
+            }
+
+            fn rotate_right(self, n: u32) -> Self {
+                <$ty>::rotate_right(self, n)
+            }
+
+            fn rotate_left(self, n: u32) -> Self {
+                <$ty>::rotate_left(self, n)
+            }
+        }
+    };
+}
+
+impl_plane!(u128, LANES);
+impl_plane!(u16, 1);
+
+// the round keys are the same for every block, every bit of a lane is filled.
+// the unused entries of shorter key schedules stay zero. zeroized on drop
+#[derive(Clone)]
+pub struct KeySchedule {
+    rounds: usize,
+    batch_round_keys: [State<u128>; MAX_ROUND_KEYS],
+    block_round_keys: [State<u16>; MAX_ROUND_KEYS],
+}
+
+impl KeySchedule {
+    pub fn new(round_keys: &[Block]) -> Self {
+        let mut schedule = KeySchedule {
+            rounds: round_keys.len() - 1,
+            batch_round_keys: [[0; 8]; MAX_ROUND_KEYS],
+            block_round_keys: [[0; 8]; MAX_ROUND_KEYS],
+        };
+        for (round, round_key) in round_keys.iter().enumerate() {
+            schedule.batch_round_keys[round] = broadcast(*round_key);
+            schedule.block_round_keys[round] = broadcast(*round_key);
+        }
+        schedule
+    }
+}
+
+impl Drop for KeySchedule {
+    fn drop(&mut self) {
+        self.batch_round_keys.zeroize();
+        self.block_round_keys.zeroize();
+    }
+}
+
+pub fn encrypt_block(block: Block, schedule: &KeySchedule) -> Block {
+    let mut blocks = [block];
+    encrypt_blocks(&mut blocks, schedule);
+    blocks[0]
+}
+
+pub fn encrypt_blocks(blocks: &mut [Block], schedule: &KeySchedule) {
+    for chunk in blocks.chunks_mut(LANES) {
+        if let [block] = chunk {
+            let state = encrypt_state(
+                pack_block(*block),
+                &schedule.block_round_keys,
+                schedule.rounds,
+            );
+            *block = unpack_block(&state);
+        } else {
+            let state = encrypt_state(pack(chunk), &schedule.batch_round_keys, schedule.rounds);
+            unpack(&state, chunk);
+        }
+    }
+}
+
+pub fn decrypt_blocks(blocks: &mut [Block], schedule: &KeySchedule) {
+    for chunk in blocks.chunks_mut(LANES) {
+        if let [block] = chunk {
+            let state = decrypt_state(
+                pack_block(*block),
+                &schedule.block_round_keys,
+                schedule.rounds,
+            );
+            *block = unpack_block(&state);
+        } else {
+            let state = decrypt_state(pack(chunk), &schedule.batch_round_keys, schedule.rounds);
+            unpack(&state, chunk);
+        }
+    }
+}
+
+fn encrypt_state<P: Plane>(
+    mut state: State<P>,
+    round_keys: &[State<P>],
+    rounds: usize,
+) -> State<P> {
+    // "0"th round: just apply the first block of the inital key
+    add_round_key(&mut state, &round_keys[0]);
+
+    for round_key in &round_keys[1..rounds] {
+        sub_bytes(&mut state);
+        state = shift_rows(&state);
+        state = mix_columns(&state);
+        add_round_key(&mut state, round_key);
+    }
+
+    // last round: do not mix columns
+    sub_bytes(&mut state);
+    state = shift_rows(&state);
+    add_round_key(&mut state, &round_keys[rounds]);
+
+    state
+}
+
+fn decrypt_state<P: Plane>(
+    mut state: State<P>,
+    round_keys: &[State<P>],
+    rounds: usize,
+) -> State<P> {
+    // first round: do not apply inverse mix columns
+    add_round_key(&mut state, &round_keys[rounds]);
+    state = inv_shift_rows(&state);
+    inv_sub_bytes(&mut state);
+
+    for round_key in round_keys[1..rounds].iter().rev() {
+        add_round_key(&mut state, round_key);
+        state = inv_mix_columns(&state);
+        state = inv_shift_rows(&state);
+        inv_sub_bytes(&mut state);
+    }
+
+    add_round_key(&mut state, &round_keys[0]);
+
+    state
+}
+
+// =================================================================
+//                     AES operations
+// =================================================================
+
+fn add_round_key<P: Plane>(state: &mut State<P>, round_key: &State<P>) {
+    state
+        .iter_mut()
+        .zip(round_key)
+        .for_each(|(plane, key_plane)| *plane = *plane ^ *key_plane);
+}
+
+// the S-box is computed instead of looked up, see sbox_circuit
+fn sub_bytes<P: Plane>(state: &mut State<P>) {
+    *state = sbox_circuit(state);
+}
+
+// the inverse affine transformation turns the S-box back into the inversion in GF(2^8):
+// inv(a) = inv_affine(S(a)), and S^-1(a) = inv(inv_affine(a))
+fn inv_sub_bytes<P: Plane>(state: &mut State<P>) {
+    inv_affine(state);
+    *state = sbox_circuit(state);
+    inv_affine(state);
+}
+
+fn inv_affine<P: Plane>(state: &mut State<P>) {
+    let s = *state;
+    for (i, plane) in state.iter_mut().enumerate() {
+        *plane = s[(i + 2) % 8] ^ s[(i + 5) % 8] ^ s[(i + 7) % 8];
+    }
+    add_constant(state, 0x05);
+}
+
+// byte p = 4 * col + row takes the byte from column col + row (ShiftRows) or col - row (InvShiftRows),
+// i.e. the lanes of row r are rotated by r columns
+fn shift_rows<P: Plane>(state: &State<P>) -> State<P> {
+    state.map(|plane| rotate_row_lanes(plane, P::rotate_right))
+}
+
+fn inv_shift_rows<P: Plane>(state: &State<P>) -> State<P> {
+    state.map(|plane| rotate_row_lanes(plane, P::rotate_left))
+}
+
+// out[r] = 2 * a[r] + 3 * a[r + 1] + a[r + 2] + a[r + 3]
+//        = 2 * (a[r] + a[r + 1]) + a[r + 1] + a[r + 2] + a[r + 3]
+fn mix_columns<P: Plane>(state: &State<P>) -> State<P> {
+    let rot_1 = rotate_rows(state, 1);
+    let rot_2 = rotate_rows(state, 2);
+    let rot_3 = rotate_rows(state, 3);
+
+    let mut sum = *state;
+    add_round_key(&mut sum, &rot_1);
+    let mut out = xtime(&sum);
+    add_round_key(&mut out, &rot_1);
+    add_round_key(&mut out, &rot_2);
+    add_round_key(&mut out, &rot_3);
+
+    out
+}
+
+// the inverse MixColumn matrix factors into the MixColumn matrix times
+// the circulant matrix (05 00 04 00), which only needs two doublings
+fn inv_mix_columns<P: Plane>(state: &State<P>) -> State<P> {
+    let mut sum = *state;
+    add_round_key(&mut sum, &rotate_rows(state, 2));
+    let mut out = xtime(&xtime(&sum));
+    add_round_key(&mut out, state);
+
+    mix_columns(&out)
+}
+
+// =================================================================
+//                  GF(2^8) arithmetic on bit planes
+// =================================================================
+
+// multiplication by x modulo the AES polynomial x^8 + x^4 + x^3 + x + 1
+fn xtime<P: Plane>(a: &State<P>) -> State<P> {
+    [
+        a[7],
+        a[0] ^ a[7],
+        a[1],
+        a[2] ^ a[7],
+        a[3] ^ a[7],
+        a[4],
+        a[5],
+        a[6],
+    ]
+}
+
+// The S-box circuit of Boyar and Peralta ("A depth-16 circuit for the AES S-box", 2011)
+// with 32 ANDs and 83 XOR/XNORs: a linear input layer, the inversion in GF(2^8) through
+// a tower field and a linear output layer, which includes the affine transformation.
+// u0 and s0 are the most significant bits
+#[rustfmt::skip]
+fn sbox_circuit<P: Plane>(state: &State<P>) -> State<P> {
+    let [u7, u6, u5, u4, u3, u2, u1, u0] = *state;
+
+    // top linear layer
+    let t1 = u0 ^ u3;   let t2 = u0 ^ u5;   let t3 = u0 ^ u6;   let t4 = u3 ^ u5;
+    let t5 = u4 ^ u6;   let t6 = t1 ^ t5;   let t7 = u1 ^ u2;   let t8 = u7 ^ t6;
+    let t9 = u7 ^ t7;   let t10 = t6 ^ t7;  let t11 = u1 ^ u5;  let t12 = u2 ^ u5;
+    let t13 = t3 ^ t4;  let t14 = t6 ^ t11; let t15 = t5 ^ t11; let t16 = t5 ^ t12;
+    let t17 = t9 ^ t16; let t18 = u3 ^ u7;  let t19 = t7 ^ t18; let t20 = t1 ^ t19;
+    let t21 = u6 ^ u7;  let t22 = t7 ^ t21; let t23 = t2 ^ t22; let t24 = t2 ^ t10;
+    let t25 = t20 ^ t17; let t26 = t3 ^ t16; let t27 = t1 ^ t12;
+
+    // middle non-linear layer
+    let m1 = t13 & t6;  let m2 = t23 & t8;  let m3 = t14 ^ m1;  let m4 = t19 & u7;
+    let m5 = m4 ^ m1;   let m6 = t3 & t16;  let m7 = t22 & t9;  let m8 = t26 ^ m6;
+    let m9 = t20 & t17; let m10 = m9 ^ m6;  let m11 = t1 & t15; let m12 = t4 & t27;
+    let m13 = m12 ^ m11; let m14 = t2 & t10; let m15 = m14 ^ m11; let m16 = m3 ^ m2;
+    let m17 = m5 ^ t24; let m18 = m8 ^ m7;  let m19 = m10 ^ m15; let m20 = m16 ^ m13;
+    let m21 = m17 ^ m15; let m22 = m18 ^ m13; let m23 = m19 ^ t25; let m24 = m22 ^ m23;
+    let m25 = m22 & m20; let m26 = m21 ^ m25; let m27 = m20 ^ m21; let m28 = m23 ^ m25;
+    let m29 = m28 & m27; let m30 = m26 & m24; let m31 = m20 & m23; let m32 = m27 & m31;
+    let m33 = m27 ^ m25; let m34 = m21 & m22; let m35 = m24 & m34; let m36 = m24 ^ m25;
+    let m37 = m21 ^ m29; let m38 = m32 ^ m33; let m39 = m23 ^ m30; let m40 = m35 ^ m36;
+    let m41 = m38 ^ m40; let m42 = m37 ^ m39; let m43 = m37 ^ m38; let m44 = m39 ^ m40;
+    let m45 = m42 ^ m41; let m46 = m44 & t6; let m47 = m40 & t8; let m48 = m39 & u7;
+    let m49 = m43 & t16; let m50 = m38 & t9; let m51 = m37 & t17; let m52 = m42 & t15;
+    let m53 = m45 & t27; let m54 = m41 & t10; let m55 = m44 & t13; let m56 = m40 & t23;
+    let m57 = m39 & t19; let m58 = m43 & t3; let m59 = m38 & t22; let m60 = m37 & t20;
+    let m61 = m42 & t1; let m62 = m45 & t4; let m63 = m41 & t2;
+
+    // bottom linear layer
+    let l0 = m61 ^ m62; let l1 = m50 ^ m56; let l2 = m46 ^ m48; let l3 = m47 ^ m55;
+    let l4 = m54 ^ m58; let l5 = m49 ^ m61; let l6 = m62 ^ l5;  let l7 = m46 ^ l3;
+    let l8 = m51 ^ m59; let l9 = m52 ^ m53; let l10 = m53 ^ l4; let l11 = m60 ^ l2;
+    let l12 = m48 ^ m51; let l13 = m50 ^ l0; let l14 = m52 ^ m61; let l15 = m55 ^ l1;
+    let l16 = m56 ^ l0; let l17 = m57 ^ l1; let l18 = m58 ^ l8; let l19 = m63 ^ l4;
+    let l20 = l0 ^ l1;  let l21 = l1 ^ l7;  let l22 = l3 ^ l12; let l23 = l18 ^ l2;
+    let l24 = l15 ^ l9; let l25 = l6 ^ l10; let l26 = l7 ^ l9;  let l27 = l8 ^ l10;
+    let l28 = l11 ^ l14; let l29 = l11 ^ l17;
+
+    let s0 = l6 ^ l24;   let s1 = !(l16 ^ l26); let s2 = !(l19 ^ l28); let s3 = l6 ^ l21;
+    let s4 = l20 ^ l22; let s5 = l25 ^ l29;    let s6 = !(l13 ^ l27); let s7 = !(l6 ^ l23);
+
+    [s7, s6, s5, s4, s3, s2, s1, s0]
+}
+
+// the constant is public, so branching on its bits is fine
+fn add_constant<P: Plane>(state: &mut State<P>, constant: u8) {
+    for (i, plane) in state.iter_mut().enumerate() {
+        if (constant >> i) & 1 == 1 {
+            *plane = !*plane;
+        }
+    }
+}
+
+// =================================================================
+//                      helper functions
+// =================================================================
+
+// the given mask of a column, repeated for the 4 columns of a plane
+fn column_mask<P: Plane>(mask: u128) -> P {
+    let column_bits = 4 * P::LANES;
+    P::truncate(mask | mask << column_bits | mask << (2 * column_bits) | mask << (3 * column_bits))
+}
+
+// rotate the lanes of every row r by r columns, the lanes of row r are the r-th lanes of
+// every column
+fn rotate_row_lanes<P: Plane>(plane: P, rotate: fn(P, u32) -> P) -> P {
+    let row_mask = column_mask::<P>((1 << P::LANES) - 1);
+    (1..4).fold(plane & row_mask, |rotated, row| {
+        let row_lanes = plane & (row_mask << (row * P::LANES));
+        rotated | rotate(row_lanes, (4 * row * P::LANES) as u32)
+    })
+}
+
+// every row r of a column receives the byte of row r + n in the same column,
+// a column occupies 4 lanes of each plane
+fn rotate_rows<P: Plane>(state: &State<P>, n: usize) -> State<P> {
+    let shift = n * P::LANES;
+    let column_bits = 4 * P::LANES;
+    let low_mask = column_mask::<P>((1 << (column_bits - shift)) - 1);
+    state
+        .map(|plane| ((plane >> shift) & low_mask) | ((plane << (column_bits - shift)) & !low_mask))
+}
+
+// byte p of the 8 blocks is an 8x8 bit matrix (one row per block), its transpose holds
+// the 8-bit lanes of byte p in every plane (one row per bit)
+fn pack(blocks: &[Block]) -> State<u128> {
+    let mut bytes = [[0; BLOCK_SIZE]; LANES];
+    for (block_bytes, block) in bytes.iter_mut().zip(blocks) {
+        *block_bytes = block.bytes();
+    }
+
+    let mut state = [0; 8];
+    for byte_index in 0..BLOCK_SIZE {
+        let lanes = transpose(u64::from_le_bytes(bytes.map(|block| block[byte_index])));
+        for (plane, lane) in state.iter_mut().zip(lanes.to_le_bytes()) {
+            *plane |= (lane as u128) << (byte_index * LANES);
+        }
+    }
+    state
+}
+
+fn unpack(state: &State<u128>, blocks: &mut [Block]) {
+    let mut bytes = [[0; BLOCK_SIZE]; LANES];
+    for byte_index in 0..BLOCK_SIZE {
+        let lanes = state.map(|plane| (plane >> (byte_index * LANES)) as u8);
+        for (block_bytes, byte) in bytes
+            .iter_mut()
+            .zip(transpose(u64::from_le_bytes(lanes)).to_le_bytes())
+        {
+            block_bytes[byte_index] = byte;
+        }
+    }
+
+    for (block, block_bytes) in blocks.iter_mut().zip(bytes) {
+        *block = block_bytes.into();
+    }
+}
+
+// the same transposition for a single block: bytes 0 to 7 (one row per byte) become the
+// low byte of every plane, bytes 8 to 15 the high byte
+fn pack_block(block: Block) -> State<u16> {
+    let bytes = block.bytes();
+    let low = transpose(u64::from_le_bytes(bytes[..8].try_into().unwrap())).to_le_bytes();
+    let high = transpose(u64::from_le_bytes(bytes[8..].try_into().unwrap())).to_le_bytes();
+    std::array::from_fn(|bit| u16::from_le_bytes([low[bit], high[bit]]))
+}
+
+fn unpack_block(state: &State<u16>) -> Block {
+    let low = transpose(u64::from_le_bytes(state.map(|plane| plane as u8)));
+    let high = transpose(u64::from_le_bytes(state.map(|plane| (plane >> 8) as u8)));
+    let mut bytes = [0; BLOCK_SIZE];
+    bytes[..8].copy_from_slice(&low.to_le_bytes());
+    bytes[8..].copy_from_slice(&high.to_le_bytes());
+    bytes.into()
+}
+
+// transpose the 8x8 bit matrix with row r in byte r: bit c of byte r moves to bit r of
+// byte c. Swaps the off-diagonal 1x1, 2x2 and 4x4 blocks (Hacker's Delight, 7-3)
+fn transpose(mut x: u64) -> u64 {
+    let t = (x ^ (x >> 7)) & 0x00aa00aa00aa00aa;
+    x ^= t ^ (t << 7);
+    let t = (x ^ (x >> 14)) & 0x0000cccc0000cccc;
+    x ^= t ^ (t << 14);
+    let t = (x ^ (x >> 28)) & 0x00000000f0f0f0f0;
+    x ^ t ^ (t << 28)
+}
+
+fn broadcast<P: Plane>(round_key: Block) -> State<P> {
+    let lane_mask = (1 << P::LANES) - 1;
+    let mut state = [0; 8];
+    for (byte_index, byte) in round_key.iter().enumerate() {
+        for (bit, plane) in state.iter_mut().enumerate() {
+            *plane |= (((byte >> bit) & 1) as u128 * lane_mask) << (byte_index * P::LANES);
+        }
+    }
+    state.map(P::truncate)
+}
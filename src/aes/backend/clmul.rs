@@ -0,0 +1,45 @@
+use std::arch::x86_64::{__m128i, _mm_clmulepi64_si128, _mm_set_epi64x, _mm_storeu_si128};
+
+pub fn is_supported() -> bool {
+    is_x86_feature_detected!("pclmulqdq")
+}
+
+// GHASH multiplication in GF(2^128) through PCLMULQDQ,
+// must only be called if is_supported() returned true
+pub fn ghash_mul(x: u128, y: u128) -> u128 {
+    let (high, low) = unsafe { clmul(x, y) };
+    reduce(high, low)
+}
+
+// GHASH stores the coefficient of x^0 in the MSB, multiplying the bit-reflected operands yields
+// the bit-reflected 255-bit product, shifted one bit to the right of the 256-bit result
+fn reduce(high: u128, low: u128) -> u128 {
+    let high = (high << 1) | (low >> 127);
+    let low = low << 1;
+
+    // the low half holds the coefficients of x^128 to x^255, x^128 = x^7 + x^2 + x + 1
+    let overflow = (low << 127) ^ (low << 126) ^ (low << 121);
+    let low = low ^ overflow;
+
+    high ^ low ^ (low >> 1) ^ (low >> 2) ^ (low >> 7)
+}
+
+// carry-less multiplication of two 128-bit values, returns the high and low half of the product
+#[target_feature(enable = "pclmulqdq")]
+unsafe fn clmul(x: u128, y: u128) -> (u128, u128) {
+    let x = _mm_set_epi64x((x >> 64) as i64, x as i64);
+    let y = _mm_set_epi64x((y >> 64) as i64, y as i64);
+
+    let low = to_u128(_mm_clmulepi64_si128::<0x00>(x, y));
+    let mid =
+        to_u128(_mm_clmulepi64_si128::<0x01>(x, y)) ^ to_u128(_mm_clmulepi64_si128::<0x10>(x, y));
+    let high = to_u128(_mm_clmulepi64_si128::<0x11>(x, y));
+
+    (high ^ (mid >> 64), low ^ (mid << 64))
+}
+
+unsafe fn to_u128(value: __m128i) -> u128 {
+    let mut bytes = [0; 16];
+    _mm_storeu_si128(bytes.as_mut_ptr() as *mut __m128i, value);
+    u128::from_le_bytes(bytes)
+}
@@ -0,0 +1,54 @@
+#[cfg(target_arch = "x86_64")]
+pub(crate) mod aesni;
+pub(crate) mod bitslice;
+#[cfg(target_arch = "x86_64")]
+pub(crate) mod clmul;
+mod tests;
+pub(crate) mod ttable;
+
+// The implementation used to run the Rijndael rounds on a single block.
+// All backends produce identical output, they only differ in speed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Backend {
+    // the fastest backend without secret-dependent table lookups, in the cipher as well as
+    // in the GHASH/POLYVAL multiplication: AesNi if the CPU supports it, Bitsliced otherwise
+    #[default]
+    Auto,
+    // straightforward implementation of the FIPS-197 round steps on a ColMatrix
+    ColMatrix,
+    // lookup-table implementation, four table lookups per column and round
+    TTable,
+    // constant-time bitsliced implementation without secret-dependent memory accesses
+    // or branches, processes up to 8 blocks at once
+    Bitsliced,
+    // AES-NI hardware instructions on x86_64, falls back to ColMatrix if the CPU
+    // doesn't support them
+    AesNi,
+}
+
+impl Backend {
+    // every backend, including Auto
+    pub const ALL: [Backend; 5] = [
+        Backend::Auto,
+        Backend::ColMatrix,
+        Backend::TTable,
+        Backend::Bitsliced,
+        Backend::AesNi,
+    ];
+
+    // the backend that actually runs the rounds, Auto is replaced with its choice
+    pub fn resolve(self) -> Backend {
+        match self {
+            Backend::Auto if Backend::is_hardware_supported() => Backend::AesNi,
+            Backend::Auto => Backend::Bitsliced,
+            backend => backend,
+        }
+    }
+
+    pub fn is_hardware_supported() -> bool {
+        #[cfg(target_arch = "x86_64")]
+        return aesni::is_supported();
+        #[cfg(not(target_arch = "x86_64"))]
+        return false;
+    }
+}
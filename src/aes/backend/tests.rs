@@ -0,0 +1,130 @@
+#[cfg(test)]
+mod test {
+    use crate::aes::{
+        backend::{bitslice, Backend},
+        datastructures::block::Block,
+        key::Key,
+        modes::common::{decrypt_block, decrypt_blocks, encrypt_block, encrypt_blocks},
+        random::XorShift,
+    };
+
+    #[test]
+    fn test_bitsliced_matches_colmatrix() {
+        let mut rng = XorShift(0x2545f4914f6cdd1d);
+
+        for key_len in [16, 24, 32] {
+            let key_data: Vec<u8> = (0..key_len).map(|_| rng.next() as u8).collect();
+            let key = Key::try_from(key_data.as_slice())
+                .unwrap()
+                .with_backend(Backend::ColMatrix);
+            let bitsliced_key = key.clone().with_backend(Backend::Bitsliced);
+
+            // cover a full batch of 8 blocks as well as partial batches
+            let blocks: Vec<Block> = (0..21).map(|_| Block::new(rng.next_u128())).collect();
+
+            let expected: Vec<Block> = blocks.iter().map(|b| encrypt_block(*b, &key)).collect();
+            let schedule = bitsliced_key.bitsliced_schedule();
+            let mut batch = blocks.clone();
+            bitslice::encrypt_blocks(&mut batch, schedule);
+            assert_eq!(batch, expected);
+
+            bitslice::decrypt_blocks(&mut batch, schedule);
+            assert_eq!(batch, blocks);
+
+            for block in blocks {
+                let ciphertext = encrypt_block(block, &key);
+                assert_eq!(encrypt_block(block, &bitsliced_key), ciphertext);
+                assert_eq!(decrypt_block(ciphertext, &bitsliced_key), block);
+            }
+        }
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[test]
+    fn test_aesni_matches_colmatrix() {
+        use crate::aes::{backend::aesni, datastructures::word::Word};
+
+        if !aesni::is_supported() {
+            return;
+        }
+
+        let mut rng = XorShift(0x9e3779b97f4a7c15);
+
+        for _ in 0..1000 {
+            let word = Word::new(rng.next() as u32);
+            assert_eq!(aesni::sub_word(word), word.sub_word());
+        }
+
+        for key_len in [16, 24, 32] {
+            let key_data: Vec<u8> = (0..key_len).map(|_| rng.next() as u8).collect();
+            let key = Key::try_from(key_data.as_slice()).unwrap();
+            let colmat_key = key.clone().with_backend(Backend::ColMatrix);
+            let aesni_key = key.with_backend(Backend::AesNi);
+
+            for _ in 0..100 {
+                let block = Block::new(rng.next_u128());
+                let ciphertext = encrypt_block(block, &colmat_key);
+                assert_eq!(encrypt_block(block, &aesni_key), ciphertext);
+                assert_eq!(decrypt_block(ciphertext, &aesni_key), block);
+            }
+        }
+    }
+
+    // differential test: random keys and blocks through every backend, for all key sizes,
+    // one block at a time and in batches of every length up to two full batches
+    #[test]
+    fn test_backends_agree() {
+        let mut rng = XorShift(0xd1b54a32d192ed03);
+
+        for key_len in [16, 24, 32] {
+            for _ in 0..8 {
+                let key_data: Vec<u8> = (0..key_len).map(|_| rng.next() as u8).collect();
+                let reference_key = Key::try_from(key_data.as_slice())
+                    .unwrap()
+                    .with_backend(Backend::ColMatrix);
+
+                let blocks: Vec<Block> = (0..17).map(|_| Block::new(rng.next_u128())).collect();
+                let expected: Vec<Block> = blocks
+                    .iter()
+                    .map(|block| encrypt_block(*block, &reference_key))
+                    .collect();
+
+                for backend in Backend::ALL {
+                    let key = reference_key.clone().with_backend(backend);
+
+                    for (block, ciphertext) in blocks.iter().zip(&expected) {
+                        assert_eq!(encrypt_block(*block, &key), *ciphertext, "{:?}", backend);
+                        assert_eq!(decrypt_block(*ciphertext, &key), *block, "{:?}", backend);
+                    }
+
+                    for len in 0..=blocks.len() {
+                        let mut batch = blocks[..len].to_vec();
+                        encrypt_blocks(&mut batch, &key);
+                        assert_eq!(batch, expected[..len], "{:?}", backend);
+                        decrypt_blocks(&mut batch, &key);
+                        assert_eq!(batch, blocks[..len], "{:?}", backend);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_auto_backend() {
+        let auto = Backend::Auto.resolve();
+        assert_ne!(auto, Backend::Auto);
+        assert_eq!(Backend::default(), Backend::Auto);
+        assert_eq!(Key::from([0; 16]).backend(), auto);
+
+        let mut key = Key::from([0; 16]).with_backend(Backend::TTable);
+        key.set_backend(Backend::Auto);
+        assert_eq!(key.backend(), auto);
+
+        // the software backends with secret-dependent lookups are never chosen automatically
+        if Backend::is_hardware_supported() {
+            assert_eq!(auto, Backend::AesNi);
+        } else {
+            assert_eq!(auto, Backend::Bitsliced);
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use crate::aes::{
+    constants::{INV_S_BOXES, INV_T_TABLES, S_BOXES, T_TABLES},
+    datastructures::block::Block,
+    key::Key,
+};
+
+type State = [u32; 4];
+
+pub fn encrypt_block(block: Block, key: &Key) -> Block {
+    let rounds = key.rounds();
+
+    // "0"th round: just apply the first block of the inital key
+    let mut state = add_round_key(to_state(block), key[0]);
+
+    for round in 1..rounds {
+        state = add_round_key(enc_round(state), key[round]);
+    }
+
+    // last round: do not mix columns, only substitute and shift
+    from_state(add_round_key(enc_last_round(state), key[rounds]))
+}
+
+pub fn decrypt_block(block: Block, key: &Key) -> Block {
+    let rounds = key.rounds();
+
+    let mut state = add_round_key(to_state(block), key[rounds]);
+
+    // the inverse tables apply InvMixColumns before the round key is added, so the
+    // round keys of the equivalent inverse cipher are used
+    for round in (1..rounds).rev() {
+        state = add_round_key(dec_round(state), *key.get_dec_round_key(round).unwrap());
+    }
+
+    // last round: do not mix columns
+    from_state(add_round_key(dec_last_round(state), key[0]))
+}
+
+// The lookups are load-bound, interleaving the rounds of several blocks measured slower
+// than one block at a time (examples/throughput.rs), so the batches aren't interleaved
+pub fn encrypt_blocks(blocks: &mut [Block], key: &Key) {
+    blocks
+        .iter_mut()
+        .for_each(|block| *block = encrypt_block(*block, key));
+}
+
+pub fn decrypt_blocks(blocks: &mut [Block], key: &Key) {
+    blocks
+        .iter_mut()
+        .for_each(|block| *block = decrypt_block(*block, key));
+}
+
+// =================================================================
+//                     round functions
+// =================================================================
+
+// the byte in row r of column c after ShiftRows comes from column c + r
+fn enc_round(s: State) -> State {
+    let mut out = [0; 4];
+    for (col, word) in out.iter_mut().enumerate() {
+        *word = T_TABLES[0][byte(s[col], 0)]
+            ^ T_TABLES[1][byte(s[(col + 1) % 4], 1)]
+            ^ T_TABLES[2][byte(s[(col + 2) % 4], 2)]
+            ^ T_TABLES[3][byte(s[(col + 3) % 4], 3)];
+    }
+    out
+}
+
+fn enc_last_round(s: State) -> State {
+    let mut out = [0; 4];
+    for (col, word) in out.iter_mut().enumerate() {
+        *word = u32::from_be_bytes([
+            S_BOXES[byte(s[col], 0)],
+            S_BOXES[byte(s[(col + 1) % 4], 1)],
+            S_BOXES[byte(s[(col + 2) % 4], 2)],
+            S_BOXES[byte(s[(col + 3) % 4], 3)],
+        ]);
+    }
+    out
+}
+
+// the byte in row r of column c after InvShiftRows comes from column c - r
+fn dec_round(s: State) -> State {
+    let mut out = [0; 4];
+    for (col, word) in out.iter_mut().enumerate() {
+        *word = INV_T_TABLES[0][byte(s[col], 0)]
+            ^ INV_T_TABLES[1][byte(s[(col + 3) % 4], 1)]
+            ^ INV_T_TABLES[2][byte(s[(col + 2) % 4], 2)]
+            ^ INV_T_TABLES[3][byte(s[(col + 1) % 4], 3)];
+    }
+    out
+}
+
+fn dec_last_round(s: State) -> State {
+    let mut out = [0; 4];
+    for (col, word) in out.iter_mut().enumerate() {
+        *word = u32::from_be_bytes([
+            INV_S_BOXES[byte(s[col], 0)],
+            INV_S_BOXES[byte(s[(col + 3) % 4], 1)],
+            INV_S_BOXES[byte(s[(col + 2) % 4], 2)],
+            INV_S_BOXES[byte(s[(col + 1) % 4], 3)],
+        ]);
+    }
+    out
+}
+
+// =================================================================
+//                      helper functions
+// =================================================================
+
+fn add_round_key(mut s: State, round_key: Block) -> State {
+    for (col, word) in s.iter_mut().enumerate() {
+        *word ^= round_key.get_word(col).0;
+    }
+    s
+}
+
+// row 0 is stored in the most significant byte of each column
+fn byte(col: u32, row: usize) -> usize {
+    (col >> (24 - 8 * row)) as u8 as usize
+}
+
+fn to_state(block: Block) -> State {
+    let data = u128::from(block);
+    [
+        (data >> 96) as u32,
+        (data >> 64) as u32,
+        (data >> 32) as u32,
+        data as u32,
+    ]
+}
+
+fn from_state(s: State) -> Block {
+    Block::new((s[0] as u128) << 96 | (s[1] as u128) << 64 | (s[2] as u128) << 32 | s[3] as u128)
+}
@@ -0,0 +1,41 @@
+use crate::aes::{datastructures::block::Block, key::Key, modes::common};
+
+// The block cipher core the modes of operation are built on. Implement it to run the modes
+// with another cipher, e.g. reduced-round AES or a test double that records its input blocks.
+pub trait BlockCipher {
+    fn encrypt_block(&self, block: Block) -> Block;
+
+    fn decrypt_block(&self, block: Block) -> Block;
+
+    // encrypt independent blocks in place, implementations may process several blocks at once
+    fn encrypt_blocks(&self, blocks: &mut [Block]) {
+        blocks
+            .iter_mut()
+            .for_each(|block| *block = self.encrypt_block(*block));
+    }
+
+    fn decrypt_blocks(&self, blocks: &mut [Block]) {
+        blocks
+            .iter_mut()
+            .for_each(|block| *block = self.decrypt_block(*block));
+    }
+}
+
+// AES with the key's round keys, computed by the key's backend
+impl BlockCipher for Key {
+    fn encrypt_block(&self, block: Block) -> Block {
+        common::encrypt_block(block, self)
+    }
+
+    fn decrypt_block(&self, block: Block) -> Block {
+        common::decrypt_block(block, self)
+    }
+
+    fn encrypt_blocks(&self, blocks: &mut [Block]) {
+        common::encrypt_blocks(blocks, self);
+    }
+
+    fn decrypt_blocks(&self, blocks: &mut [Block]) {
+        common::decrypt_blocks(blocks, self);
+    }
+}
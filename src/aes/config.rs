@@ -1,12 +1,23 @@
-use crate::aes::{constants::BLOCK_SIZE, key::Key};
+use crate::aes::{
+    backend::Backend,
+    cipher::BlockCipher,
+    constants::{BLOCK_SIZE, KEY_SIZE_AES128, KEY_SIZE_AES192, KEY_SIZE_AES256},
+    key::Key,
+    random::{fill_random, generate_iv},
+    zeroize::Zeroize,
+};
 
-pub struct AESConfig {
-    pub key: Key,
+// the modes of operation are generic over the block cipher, AES with a Key by default
+pub struct AESConfig<C: BlockCipher = Key> {
+    pub key: C,
     pub mode: OperationMode,
+    // number of worker threads used by encrypt_file/decrypt_file for the modes whose
+    // blocks can be processed independently (ECB, CTR and CBC decryption)
+    pub threads: usize,
 }
 
 #[allow(dead_code, clippy::upper_case_acronyms)]
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum OperationMode {
     ECB,
     CBC {
@@ -24,15 +35,168 @@ pub enum OperationMode {
     },
 }
 
+// Two keys of the same size, split from one combined key. XTS encrypts the data units
+// with the first key and the tweaks with the second (SP 800-38E), AES-SIV computes the
+// synthetic IV with the first key and encrypts with the second (RFC 5297). Neither mode
+// streams, so they aren't an OperationMode, see encrypt_xts_sectors and
+// authenticated_encrypt_siv.
+pub struct KeyPair<C: BlockCipher = Key> {
+    pub first: C,
+    pub second: C,
+}
+
+impl<C: BlockCipher> KeyPair<C> {
+    pub fn new(first: C, second: C) -> Self {
+        Self { first, second }
+    }
+}
+
+impl KeyPair<Key> {
+    // a 256 or 512-bit XTS-AES key, the halves must differ
+    pub fn new_xts(key_data: &[u8]) -> Result<Self, String> {
+        let (data_key, tweak_key) =
+            split_key(key_data, &[KEY_SIZE_AES128, KEY_SIZE_AES256], "XTS")?;
+        if data_key == tweak_key {
+            return Err("the data key and the tweak key of XTS must differ".to_string());
+        }
+
+        Ok(KeyPair::new(
+            Key::try_from(data_key)?,
+            Key::try_from(tweak_key)?,
+        ))
+    }
+
+    // a 256, 384 or 512-bit AES-SIV key
+    pub fn new_siv(key_data: &[u8]) -> Result<Self, String> {
+        let (mac_key, ctr_key) = split_key(
+            key_data,
+            &[KEY_SIZE_AES128, KEY_SIZE_AES192, KEY_SIZE_AES256],
+            "SIV",
+        )?;
+
+        Ok(KeyPair::new(
+            Key::try_from(mac_key)?,
+            Key::try_from(ctr_key)?,
+        ))
+    }
+}
+
+fn split_key<'a>(
+    key_data: &'a [u8],
+    key_sizes: &[usize],
+    mode: &str,
+) -> Result<(&'a [u8], &'a [u8]), String> {
+    if !key_sizes.iter().any(|size| 2 * size == key_data.len()) {
+        return Err(format!(
+            "Invalid {} key size: {}, expected one of {:?} bytes",
+            mode,
+            key_data.len(),
+            key_sizes.iter().map(|size| 2 * size).collect::<Vec<_>>()
+        ));
+    }
+
+    Ok(key_data.split_at(key_data.len() / 2))
+}
+
+// the number of bits s encrypted per block cipher call, Bits accepts any s from 1 to 128
 #[allow(dead_code)]
 #[derive(Clone, Copy, Debug)]
 pub enum CFBSegmentSize {
     Bit128,
     Bit8,
+    Bit1,
+    Bits(usize),
+}
+
+impl CFBSegmentSize {
+    pub fn bits(&self) -> usize {
+        match self {
+            CFBSegmentSize::Bit128 => 128,
+            CFBSegmentSize::Bit8 => 8,
+            CFBSegmentSize::Bit1 => 1,
+            CFBSegmentSize::Bits(bits) => *bits,
+        }
+    }
+}
+
+impl<C: BlockCipher> AESConfig<C> {
+    pub fn new(key: C, mode: OperationMode) -> Self {
+        let threads = std::thread::available_parallelism().map_or(1, |threads| threads.get());
+        Self { key, mode, threads }
+    }
+
+    // a single thread disables the parallel file path
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+}
+
+// the IV is zeroized with the config, the key zeroizes itself
+impl<C: BlockCipher> Drop for AESConfig<C> {
+    fn drop(&mut self) {
+        if let Some(iv) = self.mode.iv_mut() {
+            iv.zeroize();
+        }
+    }
 }
 
-impl AESConfig {
-    pub fn new(key: Key, mode: OperationMode) -> Self {
-        Self { key, mode }
+impl OperationMode {
+    // The constructors below fill the IV from the operating system RNG. The IV isn't
+    // secret but has to be passed on for decryption, see iv().
+    pub fn new_cbc() -> Result<Self, String> {
+        Ok(OperationMode::CBC { iv: generate_iv()? })
+    }
+
+    pub fn new_cfb(seg_size: CFBSegmentSize) -> Result<Self, String> {
+        Ok(OperationMode::CFB {
+            iv: generate_iv()?,
+            seg_size,
+        })
+    }
+
+    pub fn new_ofb() -> Result<Self, String> {
+        Ok(OperationMode::OFB { iv: generate_iv()? })
+    }
+
+    // a random 96-bit nonce, the 32-bit counter starts at 0 so it can't wrap around
+    // before 2^32 blocks have been encrypted
+    pub fn new_ctr() -> Result<Self, String> {
+        let mut iv = [0; BLOCK_SIZE];
+        fill_random(&mut iv[..BLOCK_SIZE - 4])?;
+        Ok(OperationMode::CTR { iv })
+    }
+
+    // a reference, a copy wouldn't be zeroized with the config
+    pub fn iv(&self) -> Option<&[u8; BLOCK_SIZE]> {
+        match self {
+            OperationMode::ECB => None,
+            OperationMode::CBC { iv }
+            | OperationMode::CFB { iv, seg_size: _ }
+            | OperationMode::OFB { iv }
+            | OperationMode::CTR { iv } => Some(iv),
+        }
+    }
+
+    fn iv_mut(&mut self) -> Option<&mut [u8; BLOCK_SIZE]> {
+        match self {
+            OperationMode::ECB => None,
+            OperationMode::CBC { iv }
+            | OperationMode::CFB { iv, seg_size: _ }
+            | OperationMode::OFB { iv }
+            | OperationMode::CTR { iv } => Some(iv),
+        }
+    }
+}
+
+impl AESConfig<Key> {
+    // the backend is stored in the key, which also holds the matching key schedule
+    pub fn backend(&self) -> Backend {
+        self.key.backend()
+    }
+
+    pub fn with_backend(mut self, backend: Backend) -> Self {
+        self.key.set_backend(backend);
+        self
     }
 }
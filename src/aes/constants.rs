@@ -1,11 +1,18 @@
 use crate::aes::datastructures::{
-    gf_math::{calc_lookup_table, calc_round_constants},
+    gf_math::{
+        calc_antilog_table, calc_ghash_reduction_table, calc_inv_s_boxes, calc_log_table,
+        calc_lookup_table, calc_round_constants, calc_s_boxes, calc_t_tables,
+    },
     word::Word,
 };
 
 pub const BLOCK_SIZE: usize = 16;
 pub const ROW_SIZE: usize = 4;
 pub const COL_SIZE: usize = 4;
+// the modes read this many bytes at once to encrypt independent blocks in batches
+pub const STREAM_BUFFER_SIZE: usize = 1024 * BLOCK_SIZE;
+// the parallel file path hands chunks of this size to the worker threads
+pub const PARALLEL_CHUNK_SIZE: usize = 64 * STREAM_BUFFER_SIZE;
 
 // ===========================================================================
 //                        key generation constants
@@ -16,64 +23,73 @@ pub const WORD_SIZE: usize = 4;
 pub const KEY_SIZE_AES128: usize = 128 / 8;
 pub const KEY_SIZE_AES192: usize = 192 / 8;
 pub const KEY_SIZE_AES256: usize = 256 / 8;
+// non-standard Rijndael key lengths, only accepted by Key::new_rijndael
+pub const KEY_SIZE_RIJNDAEL160: usize = 160 / 8;
+pub const KEY_SIZE_RIJNDAEL224: usize = 224 / 8;
 
 pub const ENCRYPTION_ROUNDS_AES128: usize = 10;
 pub const ENCRYPTION_ROUNDS_AES192: usize = 12;
 pub const ENCRYPTION_ROUNDS_AES256: usize = 14;
+pub const ENCRYPTION_ROUNDS_RIJNDAEL160: usize = 11;
+pub const ENCRYPTION_ROUNDS_RIJNDAEL224: usize = 13;
 
 pub const ROUND_WORDS_AES128: usize = KEY_SIZE_AES128 / WORD_SIZE;
 pub const ROUND_WORDS_AES192: usize = KEY_SIZE_AES192 / WORD_SIZE;
 pub const ROUND_WORDS_AES256: usize = KEY_SIZE_AES256 / WORD_SIZE;
+pub const ROUND_WORDS_RIJNDAEL160: usize = KEY_SIZE_RIJNDAEL160 / WORD_SIZE;
+pub const ROUND_WORDS_RIJNDAEL224: usize = KEY_SIZE_RIJNDAEL224 / WORD_SIZE;
 
 pub const EXPANDED_KEY_SIZE_AES128: usize = ROUND_KEY_SIZE * (ENCRYPTION_ROUNDS_AES128 + 1);
 pub const EXPANDED_KEY_SIZE_AES192: usize = ROUND_KEY_SIZE * (ENCRYPTION_ROUNDS_AES192 + 1);
 pub const EXPANDED_KEY_SIZE_AES256: usize = ROUND_KEY_SIZE * (ENCRYPTION_ROUNDS_AES256 + 1);
+pub const EXPANDED_KEY_SIZE_RIJNDAEL160: usize =
+    ROUND_KEY_SIZE * (ENCRYPTION_ROUNDS_RIJNDAEL160 + 1);
+pub const EXPANDED_KEY_SIZE_RIJNDAEL224: usize =
+    ROUND_KEY_SIZE * (ENCRYPTION_ROUNDS_RIJNDAEL224 + 1);
 
 pub const KEY_ROUND_CONSTANTS: [u8; ENCRYPTION_ROUNDS_AES128] = calc_round_constants();
 
+// Rijndael allows block and key lengths of 4 to 8 words (128 to 256 bits in steps of 32)
+pub const RIJNDAEL_MIN_WORDS: usize = 4;
+pub const RIJNDAEL_MAX_WORDS: usize = 8;
+
+// SP 800-38C tag and nonce sizes in bytes
+pub const CCM_TAG_SIZES: [usize; 7] = [4, 6, 8, 10, 12, 14, 16];
+pub const CCM_MIN_NONCE_SIZE: usize = 7;
+pub const CCM_MAX_NONCE_SIZE: usize = 13;
+
+// RFC 8452 limits the plaintext and the AAD to 2^36 bytes
+pub const GCM_SIV_NONCE_SIZE: usize = 12;
+pub const GCM_SIV_MAX_INPUT_SIZE: u64 = 1 << 36;
+
+// RFC 5297 allows at most 126 AAD components (n <= 127 strings including the plaintext).
+// The input is limited to 2^35 bytes (2^31 blocks), the 32-bit counter starts below 2^31
+// and can't wrap around then
+pub const SIV_MAX_AAD_COMPONENTS: usize = 126;
+pub const SIV_MAX_INPUT_SIZE: u64 = 1 << 35;
+
+// SP 800-38E limits a data unit to 2^20 blocks
+pub const XTS_MAX_DATA_UNIT_BLOCKS: usize = 1 << 20;
+
+// bytes of E_K(0) shown as the key fingerprint and stored in key files
+pub const KEY_CHECK_VALUE_SIZE: usize = 3;
+// an armored 256-bit key file has about 120 bytes, the limit leaves room for whitespace
+pub const MAX_KEY_FILE_SIZE: u64 = 4096;
+
 pub const PADDING_MARKER: u8 = 0x80;
 pub const PADDING_BYTE: u8 = 0x00;
 
 // ===========================================================================
 //                        block operation constants
 // ===========================================================================
-pub const S_BOXES: [u8; 256] = [
-    0x63, 0x7C, 0x77, 0x7B, 0xF2, 0x6B, 0x6F, 0xC5, 0x30, 0x01, 0x67, 0x2B, 0xFE, 0xD7, 0xAB, 0x76,
-    0xCA, 0x82, 0xC9, 0x7D, 0xFA, 0x59, 0x47, 0xF0, 0xAD, 0xD4, 0xA2, 0xAF, 0x9C, 0xA4, 0x72, 0xC0,
-    0xB7, 0xFD, 0x93, 0x26, 0x36, 0x3F, 0xF7, 0xCC, 0x34, 0xA5, 0xE5, 0xF1, 0x71, 0xD8, 0x31, 0x15,
-    0x04, 0xC7, 0x23, 0xC3, 0x18, 0x96, 0x05, 0x9A, 0x07, 0x12, 0x80, 0xE2, 0xEB, 0x27, 0xB2, 0x75,
-    0x09, 0x83, 0x2C, 0x1A, 0x1B, 0x6E, 0x5A, 0xA0, 0x52, 0x3B, 0xD6, 0xB3, 0x29, 0xE3, 0x2F, 0x84,
-    0x53, 0xD1, 0x00, 0xED, 0x20, 0xFC, 0xB1, 0x5B, 0x6A, 0xCB, 0xBE, 0x39, 0x4A, 0x4C, 0x58, 0xCF,
-    0xD0, 0xEF, 0xAA, 0xFB, 0x43, 0x4D, 0x33, 0x85, 0x45, 0xF9, 0x02, 0x7F, 0x50, 0x3C, 0x9F, 0xA8,
-    0x51, 0xA3, 0x40, 0x8F, 0x92, 0x9D, 0x38, 0xF5, 0xBC, 0xB6, 0xDA, 0x21, 0x10, 0xFF, 0xF3, 0xD2,
-    0xCD, 0x0C, 0x13, 0xEC, 0x5F, 0x97, 0x44, 0x17, 0xC4, 0xA7, 0x7E, 0x3D, 0x64, 0x5D, 0x19, 0x73,
-    0x60, 0x81, 0x4F, 0xDC, 0x22, 0x2A, 0x90, 0x88, 0x46, 0xEE, 0xB8, 0x14, 0xDE, 0x5E, 0x0B, 0xDB,
-    0xE0, 0x32, 0x3A, 0x0A, 0x49, 0x06, 0x24, 0x5C, 0xC2, 0xD3, 0xAC, 0x62, 0x91, 0x95, 0xE4, 0x79,
-    0xE7, 0xC8, 0x37, 0x6D, 0x8D, 0xD5, 0x4E, 0xA9, 0x6C, 0x56, 0xF4, 0xEA, 0x65, 0x7A, 0xAE, 0x08,
-    0xBA, 0x78, 0x25, 0x2E, 0x1C, 0xA6, 0xB4, 0xC6, 0xE8, 0xDD, 0x74, 0x1F, 0x4B, 0xBD, 0x8B, 0x8A,
-    0x70, 0x3E, 0xB5, 0x66, 0x48, 0x03, 0xF6, 0x0E, 0x61, 0x35, 0x57, 0xB9, 0x86, 0xC1, 0x1D, 0x9E,
-    0xE1, 0xF8, 0x98, 0x11, 0x69, 0xD9, 0x8E, 0x94, 0x9B, 0x1E, 0x87, 0xE9, 0xCE, 0x55, 0x28, 0xDF,
-    0x8C, 0xA1, 0x89, 0x0D, 0xBF, 0xE6, 0x42, 0x68, 0x41, 0x99, 0x2D, 0x0F, 0xB0, 0x54, 0xBB, 0x16,
-];
+// derived from the GF(2^8) arithmetic at compile time, see gf_math::calc_s_boxes
+pub const S_BOXES: [u8; 256] = calc_s_boxes();
+pub const INV_S_BOXES: [u8; 256] = calc_inv_s_boxes(&S_BOXES);
+pub const S_BOX_AFFINE_CONSTANT: u8 = 0x63;
 
-pub const INV_S_BOXES: [u8; 256] = [
-    0x52, 0x09, 0x6a, 0xd5, 0x30, 0x36, 0xa5, 0x38, 0xbf, 0x40, 0xa3, 0x9e, 0x81, 0xf3, 0xd7, 0xfb,
-    0x7c, 0xe3, 0x39, 0x82, 0x9b, 0x2f, 0xff, 0x87, 0x34, 0x8e, 0x43, 0x44, 0xc4, 0xde, 0xe9, 0xcb,
-    0x54, 0x7b, 0x94, 0x32, 0xa6, 0xc2, 0x23, 0x3d, 0xee, 0x4c, 0x95, 0x0b, 0x42, 0xfa, 0xc3, 0x4e,
-    0x08, 0x2e, 0xa1, 0x66, 0x28, 0xd9, 0x24, 0xb2, 0x76, 0x5b, 0xa2, 0x49, 0x6d, 0x8b, 0xd1, 0x25,
-    0x72, 0xf8, 0xf6, 0x64, 0x86, 0x68, 0x98, 0x16, 0xd4, 0xa4, 0x5c, 0xcc, 0x5d, 0x65, 0xb6, 0x92,
-    0x6c, 0x70, 0x48, 0x50, 0xfd, 0xed, 0xb9, 0xda, 0x5e, 0x15, 0x46, 0x57, 0xa7, 0x8d, 0x9d, 0x84,
-    0x90, 0xd8, 0xab, 0x00, 0x8c, 0xbc, 0xd3, 0x0a, 0xf7, 0xe4, 0x58, 0x05, 0xb8, 0xb3, 0x45, 0x06,
-    0xd0, 0x2c, 0x1e, 0x8f, 0xca, 0x3f, 0x0f, 0x02, 0xc1, 0xaf, 0xbd, 0x03, 0x01, 0x13, 0x8a, 0x6b,
-    0x3a, 0x91, 0x11, 0x41, 0x4f, 0x67, 0xdc, 0xea, 0x97, 0xf2, 0xcf, 0xce, 0xf0, 0xb4, 0xe6, 0x73,
-    0x96, 0xac, 0x74, 0x22, 0xe7, 0xad, 0x35, 0x85, 0xe2, 0xf9, 0x37, 0xe8, 0x1c, 0x75, 0xdf, 0x6e,
-    0x47, 0xf1, 0x1a, 0x71, 0x1d, 0x29, 0xc5, 0x89, 0x6f, 0xb7, 0x62, 0x0e, 0xaa, 0x18, 0xbe, 0x1b,
-    0xfc, 0x56, 0x3e, 0x4b, 0xc6, 0xd2, 0x79, 0x20, 0x9a, 0xdb, 0xc0, 0xfe, 0x78, 0xcd, 0x5a, 0xf4,
-    0x1f, 0xdd, 0xa8, 0x33, 0x88, 0x07, 0xc7, 0x31, 0xb1, 0x12, 0x10, 0x59, 0x27, 0x80, 0xec, 0x5f,
-    0x60, 0x51, 0x7f, 0xa9, 0x19, 0xb5, 0x4a, 0x0d, 0x2d, 0xe5, 0x7a, 0x9f, 0x93, 0xc9, 0x9c, 0xef,
-    0xa0, 0xe0, 0x3b, 0x4d, 0xae, 0x2a, 0xf5, 0xb0, 0xc8, 0xeb, 0xbb, 0x3c, 0x83, 0x53, 0x99, 0x61,
-    0x17, 0x2b, 0x04, 0x7e, 0xba, 0x77, 0xd6, 0x26, 0xe1, 0x69, 0x14, 0x63, 0x55, 0x21, 0x0c, 0x7d,
-];
+// the ShiftRows offsets of rows 1 to 3 for the block lengths Nb = 4 to 8
+pub const RIJNDAEL_SHIFT_OFFSETS: [[usize; 3]; RIJNDAEL_MAX_WORDS - RIJNDAEL_MIN_WORDS + 1] =
+    [[1, 2, 3], [1, 2, 3], [1, 2, 3], [1, 2, 4], [1, 3, 4]];
 
 pub const MIX_COL_MATRIX: [Word; 4] = [
     Word::new(u32::from_be_bytes([0x02, 0x03, 0x01, 0x01])),
@@ -89,11 +105,18 @@ pub const INV_MIX_COL_MATRIX: [Word; 4] = [
     Word::new(u32::from_be_bytes([0x0B, 0x0D, 0x09, 0x0E])),
 ];
 
+// T-tables merge SubBytes, ShiftRows and MixColumns into four lookups per column,
+// the inverse tables merge InvSubBytes and InvMixColumns in the same way
+pub const T_TABLES: [[u32; 256]; 4] = calc_t_tables(&S_BOXES, &MIX_COL_MATRIX);
+pub const INV_T_TABLES: [[u32; 256]; 4] = calc_t_tables(&INV_S_BOXES, &INV_MIX_COL_MATRIX);
+
 // ===========================================================================
 //                        GF arithmetic constants
 // ===========================================================================
-pub const GHASH_IRREDUCIBLE_POLY: u128 = 0b1110_0001 << 120;
+pub const GHASH_REDUCTION_TABLE: [u128; 16] = calc_ghash_reduction_table();
 pub const AES_IRREDUCIBLE_POLY: u8 = 0x1b;
+pub const GF256_ANTILOG_TABLE: [u8; 256] = calc_antilog_table();
+pub const GF256_LOG_TABLE: [u8; 256] = calc_log_table(&GF256_ANTILOG_TABLE);
 // calculate lookup tables for the values 2, 3, 9, 11, 13 and 15
 // (these are the values used in the MixColumn and InverseMixColumn matrices)
 pub const GF256_MULT_02_LOOKUP_TABLE: [u8; 256] = calc_lookup_table(2);
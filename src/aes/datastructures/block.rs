@@ -1,6 +1,6 @@
 use crate::aes::{
     constants::BLOCK_SIZE,
-    datastructures::{gf_math::ghash_mul, word::Word},
+    datastructures::{gf128::Gf128, word::Word},
 };
 use std::{array::TryFromSliceError, fmt::Display};
 
@@ -51,15 +51,11 @@ impl Block {
     }
 
     pub fn rotate_left(&mut self, bytes: usize) {
-        let mut u128_bytes = self.bytes();
-        u128_bytes.rotate_left(bytes);
-        self.0 = u128::from_be_bytes(u128_bytes);
+        self.0 = self.0.rotate_left(bytes as u32 * 8);
     }
 
     pub fn rotate_right(&mut self, bytes: usize) {
-        let mut u128_bytes = self.bytes();
-        u128_bytes.rotate_right(bytes);
-        self.0 = u128::from_be_bytes(u128_bytes);
+        self.0 = self.0.rotate_right(bytes as u32 * 8);
     }
 
     pub fn bytes(self) -> [u8; BLOCK_SIZE] {
@@ -106,7 +102,7 @@ impl std::ops::Mul for Block {
 
 impl std::ops::MulAssign for Block {
     fn mul_assign(&mut self, rhs: Self) {
-        self.0 = ghash_mul(self.0, rhs.0);
+        self.0 = (Gf128::from(*self) * Gf128::from(rhs)).value();
     }
 }
 
@@ -202,12 +198,11 @@ impl From<[u8; 16]> for Block {
 
 impl From<[Word; 4]> for Block {
     fn from(value: [Word; 4]) -> Self {
-        let bytes = value
+        let block = value
             .into_iter()
-            .flat_map(|word| word.bytes())
-            .collect::<Vec<u8>>();
+            .fold(0, |acc, word| (acc << 32) | word.0 as u128);
 
-        bytes.as_slice().try_into().unwrap()
+        Block::new(block)
     }
 }
 
@@ -247,13 +242,7 @@ impl From<Block> for u128 {
 
 impl From<Block> for [Word; 4] {
     fn from(value: Block) -> Self {
-        value
-            .bytes()
-            .chunks(4)
-            .map(|chunk| Word::try_from(chunk).unwrap())
-            .collect::<Vec<Word>>()
-            .try_into()
-            .unwrap()
+        std::array::from_fn(|col| value.get_word(col))
     }
 }
 
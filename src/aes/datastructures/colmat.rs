@@ -68,13 +68,11 @@ impl ColMatrix {
     }
 
     fn get_row(&self, row: usize) -> Word {
-        (0..BLOCK_SIZE)
-            .skip(row)
-            .step_by(ROW_SIZE)
-            .map(|byte_index| self.block.get_byte(byte_index))
-            .collect::<Vec<_>>()
-            .try_into()
-            .unwrap()
+        let mut row_data = [0; COL_SIZE];
+        for (col, byte) in row_data.iter_mut().enumerate() {
+            *byte = self.block.get_byte(col * ROW_SIZE + row);
+        }
+        row_data.into()
     }
 
     fn set_row(&mut self, row: usize, value: Word) {
@@ -96,13 +94,7 @@ impl ColMatrix {
     }
 
     fn map_bytes(&mut self, map_fn: impl Fn(u8) -> u8) {
-        self.block = self
-            .block
-            .iter()
-            .map(map_fn)
-            .collect::<Vec<u8>>()
-            .try_into()
-            .unwrap()
+        self.block = self.block.bytes().map(map_fn).into();
     }
 }
 
@@ -0,0 +1,163 @@
+use crate::aes::{constants::BLOCK_SIZE, datastructures::block::Block};
+use std::fmt::Display;
+
+// An element of GF(2^128) in GCM's bit-reflected convention (NIST SP 800-38D, Section 6.3):
+// the MSB holds the coefficient of x^0 and the LSB that of x^127, products are reduced
+// by the GHASH polynomial x^128 + x^7 + x^2 + x + 1.
+//
+// POLYVAL (RFC 8452) works in the same field, but stores its elements in little-endian byte
+// order and reduces by x^128 + x^127 + x^126 + x^121 + 1. from_polyval_bytes/to_polyval_bytes
+// convert between the two conventions (RFC 8452, Appendix A).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Gf128(pub u128);
+
+impl Gf128 {
+    pub const ZERO: Gf128 = Gf128(0);
+    pub const ONE: Gf128 = Gf128(1 << 127);
+    // x^128 = x^7 + x^2 + x + 1, the coefficients of x^0 to x^7 stored in the MSBs
+    pub const IRREDUCIBLE_POLY: u128 = 0b1110_0001 << 120;
+
+    pub const fn new(value: u128) -> Self {
+        Gf128(value)
+    }
+
+    pub const fn value(self) -> u128 {
+        self.0
+    }
+
+    pub const fn add(self, rhs: Self) -> Self {
+        Gf128(self.0 ^ rhs.0)
+    }
+
+    // as described in NIST Special Publication 800-38D, Section 6.3
+    pub const fn mul(self, rhs: Self) -> Self {
+        let x = self.0;
+        let mut y = rhs.0;
+        let mut out: u128 = 0;
+        let mut bit_mask: u128 = 1 << 127;
+
+        while bit_mask > 0 {
+            // if the i-th bit of x is 1, XOR the out polynomial with the current y
+            if (x & bit_mask) > 0 {
+                out ^= y;
+            }
+
+            // in each round multiply y by x, reducing it if the coefficient of x^127 was set
+            y = Gf128(y).mul_x().0;
+            bit_mask >>= 1;
+        }
+
+        Gf128(out)
+    }
+
+    // multiplication by x is a right shift in the reflected bit order
+    pub const fn mul_x(self) -> Self {
+        let lsb_set = self.0 & 1 != 0;
+        let mut out = self.0 >> 1;
+        if lsb_set {
+            out ^= Gf128::IRREDUCIBLE_POLY;
+        }
+
+        Gf128(out)
+    }
+
+    pub const fn square(self) -> Self {
+        self.mul(self)
+    }
+
+    // square and multiply, x^0 is one for every x, including zero
+    pub const fn pow(self, mut exp: u128) -> Self {
+        let mut out = Gf128::ONE;
+        let mut base = self;
+
+        while exp > 0 {
+            if exp & 1 == 1 {
+                out = out.mul(base);
+            }
+            base = base.square();
+            exp >>= 1;
+        }
+
+        out
+    }
+
+    // the multiplicative group has 2^128 - 1 elements, so x^-1 = x^(2^128 - 2),
+    // zero has no inverse and is mapped to itself
+    pub const fn inverse(self) -> Self {
+        self.pow(u128::MAX - 1)
+    }
+
+    // POLYVAL's dot(a, b) = a * b * x^-128 of two elements converted with from_polyval_bytes,
+    // which is a * b * x in GCM's convention
+    pub const fn polyval_mul(self, rhs: Self) -> Self {
+        self.mul(rhs.mul_x())
+    }
+
+    pub const fn from_ghash_bytes(bytes: [u8; BLOCK_SIZE]) -> Self {
+        Gf128(u128::from_be_bytes(bytes))
+    }
+
+    pub const fn to_ghash_bytes(self) -> [u8; BLOCK_SIZE] {
+        self.0.to_be_bytes()
+    }
+
+    // POLYVAL stores the coefficient of x^0 in the LSB of the first byte, reversing the bytes
+    // gives GCM's order (up to the factor x that polyval_mul accounts for)
+    pub const fn from_polyval_bytes(bytes: [u8; BLOCK_SIZE]) -> Self {
+        Gf128(u128::from_le_bytes(bytes))
+    }
+
+    pub const fn to_polyval_bytes(self) -> [u8; BLOCK_SIZE] {
+        self.0.to_le_bytes()
+    }
+}
+
+impl From<Block> for Gf128 {
+    fn from(value: Block) -> Self {
+        Gf128(u128::from(value))
+    }
+}
+
+impl From<Gf128> for Block {
+    fn from(value: Gf128) -> Self {
+        Block::new(value.0)
+    }
+}
+
+impl Display for Gf128 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:032x}", self.0)
+    }
+}
+
+// =================================================================
+//                   arithmetic operations
+// =================================================================
+
+impl std::ops::Add for Gf128 {
+    type Output = Gf128;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Gf128::add(self, rhs)
+    }
+}
+
+impl std::ops::AddAssign for Gf128 {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl std::ops::Mul for Gf128 {
+    type Output = Gf128;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Gf128::mul(self, rhs)
+    }
+}
+
+impl std::ops::MulAssign for Gf128 {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
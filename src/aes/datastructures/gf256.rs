@@ -0,0 +1,156 @@
+use crate::aes::constants::{GF256_ANTILOG_TABLE, GF256_LOG_TABLE};
+use std::fmt::Display;
+
+// An element of GF(2^8) as used by AES: bit i holds the coefficient of x^i,
+// products are reduced by the AES polynomial x^8 + x^4 + x^3 + x + 1 (AES_IRREDUCIBLE_POLY).
+// Multiplication, inversion and powers use the log/antilog tables to the generator x + 1.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Gf256(pub u8);
+
+impl Gf256 {
+    pub const ZERO: Gf256 = Gf256(0);
+    pub const ONE: Gf256 = Gf256(1);
+    pub const GENERATOR: Gf256 = Gf256(0x03);
+
+    pub const fn new(value: u8) -> Self {
+        Gf256(value)
+    }
+
+    pub const fn value(self) -> u8 {
+        self.0
+    }
+
+    // addition and subtraction are both the XOR of the coefficients
+    pub const fn add(self, rhs: Self) -> Self {
+        Gf256(self.0 ^ rhs.0)
+    }
+
+    pub const fn mul(self, rhs: Self) -> Self {
+        if self.0 == 0 || rhs.0 == 0 {
+            return Gf256::ZERO;
+        }
+
+        let log =
+            GF256_LOG_TABLE[self.0 as usize] as usize + GF256_LOG_TABLE[rhs.0 as usize] as usize;
+        Gf256(GF256_ANTILOG_TABLE[log % 255])
+    }
+
+    // the multiplicative inverse, zero has none and is mapped to itself (as in the S-box)
+    pub const fn inverse(self) -> Self {
+        if self.0 == 0 {
+            return Gf256::ZERO;
+        }
+
+        Gf256(GF256_ANTILOG_TABLE[255 - GF256_LOG_TABLE[self.0 as usize] as usize])
+    }
+
+    // x^0 is one for every x, including zero
+    pub const fn pow(self, exp: u32) -> Self {
+        if exp == 0 {
+            return Gf256::ONE;
+        } else if self.0 == 0 {
+            return Gf256::ZERO;
+        }
+
+        // the multiplicative group has 255 elements
+        let log = GF256_LOG_TABLE[self.0 as usize] as usize * (exp % 255) as usize;
+        Gf256(GF256_ANTILOG_TABLE[log % 255])
+    }
+
+    // the discrete logarithm to the base GENERATOR, zero has none
+    pub const fn log(self) -> Option<u8> {
+        if self.0 == 0 {
+            return None;
+        }
+
+        Some(GF256_LOG_TABLE[self.0 as usize])
+    }
+
+    // GENERATOR raised to the given power
+    pub const fn antilog(exp: u8) -> Self {
+        Gf256(GF256_ANTILOG_TABLE[exp as usize % 255])
+    }
+}
+
+impl From<u8> for Gf256 {
+    fn from(value: u8) -> Self {
+        Gf256::new(value)
+    }
+}
+
+impl From<Gf256> for u8 {
+    fn from(value: Gf256) -> Self {
+        value.0
+    }
+}
+
+impl Display for Gf256 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:02x}", self.0)
+    }
+}
+
+// =================================================================
+//                   arithmetic operations
+// =================================================================
+
+impl std::ops::Add for Gf256 {
+    type Output = Gf256;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Gf256::add(self, rhs)
+    }
+}
+
+impl std::ops::AddAssign for Gf256 {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl std::ops::Sub for Gf256 {
+    type Output = Gf256;
+
+    // subtraction is the same operation as addition in GF(2^8)
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn sub(self, rhs: Self) -> Self::Output {
+        Gf256::add(self, rhs)
+    }
+}
+
+impl std::ops::SubAssign for Gf256 {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl std::ops::Mul for Gf256 {
+    type Output = Gf256;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Gf256::mul(self, rhs)
+    }
+}
+
+impl std::ops::MulAssign for Gf256 {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl std::ops::Div for Gf256 {
+    type Output = Gf256;
+
+    // panics on a division by zero, like the integer types
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self::Output {
+        assert!(rhs.0 != 0, "attempt to divide by zero in GF(2^8)");
+        self * rhs.inverse()
+    }
+}
+
+impl std::ops::DivAssign for Gf256 {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
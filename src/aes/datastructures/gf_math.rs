@@ -1,7 +1,10 @@
-use crate::aes::constants::{
-    AES_IRREDUCIBLE_POLY, ENCRYPTION_ROUNDS_AES128, GF256_MULT_02_LOOKUP_TABLE,
-    GF256_MULT_03_LOOKUP_TABLE, GF256_MULT_09_LOOKUP_TABLE, GF256_MULT_11_LOOKUP_TABLE,
-    GF256_MULT_13_LOOKUP_TABLE, GF256_MULT_14_LOOKUP_TABLE, GHASH_IRREDUCIBLE_POLY,
+use crate::aes::{
+    constants::{
+        AES_IRREDUCIBLE_POLY, ENCRYPTION_ROUNDS_AES128, GF256_MULT_02_LOOKUP_TABLE,
+        GF256_MULT_03_LOOKUP_TABLE, GF256_MULT_09_LOOKUP_TABLE, GF256_MULT_11_LOOKUP_TABLE,
+        GF256_MULT_13_LOOKUP_TABLE, GF256_MULT_14_LOOKUP_TABLE, S_BOX_AFFINE_CONSTANT,
+    },
+    datastructures::{gf128::Gf128, gf256::Gf256, word::Word},
 };
 
 pub fn add(a: u8, b: u8) -> u8 {
@@ -17,48 +20,119 @@ pub fn mul(a: u8, b: u8) -> u8 {
         11 => GF256_MULT_11_LOOKUP_TABLE[a as usize],
         13 => GF256_MULT_13_LOOKUP_TABLE[a as usize],
         14 => GF256_MULT_14_LOOKUP_TABLE[a as usize],
-        _ => {
-            eprintln!(
-                "had to perform a manual gf256 multiplication: {:#02x} * {:#02x}",
-                a, b
-            );
-            gf256_mult(a, b)
-        }
+        _ => Gf256::new(a).mul(Gf256::new(b)).value(),
     }
 }
 
 // as described in NIST Special Publication 800-38D, Section 6.3
-pub fn ghash_mul(x: u128, mut y: u128) -> u128 {
-    let mut out: u128 = 0;
-    let mut bit_mask: u128 = 1 << 127;
-    let lsb_mask: u128 = 1;
-
-    while bit_mask > 0 {
-        // if the i-th bit of x is 1, XOR the out polynomial with the current y
-        if (x & bit_mask) > 0 {
-            out ^= y;
-        }
+#[deprecated(note = "use Gf128::mul, the field type of GHASH and POLYVAL")]
+pub fn ghash_mul(x: u128, y: u128) -> u128 {
+    Gf128(x).mul(Gf128(y)).value()
+}
+
+pub const fn calc_lookup_table(a: u8) -> [u8; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i <= 255 {
+        table[i] = gf256_mult(a, i as u8);
+        i += 1;
+    }
 
-        let lsb_set = (y & lsb_mask) > 0;
+    table
+}
 
-        y >>= 1;
+// the powers of the generator x + 1 (0x03) run through every non-zero element,
+// the log table maps them back to their exponent (log(0) is undefined and left at 0)
+pub const fn calc_antilog_table() -> [u8; 256] {
+    let mut table = [0; 256];
+    let mut value = 1;
+    let mut i = 0;
+    while i < 256 {
+        table[i] = value;
+        value = gf256_mult(value, 0x03);
+        i += 1;
+    }
 
-        // in each round divide the y polynomial by the irreducible polynomial if the LSB is 1
-        if lsb_set {
-            y ^= GHASH_IRREDUCIBLE_POLY;
-        }
+    table
+}
 
-        bit_mask >>= 1;
+pub const fn calc_log_table(antilog_table: &[u8; 256]) -> [u8; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 255 {
+        table[antilog_table[i] as usize] = i as u8;
+        i += 1;
     }
 
-    out
+    table
 }
 
-pub const fn calc_lookup_table(a: u8) -> [u8; 256] {
+// SubBytes as defined in FIPS-197, Section 5.1.1: take the multiplicative inverse,
+// then apply the affine transformation b_i ^ b_(i+4) ^ b_(i+5) ^ b_(i+6) ^ b_(i+7) ^ c_i
+pub const fn calc_s_boxes() -> [u8; 256] {
     let mut table = [0; 256];
     let mut i = 0;
-    while i <= 255 {
-        table[i] = gf256_mult(a, i as u8);
+    while i < 256 {
+        let b = Gf256::new(i as u8).inverse().value();
+        table[i] = b
+            ^ b.rotate_left(1)
+            ^ b.rotate_left(2)
+            ^ b.rotate_left(3)
+            ^ b.rotate_left(4)
+            ^ S_BOX_AFFINE_CONSTANT;
+        i += 1;
+    }
+
+    table
+}
+
+pub const fn calc_inv_s_boxes(s_boxes: &[u8; 256]) -> [u8; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        table[s_boxes[i] as usize] = i as u8;
+        i += 1;
+    }
+
+    table
+}
+
+// combine the byte substitution with the multiplication by one column of the
+// (inverse) MixColumn matrix: table k maps a byte in row k of the state to its
+// contribution to the mixed column, the MSB holding the value for row 0
+pub const fn calc_t_tables(s_boxes: &[u8; 256], mix_col_matrix: &[Word; 4]) -> [[u32; 256]; 4] {
+    let mut tables = [[0; 256]; 4];
+    let mut k = 0;
+    while k < 4 {
+        let mut i = 0;
+        while i <= 255 {
+            let substituted = s_boxes[i];
+            let mut entry: u32 = 0;
+            let mut row = 0;
+            while row < 4 {
+                let factor = mix_col_matrix[row].0.to_be_bytes()[k];
+                entry = (entry << 8) | gf256_mult(substituted, factor) as u32;
+                row += 1;
+            }
+
+            tables[k][i] = entry;
+            i += 1;
+        }
+        k += 1;
+    }
+
+    tables
+}
+
+// the coefficients of x^128 to x^131 that are shifted out when multiplying by x^4,
+// reduced by the GHASH polynomial
+pub const fn calc_ghash_reduction_table() -> [u128; 16] {
+    let mut table = [0; 16];
+    let mut i = 0;
+    while i < 16 {
+        table[i] = Gf128::new(i as u128)
+            .mul(Gf128::new(1 << (127 - 4)))
+            .value();
         i += 1;
     }
 
@@ -0,0 +1,144 @@
+#[cfg(target_arch = "x86_64")]
+use crate::aes::backend::clmul;
+use crate::aes::{
+    backend::Backend,
+    constants::{BLOCK_SIZE, GHASH_REDUCTION_TABLE},
+    datastructures::{block::Block, gf128::Gf128},
+    zeroize::Zeroize,
+};
+
+// Multiplication by a fixed hash subkey H, with one of three methods matching the
+// block cipher backend:
+// - PCLMULQDQ, together with the AES-NI backend
+// - Shoup's method using a table of the products of H with every 4-bit polynomial, for the
+//   table based backends. The table is indexed by the secret data, like their S-boxes.
+// - a bitwise shift-and-add with masks instead of branches, for the bitsliced backend (and
+//   AES-NI without PCLMULQDQ), so the whole cipher stays free of secret-dependent lookups
+// H and the table are zeroized on drop
+#[derive(Clone)]
+pub struct GHashKey {
+    table: [u128; 16],
+    hash_subkey: u128,
+    method: Method,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Method {
+    Table,
+    Bitwise,
+    #[cfg(target_arch = "x86_64")]
+    Clmul,
+}
+
+impl GHashKey {
+    pub fn new(hash_subkey: Block, backend: Backend) -> Self {
+        let h = u128::from(hash_subkey);
+
+        let method = match backend.resolve() {
+            Backend::ColMatrix | Backend::TTable => Method::Table,
+            #[cfg(target_arch = "x86_64")]
+            Backend::AesNi if clmul::is_supported() => Method::Clmul,
+            _ => Method::Bitwise,
+        };
+
+        // entry n is H multiplied by the polynomial stored in the 4 MSBs of n << 124
+        let mut table = [0; 16];
+        if method == Method::Table {
+            for (nibble, entry) in table.iter_mut().enumerate() {
+                *entry = (Gf128::new((nibble as u128) << 124) * Gf128::new(h)).value();
+            }
+        }
+
+        Self {
+            table,
+            hash_subkey: h,
+            method,
+        }
+    }
+
+    pub fn mul(&self, x: Block) -> Block {
+        match self.method {
+            Method::Table => self.table_mul(x),
+            Method::Bitwise => self.bitwise_mul(x),
+            #[cfg(target_arch = "x86_64")]
+            Method::Clmul => Block::new(clmul::ghash_mul(u128::from(x), self.hash_subkey)),
+        }
+    }
+
+    // Horner's method from the highest to the lowest degree nibble:
+    // z = z * x^4 + nibble * H, the 4 bits shifted out of z are reduced with a lookup table
+    fn table_mul(&self, x: Block) -> Block {
+        let x = u128::from(x);
+        let mut z: u128 = 0;
+
+        for nibble_index in 0..32 {
+            let nibble = (x >> (4 * nibble_index)) & 0xf;
+            let reduction = GHASH_REDUCTION_TABLE[(z & 0xf) as usize];
+            z = (z >> 4) ^ reduction ^ self.table[nibble as usize];
+        }
+
+        Block::new(z)
+    }
+
+    // SP 800-38D, Algorithm 1: the bits of x select which multiples v = H * x^i are added.
+    // Both conditions are turned into all-zero or all-one masks, the running time and the
+    // memory accesses don't depend on x or H.
+    fn bitwise_mul(&self, x: Block) -> Block {
+        let x = u128::from(x);
+        let mut v = self.hash_subkey;
+        let mut z: u128 = 0;
+
+        for i in (0..128).rev() {
+            z ^= v & 0u128.wrapping_sub((x >> i) & 1);
+            v = (v >> 1) ^ (Gf128::IRREDUCIBLE_POLY & 0u128.wrapping_sub(v & 1));
+        }
+
+        Block::new(z)
+    }
+}
+
+impl Drop for GHashKey {
+    fn drop(&mut self) {
+        self.table.zeroize();
+        self.hash_subkey.zeroize();
+    }
+}
+
+// POLYVAL (RFC 8452) computed with GHASH, following RFC 8452 Appendix A:
+// POLYVAL(H, X_1, ..., X_n) =
+//     ByteReverse(GHASH(mulX_GHASH(ByteReverse(H)), ByteReverse(X_1), ..., ByteReverse(X_n)))
+// The state is zeroized on drop, the key zeroizes itself.
+pub struct Polyval {
+    key: GHashKey,
+    state: Block,
+}
+
+impl Polyval {
+    pub fn new(hash_key: [u8; BLOCK_SIZE], backend: Backend) -> Self {
+        let h = Gf128::from_polyval_bytes(hash_key).mul_x();
+        Self {
+            key: GHashKey::new(h.into(), backend),
+            state: Block::default(),
+        }
+    }
+
+    // the data is padded with zeros to a multiple of the block size
+    pub fn update(&mut self, data: &[u8]) {
+        for chunk in data.chunks(BLOCK_SIZE) {
+            let mut block_data = [0; BLOCK_SIZE];
+            block_data[..chunk.len()].copy_from_slice(chunk);
+            let x = Gf128::from_polyval_bytes(block_data);
+            self.state = self.key.mul(self.state ^ Block::from(x));
+        }
+    }
+
+    pub fn finalize(&self) -> [u8; BLOCK_SIZE] {
+        Gf128::from(self.state).to_polyval_bytes()
+    }
+}
+
+impl Drop for Polyval {
+    fn drop(&mut self) {
+        self.state.zeroize();
+    }
+}
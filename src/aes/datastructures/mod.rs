@@ -1,5 +1,8 @@
 pub mod block;
 pub mod colmat;
-pub mod word;
+pub mod gf128;
+pub mod gf256;
 pub mod gf_math;
+pub(crate) mod ghash;
 mod tests;
+pub mod word;
@@ -1,7 +1,17 @@
 #[cfg(test)]
 mod test {
     use crate::aes::{
-        constants::BLOCK_SIZE, datastructures::block::Block, datastructures::colmat::ColMatrix,
+        backend::Backend,
+        constants::{BLOCK_SIZE, INV_S_BOXES, S_BOXES},
+        datastructures::block::Block,
+        datastructures::{
+            colmat::ColMatrix,
+            gf128::Gf128,
+            gf256::Gf256,
+            gf_math,
+            ghash::{GHashKey, Polyval},
+        },
+        random::XorShift,
     };
 
     #[test]
@@ -218,4 +228,207 @@ mod test {
             );
         }
     }
+
+    #[test]
+    fn test_ghash_key_matches_block_mul() {
+        let mut rng = XorShift(0x853c49e6748fea9b);
+
+        for _ in 0..50 {
+            let hash_subkey = Block::new(rng.next_u128());
+            // the table, the bitwise and (if supported) the PCLMULQDQ multiplication
+            let keys = [Backend::ColMatrix, Backend::Bitsliced, Backend::AesNi]
+                .map(|backend| GHashKey::new(hash_subkey, backend));
+
+            // random operands and the edge cases of the reduction
+            let operands = (0..20)
+                .map(|_| rng.next_u128())
+                .chain([0, 1, 1 << 127, u128::MAX]);
+            for x in operands {
+                let x = Block::new(x);
+                for key in &keys {
+                    assert_eq!(key.mul(x), x * hash_subkey);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_s_boxes_match_fips_197() {
+        assert_eq!(S_BOXES, FIPS_197_S_BOXES);
+        assert_eq!(INV_S_BOXES, FIPS_197_INV_S_BOXES);
+    }
+
+    #[test]
+    fn test_gf256_arithmetic() {
+        // FIPS-197, Sections 4.1 and 4.2
+        assert_eq!(Gf256(0x57) + Gf256(0x83), Gf256(0xd4));
+        assert_eq!(Gf256(0x57) * Gf256(0x83), Gf256(0xc1));
+        assert_eq!(Gf256(0x57) * Gf256(0x13), Gf256(0xfe));
+        assert_eq!(Gf256(0x57) * Gf256(0x02), Gf256(0xae));
+        assert_eq!(Gf256(0x57).pow(2), Gf256(0x57) * Gf256(0x57));
+        assert_eq!(Gf256(0x00).pow(0), Gf256::ONE);
+        assert_eq!(Gf256(0x53).inverse(), Gf256(0xca));
+        assert_eq!(Gf256(0x00).inverse(), Gf256::ZERO);
+        assert_eq!(Gf256(0x00).log(), None);
+        assert_eq!(Gf256(0xc1).to_string(), "c1");
+
+        for a in 1..=255 {
+            let a = Gf256(a);
+            assert_eq!(a * a.inverse(), Gf256::ONE);
+            assert_eq!(a / a, Gf256::ONE);
+            assert_eq!(a - a, Gf256::ZERO);
+            assert_eq!(a.pow(255), Gf256::ONE);
+            assert_eq!(Gf256::antilog(a.log().unwrap()), a);
+            assert_eq!(a.pow(3), a * a * a);
+
+            // the table based multiplication agrees with shift-and-add
+            for b in 0..=255 {
+                let b = Gf256(b);
+                let mut expected = Gf256::ZERO;
+                let mut power = a;
+                for bit in 0..8 {
+                    if b.0 >> bit & 1 == 1 {
+                        expected += power;
+                    }
+                    // multiply by x and reduce by x^8 + x^4 + x^3 + x + 1
+                    power = Gf256(power.0 << 1 ^ if power.0 & 0x80 != 0 { 0x1b } else { 0 });
+                }
+                assert_eq!(a * b, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_gf128_arithmetic() {
+        let a = Gf128(0x66e94bd4ef8a2c3b884cfa59ca342b2e);
+        let b = Gf128(0x0388dace60b6a392f328c2b971b2fe78);
+
+        // NIST GCM test case 2: GHASH(H, {}, C) = (C * H + len(A) || len(C)) * H
+        let lengths = Gf128(128);
+        assert_eq!(
+            (b * a + lengths) * a,
+            Gf128(0xf38cbb1ad69223dcc3457ae5b6b0f885)
+        );
+
+        assert_eq!(a * Gf128::ONE, a);
+        assert_eq!(a * Gf128::ZERO, Gf128::ZERO);
+        assert_eq!(a + a, Gf128::ZERO);
+        assert_eq!(a * b, b * a);
+        // the deprecated function of gf_math computes the same product
+        #[allow(deprecated)]
+        let product = gf_math::ghash_mul(a.value(), b.value());
+        assert_eq!(Gf128(product), a * b);
+        assert_eq!(a.square(), a * a);
+        assert_eq!(a.pow(3), a * a * a);
+        assert_eq!(a.pow(0), Gf128::ONE);
+        assert_eq!(a * a.inverse(), Gf128::ONE);
+        assert_eq!(b * b.inverse(), Gf128::ONE);
+        assert_eq!(Gf128::ZERO.inverse(), Gf128::ZERO);
+        // x^128 = x^7 + x^2 + x + 1
+        let x = Gf128(1 << 126);
+        assert_eq!(x.pow(128), Gf128(Gf128::IRREDUCIBLE_POLY));
+        assert_eq!(Gf128(1).mul_x(), Gf128(Gf128::IRREDUCIBLE_POLY));
+        assert_eq!(Block::from(a) * Block::from(b), Block::from(a * b));
+        assert_eq!(a.to_string(), "66e94bd4ef8a2c3b884cfa59ca342b2e");
+    }
+
+    #[test]
+    fn test_gf128_polyval_conversion() {
+        // RFC 8452, Appendix A
+        let h: [u8; BLOCK_SIZE] = 0x25629347589242761d31f826ba4b757b_u128.to_be_bytes();
+        let x_1: [u8; BLOCK_SIZE] = 0x4f4f95668c83dfb6401762bb2d01a262_u128.to_be_bytes();
+        let x_2: [u8; BLOCK_SIZE] = 0xd1a24ddd2721d006bbe45f20d3c9f362_u128.to_be_bytes();
+
+        // mulX_GHASH(ByteReverse(H))
+        let h = Gf128::from_polyval_bytes(h);
+        assert_eq!(h.mul_x(), Gf128(0xdcbaa5dd137c188ebb21492c23c9b112));
+
+        let mut s = Gf128::ZERO;
+        for x in [x_1, x_2] {
+            s = (s + Gf128::from_polyval_bytes(x)).polyval_mul(h);
+        }
+        assert_eq!(
+            s.to_polyval_bytes(),
+            0xf7a3b47b846119fae5b7866cf5e5b77e_u128.to_be_bytes()
+        );
+
+        let bytes: [u8; BLOCK_SIZE] = std::array::from_fn(|i| i as u8 * 17);
+        assert_eq!(Gf128::from_polyval_bytes(bytes).to_polyval_bytes(), bytes);
+        assert_eq!(Gf128::from_ghash_bytes(bytes).to_ghash_bytes(), bytes);
+    }
+
+    #[test]
+    fn test_polyval() {
+        // RFC 8452, Appendix A
+        let h: [u8; BLOCK_SIZE] = 0x25629347589242761d31f826ba4b757b_u128.to_be_bytes();
+        let x: [u8; 2 * BLOCK_SIZE] = [
+            0x4f4f95668c83dfb6401762bb2d01a262_u128.to_be_bytes(),
+            0xd1a24ddd2721d006bbe45f20d3c9f362_u128.to_be_bytes(),
+        ]
+        .concat()
+        .try_into()
+        .unwrap();
+
+        for backend in [Backend::ColMatrix, Backend::Bitsliced, Backend::AesNi] {
+            let mut polyval = Polyval::new(h, backend);
+            polyval.update(&x);
+            assert_eq!(
+                polyval.finalize(),
+                0xf7a3b47b846119fae5b7866cf5e5b77e_u128.to_be_bytes()
+            );
+
+            // a partial block is padded with zeros
+            let mut padded = [0; BLOCK_SIZE];
+            padded[..5].copy_from_slice(&x[..5]);
+            let expected =
+                Gf128::from_polyval_bytes(padded).polyval_mul(Gf128::from_polyval_bytes(h));
+
+            let mut polyval = Polyval::new(h, backend);
+            polyval.update(&x[..5]);
+            assert_eq!(polyval.finalize(), expected.to_polyval_bytes());
+        }
+    }
+
+    // FIPS-197, Figure 7
+    const FIPS_197_S_BOXES: [u8; 256] = [
+        0x63, 0x7C, 0x77, 0x7B, 0xF2, 0x6B, 0x6F, 0xC5, 0x30, 0x01, 0x67, 0x2B, 0xFE, 0xD7, 0xAB,
+        0x76, 0xCA, 0x82, 0xC9, 0x7D, 0xFA, 0x59, 0x47, 0xF0, 0xAD, 0xD4, 0xA2, 0xAF, 0x9C, 0xA4,
+        0x72, 0xC0, 0xB7, 0xFD, 0x93, 0x26, 0x36, 0x3F, 0xF7, 0xCC, 0x34, 0xA5, 0xE5, 0xF1, 0x71,
+        0xD8, 0x31, 0x15, 0x04, 0xC7, 0x23, 0xC3, 0x18, 0x96, 0x05, 0x9A, 0x07, 0x12, 0x80, 0xE2,
+        0xEB, 0x27, 0xB2, 0x75, 0x09, 0x83, 0x2C, 0x1A, 0x1B, 0x6E, 0x5A, 0xA0, 0x52, 0x3B, 0xD6,
+        0xB3, 0x29, 0xE3, 0x2F, 0x84, 0x53, 0xD1, 0x00, 0xED, 0x20, 0xFC, 0xB1, 0x5B, 0x6A, 0xCB,
+        0xBE, 0x39, 0x4A, 0x4C, 0x58, 0xCF, 0xD0, 0xEF, 0xAA, 0xFB, 0x43, 0x4D, 0x33, 0x85, 0x45,
+        0xF9, 0x02, 0x7F, 0x50, 0x3C, 0x9F, 0xA8, 0x51, 0xA3, 0x40, 0x8F, 0x92, 0x9D, 0x38, 0xF5,
+        0xBC, 0xB6, 0xDA, 0x21, 0x10, 0xFF, 0xF3, 0xD2, 0xCD, 0x0C, 0x13, 0xEC, 0x5F, 0x97, 0x44,
+        0x17, 0xC4, 0xA7, 0x7E, 0x3D, 0x64, 0x5D, 0x19, 0x73, 0x60, 0x81, 0x4F, 0xDC, 0x22, 0x2A,
+        0x90, 0x88, 0x46, 0xEE, 0xB8, 0x14, 0xDE, 0x5E, 0x0B, 0xDB, 0xE0, 0x32, 0x3A, 0x0A, 0x49,
+        0x06, 0x24, 0x5C, 0xC2, 0xD3, 0xAC, 0x62, 0x91, 0x95, 0xE4, 0x79, 0xE7, 0xC8, 0x37, 0x6D,
+        0x8D, 0xD5, 0x4E, 0xA9, 0x6C, 0x56, 0xF4, 0xEA, 0x65, 0x7A, 0xAE, 0x08, 0xBA, 0x78, 0x25,
+        0x2E, 0x1C, 0xA6, 0xB4, 0xC6, 0xE8, 0xDD, 0x74, 0x1F, 0x4B, 0xBD, 0x8B, 0x8A, 0x70, 0x3E,
+        0xB5, 0x66, 0x48, 0x03, 0xF6, 0x0E, 0x61, 0x35, 0x57, 0xB9, 0x86, 0xC1, 0x1D, 0x9E, 0xE1,
+        0xF8, 0x98, 0x11, 0x69, 0xD9, 0x8E, 0x94, 0x9B, 0x1E, 0x87, 0xE9, 0xCE, 0x55, 0x28, 0xDF,
+        0x8C, 0xA1, 0x89, 0x0D, 0xBF, 0xE6, 0x42, 0x68, 0x41, 0x99, 0x2D, 0x0F, 0xB0, 0x54, 0xBB,
+        0x16,
+    ];
+    // FIPS-197, Figure 14
+    const FIPS_197_INV_S_BOXES: [u8; 256] = [
+        0x52, 0x09, 0x6a, 0xd5, 0x30, 0x36, 0xa5, 0x38, 0xbf, 0x40, 0xa3, 0x9e, 0x81, 0xf3, 0xd7,
+        0xfb, 0x7c, 0xe3, 0x39, 0x82, 0x9b, 0x2f, 0xff, 0x87, 0x34, 0x8e, 0x43, 0x44, 0xc4, 0xde,
+        0xe9, 0xcb, 0x54, 0x7b, 0x94, 0x32, 0xa6, 0xc2, 0x23, 0x3d, 0xee, 0x4c, 0x95, 0x0b, 0x42,
+        0xfa, 0xc3, 0x4e, 0x08, 0x2e, 0xa1, 0x66, 0x28, 0xd9, 0x24, 0xb2, 0x76, 0x5b, 0xa2, 0x49,
+        0x6d, 0x8b, 0xd1, 0x25, 0x72, 0xf8, 0xf6, 0x64, 0x86, 0x68, 0x98, 0x16, 0xd4, 0xa4, 0x5c,
+        0xcc, 0x5d, 0x65, 0xb6, 0x92, 0x6c, 0x70, 0x48, 0x50, 0xfd, 0xed, 0xb9, 0xda, 0x5e, 0x15,
+        0x46, 0x57, 0xa7, 0x8d, 0x9d, 0x84, 0x90, 0xd8, 0xab, 0x00, 0x8c, 0xbc, 0xd3, 0x0a, 0xf7,
+        0xe4, 0x58, 0x05, 0xb8, 0xb3, 0x45, 0x06, 0xd0, 0x2c, 0x1e, 0x8f, 0xca, 0x3f, 0x0f, 0x02,
+        0xc1, 0xaf, 0xbd, 0x03, 0x01, 0x13, 0x8a, 0x6b, 0x3a, 0x91, 0x11, 0x41, 0x4f, 0x67, 0xdc,
+        0xea, 0x97, 0xf2, 0xcf, 0xce, 0xf0, 0xb4, 0xe6, 0x73, 0x96, 0xac, 0x74, 0x22, 0xe7, 0xad,
+        0x35, 0x85, 0xe2, 0xf9, 0x37, 0xe8, 0x1c, 0x75, 0xdf, 0x6e, 0x47, 0xf1, 0x1a, 0x71, 0x1d,
+        0x29, 0xc5, 0x89, 0x6f, 0xb7, 0x62, 0x0e, 0xaa, 0x18, 0xbe, 0x1b, 0xfc, 0x56, 0x3e, 0x4b,
+        0xc6, 0xd2, 0x79, 0x20, 0x9a, 0xdb, 0xc0, 0xfe, 0x78, 0xcd, 0x5a, 0xf4, 0x1f, 0xdd, 0xa8,
+        0x33, 0x88, 0x07, 0xc7, 0x31, 0xb1, 0x12, 0x10, 0x59, 0x27, 0x80, 0xec, 0x5f, 0x60, 0x51,
+        0x7f, 0xa9, 0x19, 0xb5, 0x4a, 0x0d, 0x2d, 0xe5, 0x7a, 0x9f, 0x93, 0xc9, 0x9c, 0xef, 0xa0,
+        0xe0, 0x3b, 0x4d, 0xae, 0x2a, 0xf5, 0xb0, 0xc8, 0xeb, 0xbb, 0x3c, 0x83, 0x53, 0x99, 0x61,
+        0x17, 0x2b, 0x04, 0x7e, 0xba, 0x77, 0xd6, 0x26, 0xe1, 0x69, 0x14, 0x63, 0x55, 0x21, 0x0c,
+        0x7d,
+    ];
 }
@@ -1,6 +1,6 @@
 use crate::aes::{
-    constants::{KEY_ROUND_CONSTANTS, S_BOXES},
-    datastructures::gf_math,
+    constants::{INV_S_BOXES, KEY_ROUND_CONSTANTS, S_BOXES},
+    datastructures::{gf256::Gf256, gf_math},
 };
 use std::{
     array::TryFromSliceError,
@@ -42,8 +42,21 @@ impl Word {
         bytes.into()
     }
 
+    pub fn inv_sub_word(self) -> Self {
+        let mut bytes = self.0.to_be_bytes();
+        bytes
+            .iter_mut()
+            .for_each(|byte| *byte = INV_S_BOXES[*byte as usize]);
+
+        bytes.into()
+    }
+
     pub fn apply_rcon(mut self, round: usize) -> Self {
-        let rcon = KEY_ROUND_CONSTANTS[round - 1] as u32;
+        let rcon = match KEY_ROUND_CONSTANTS.get(round - 1) {
+            Some(rcon) => *rcon as u32,
+            // schedules with more rounds than AES-128 continue the sequence of powers of x
+            None => Gf256::new(0x02).pow(round as u32 - 1).value() as u32,
+        };
         self.0 ^= rcon << 24;
         self
     }
@@ -0,0 +1,117 @@
+mod tests;
+
+// Hex and base64 (RFC 4648, standard alphabet with padding) for keys and key files.
+// The decoders are strict, anything but the encoded data is rejected.
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const BASE64_PADDING: u8 = b'=';
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+// The outputs are allocated with their final size, a reallocation would leave a copy of the
+// (possibly secret) data behind in the freed buffer.
+pub fn hex_encode(data: &[u8]) -> String {
+    let mut encoded = String::with_capacity(2 * data.len());
+    for byte in data {
+        encoded.push(HEX_DIGITS[(byte >> 4) as usize] as char);
+        encoded.push(HEX_DIGITS[(byte & 0x0f) as usize] as char);
+    }
+
+    encoded
+}
+
+// upper and lower case digits are accepted
+pub fn hex_decode(hex: &str) -> Result<Vec<u8>, String> {
+    if !hex.len().is_multiple_of(2) {
+        return Err(format!(
+            "invalid hex length {}, expected an even number of digits",
+            hex.len()
+        ));
+    }
+
+    let mut decoded = Vec::with_capacity(hex.len() / 2);
+    for digits in hex.as_bytes().chunks(2) {
+        decoded.push(hex_digit(digits[0])? << 4 | hex_digit(digits[1])?);
+    }
+
+    Ok(decoded)
+}
+
+pub fn base64_encode(data: &[u8]) -> String {
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+
+    for chunk in data.chunks(3) {
+        let mut group = [0; 3];
+        group[..chunk.len()].copy_from_slice(chunk);
+        let bits = u32::from_be_bytes([0, group[0], group[1], group[2]]);
+
+        // a chunk of n bytes is encoded as n + 1 characters followed by padding
+        for index in 0..4 {
+            if index <= chunk.len() {
+                let sextet = (bits >> (18 - 6 * index)) & 0x3f;
+                encoded.push(BASE64_ALPHABET[sextet as usize] as char);
+            } else {
+                encoded.push(BASE64_PADDING as char);
+            }
+        }
+    }
+
+    encoded
+}
+
+pub fn base64_decode(base64: &str) -> Result<Vec<u8>, String> {
+    let base64 = base64.as_bytes();
+    if !base64.len().is_multiple_of(4) {
+        return Err(format!(
+            "invalid base64 length {}, expected a multiple of 4",
+            base64.len()
+        ));
+    }
+
+    let mut decoded = Vec::with_capacity(base64.len() / 4 * 3);
+    let group_count = base64.len() / 4;
+
+    for (group_index, group) in base64.chunks(4).enumerate() {
+        let padding = group
+            .iter()
+            .rev()
+            .take_while(|&&c| c == BASE64_PADDING)
+            .count();
+        if padding > 2 || (padding > 0 && group_index + 1 != group_count) {
+            return Err("invalid base64 padding".to_string());
+        }
+
+        let mut bits = 0u32;
+        for &character in &group[..4 - padding] {
+            bits = bits << 6 | base64_digit(character)?;
+        }
+        bits <<= 6 * padding;
+
+        // the bits that don't fit into the decoded bytes have to be 0 (RFC 4648 3.5)
+        let byte_count = 3 - padding;
+        if bits & (0xffffff >> (8 * byte_count)) != 0 {
+            return Err("invalid base64, non-zero bits in the padding".to_string());
+        }
+        decoded.extend_from_slice(&bits.to_be_bytes()[1..1 + byte_count]);
+    }
+
+    Ok(decoded)
+}
+
+fn hex_digit(digit: u8) -> Result<u8, String> {
+    match digit {
+        b'0'..=b'9' => Ok(digit - b'0'),
+        b'a'..=b'f' => Ok(digit - b'a' + 10),
+        b'A'..=b'F' => Ok(digit - b'A' + 10),
+        _ => Err(format!("invalid hex digit {:?}", digit as char)),
+    }
+}
+
+fn base64_digit(character: u8) -> Result<u32, String> {
+    BASE64_ALPHABET
+        .iter()
+        .position(|&c| c == character)
+        .map(|index| index as u32)
+        .ok_or(format!("invalid base64 character {:?}", character as char))
+}
@@ -0,0 +1,48 @@
+#[cfg(test)]
+mod test {
+    use crate::aes::encoding::{base64_decode, base64_encode, hex_decode, hex_encode};
+
+    #[test]
+    fn test_hex() {
+        let data = [0x00, 0x01, 0x7f, 0x80, 0xab, 0xff];
+        assert_eq!(hex_encode(&data), "00017f80abff");
+        assert_eq!(hex_decode("00017f80abff").unwrap(), data);
+        assert_eq!(hex_decode("00017F80ABFF").unwrap(), data);
+        assert_eq!(hex_decode("").unwrap(), []);
+
+        assert!(hex_decode("abc").is_err());
+        assert!(hex_decode("0g").is_err());
+        assert!(hex_decode(" 00").is_err());
+        assert!(hex_decode("0x00").is_err());
+    }
+
+    #[test]
+    fn test_base64() {
+        // RFC 4648 section 10
+        let test_cases = [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ];
+
+        for (data, encoded) in test_cases {
+            assert_eq!(base64_encode(data.as_bytes()), encoded);
+            assert_eq!(base64_decode(encoded).unwrap(), data.as_bytes());
+        }
+
+        let data: Vec<u8> = (0..=255).collect();
+        assert_eq!(base64_decode(&base64_encode(&data)).unwrap(), data);
+        assert_eq!(base64_encode(&[0xfb, 0xff]), "+/8=");
+
+        let invalid = [
+            "Zg=", "Zg", "Z===", "Zg==Zg==", "Zh==", "Zm9=", "Zm9v\n", "Zm-v",
+        ];
+        for encoded in invalid {
+            assert!(base64_decode(encoded).is_err(), "{}", encoded);
+        }
+    }
+}
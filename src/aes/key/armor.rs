@@ -0,0 +1,130 @@
+use crate::aes::{
+    encoding::{base64_decode, base64_encode, hex_decode, hex_encode},
+    key::{size::KeySize, Key},
+    zeroize::Zeroizing,
+};
+
+// =================================================================
+//                      armored key files
+// =================================================================
+//
+// -----BEGIN AES KEY-----
+// Key-Size: 128
+// Check-Value: c6a13b
+// AAECAwQFBgcICQoLDA0ODw==
+// -----END AES KEY-----
+//
+// The check value is the key's fingerprint, a corrupted or mistyped key (or key size)
+// is rejected when the file is loaded.
+
+const ARMOR_BEGIN: &str = "-----BEGIN AES KEY-----";
+const ARMOR_END: &str = "-----END AES KEY-----";
+const HEADER_KEY_SIZE: &str = "Key-Size";
+const HEADER_CHECK_VALUE: &str = "Check-Value";
+const ARMOR_LINE_LENGTH: usize = 64;
+
+impl Key {
+    // the hex and base64 representations only accept the AES key lengths, like try_from
+    pub fn from_hex(hex: &str) -> Result<Self, String> {
+        let key_data = Zeroizing::new(hex_decode(hex.trim())?);
+        Key::try_from(key_data.as_slice())
+    }
+
+    pub fn to_hex(&self) -> Zeroizing<String> {
+        Zeroizing::new(hex_encode(&self.expose_cipher_key()))
+    }
+
+    pub fn from_base64(base64: &str) -> Result<Self, String> {
+        let key_data = Zeroizing::new(base64_decode(base64.trim())?);
+        Key::try_from(key_data.as_slice())
+    }
+
+    pub fn to_base64(&self) -> Zeroizing<String> {
+        Zeroizing::new(base64_encode(&self.expose_cipher_key()))
+    }
+
+    // the key in the armored key file format, the Rijndael key lengths are supported
+    pub fn to_armored(&self) -> Result<Zeroizing<String>, String> {
+        let encoded = self.to_base64();
+        // room for the lines of the key and the headers, the buffer is never reallocated
+        let capacity = encoded.len() + encoded.len() / ARMOR_LINE_LENGTH + 128;
+        let mut armored = Zeroizing::new(String::with_capacity(capacity));
+        armored.push_str(ARMOR_BEGIN);
+        armored.push('\n');
+        armored.push_str(&format!(
+            "{}: {}\n{}: {}\n",
+            HEADER_KEY_SIZE,
+            self.key_size.byte_size() * 8,
+            HEADER_CHECK_VALUE,
+            self.fingerprint()
+        ));
+        // base64 only contains ASCII, the lines can be split at any byte
+        for line in encoded.as_bytes().chunks(ARMOR_LINE_LENGTH) {
+            armored.push_str(std::str::from_utf8(line).map_err(|err| err.to_string())?);
+            armored.push('\n');
+        }
+        armored.push_str(ARMOR_END);
+        armored.push('\n');
+
+        Ok(armored)
+    }
+
+    pub fn from_armored(armored: &str) -> Result<Self, String> {
+        let mut lines = armored.trim().lines().map(str::trim);
+        if lines.next() != Some(ARMOR_BEGIN) || lines.next_back() != Some(ARMOR_END) {
+            return Err(format!(
+                "invalid key file, expected the key between {} and {}",
+                ARMOR_BEGIN, ARMOR_END
+            ));
+        }
+
+        let mut key_size = None;
+        let mut check_value = None;
+        // the encoded key can't be longer than the file, so the buffer is never reallocated
+        let mut encoded = Zeroizing::new(String::with_capacity(armored.len()));
+
+        for line in lines {
+            match line.split_once(':') {
+                Some((HEADER_KEY_SIZE, value)) => key_size = Some(parse_key_size(value.trim())?),
+                Some((HEADER_CHECK_VALUE, value)) => check_value = Some(value.trim()),
+                Some((name, _)) => return Err(format!("unknown key file header {:?}", name)),
+                None => encoded.push_str(line),
+            }
+        }
+
+        let key_size = key_size.ok_or(format!("the key file has no {} header", HEADER_KEY_SIZE))?;
+        let check_value =
+            check_value.ok_or(format!("the key file has no {} header", HEADER_CHECK_VALUE))?;
+
+        let key_data = Zeroizing::new(base64_decode(&encoded)?);
+        if key_data.len() != key_size.byte_size() {
+            return Err(format!(
+                "the key is {} bits long, the key file specifies {} bits",
+                key_data.len() * 8,
+                key_size.byte_size() * 8
+            ));
+        }
+
+        let key = Key::new(&key_data, key_size)?;
+        if !key.fingerprint().eq_ignore_ascii_case(check_value) {
+            return Err(format!(
+                "key check value mismatch, expected {} but the key has {}",
+                check_value,
+                key.fingerprint()
+            ));
+        }
+
+        Ok(key)
+    }
+}
+
+fn parse_key_size(bits: &str) -> Result<KeySize, String> {
+    let bits: usize = bits
+        .parse()
+        .map_err(|_| format!("invalid key size {:?}", bits))?;
+    if !bits.is_multiple_of(8) {
+        return Err(format!("invalid key size {} bits", bits));
+    }
+
+    KeySize::from_byte_size(bits / 8, true)
+}
@@ -1,10 +1,15 @@
+#[cfg(target_arch = "x86_64")]
+use crate::aes::backend::aesni;
 use crate::aes::{
     constants::{ROUND_KEY_SIZE, WORD_SIZE},
-    datastructures::{block::Block, word::Word},
+    datastructures::{block::Block, colmat::ColMatrix, word::Word},
     key::size::KeySize,
+    zeroize::Zeroizing,
 };
 
-pub fn expand_key(key_data: &[u8], key_size: KeySize) -> Result<Vec<Block>, String> {
+// rounds + 1 round keys, usually key_size.encryption_rounds() rounds. Reduced-round
+// variants use a shorter schedule, schedules longer than the standard one are possible as well
+pub fn expand_key(key_data: &[u8], key_size: KeySize, rounds: usize) -> Result<Vec<Block>, String> {
     if key_data.len() != key_size.byte_size() {
         return Err(format!(
             "invalid key size: expected {} bytes, got {}",
@@ -13,22 +18,46 @@ pub fn expand_key(key_data: &[u8], key_size: KeySize) -> Result<Vec<Block>, Stri
         ));
     }
 
-    let expanded_word_size = key_size.expanded_word_size();
+    let expanded_word_size = (rounds + 1) * (ROUND_KEY_SIZE / WORD_SIZE);
 
-    let mut expanded_data: Vec<Word> = Vec::with_capacity(expanded_word_size);
+    // the expanded data starts with the cipher key, it's wiped when the round keys have been
+    // made from it
+    let mut expanded_data: Zeroizing<Vec<Word>> =
+        Zeroizing::new(Vec::with_capacity(expanded_word_size));
     // the first round key is the key itself
     for word in key_data.chunks(WORD_SIZE) {
         expanded_data.push(word.try_into()?);
     }
 
     while expanded_data.len() < expanded_word_size {
-        expanded_data.push(generate_next_word(&expanded_data, key_size)?);
+        let next_word = generate_next_word(&expanded_data, key_size)?;
+        expanded_data.push(next_word);
     }
 
-    make_round_keys(expanded_data)
+    make_round_keys(&expanded_data)
 }
 
-fn generate_next_word(round_data: &Vec<Word>, key_size: KeySize) -> Result<Word, String> {
+// the decryption round keys of the equivalent inverse cipher (FIPS-197, Section 5.3.5):
+// InvMixColumns is applied to every round key except the first and the last one
+pub fn invert_round_keys(round_keys: &[Block]) -> Vec<Block> {
+    let rounds = round_keys.len() - 1;
+
+    round_keys
+        .iter()
+        .enumerate()
+        .map(|(round, round_key)| {
+            if round == 0 || round == rounds {
+                return *round_key;
+            }
+
+            let mut colmat = ColMatrix::new(*round_key);
+            colmat.inv_mix_columns();
+            colmat.block()
+        })
+        .collect()
+}
+
+fn generate_next_word(round_data: &[Word], key_size: KeySize) -> Result<Word, String> {
     let index = round_data.len();
     let round_words = key_size.expansion_round_word_width();
     let round = round_data.len() / round_words;
@@ -41,39 +70,106 @@ fn generate_next_word(round_data: &Vec<Word>, key_size: KeySize) -> Result<Word,
     // always calculcate the next word from the word with the same index in the previous round
     // and the previously generated word
     let previous_round_word = round_data[(round - 1) * round_words + round_index];
-
-    // for the first word of each expansion round the previous word is always rotated,
-    // the bytes are substituted and the round constant is applied.
-    // additionally, if a 256-bit key is used, then for the fifth word of each expansion round
-    // the previous words' bytes are substituted.
-    let previous_word = if round_index == 0 {
-        round_data[index - 1]
-            .rot_word()
-            .sub_word()
-            .apply_rcon(round)
-    } else if round_index == 4 && key_size == KeySize::AES256 {
-        round_data[index - 1].sub_word()
-    } else {
-        round_data[index - 1]
-    };
+    let previous_word = transform_previous_word(round_data[index - 1], index, key_size);
 
     Ok(previous_round_word ^ previous_word)
 }
 
-fn make_round_keys(round_data: Vec<Word>) -> Result<Vec<Block>, String> {
-    let mut round_keys = Vec::new();
+// Recover the cipher key from consecutive round keys, the first of which is the round key of
+// the given round. Every word of the schedule is the XOR of the word Nk positions earlier and
+// the transformed previous word, so the schedule can be run backwards from any Nk
+// consecutive words.
+pub fn invert_key_schedule(
+    round: usize,
+    round_keys: &[Block],
+    key_size: KeySize,
+) -> Result<Vec<u8>, String> {
+    let key_words = key_size.expansion_round_word_width();
+    let round_key_words = ROUND_KEY_SIZE / WORD_SIZE;
+    let required_round_keys = key_words.div_ceil(round_key_words);
+
+    if round_keys.len() < required_round_keys {
+        return Err(format!(
+            "can't invert the key schedule of a {}-bit key from {} round key(s), {} consecutive round keys are required",
+            key_size.byte_size() * 8,
+            round_keys.len(),
+            required_round_keys
+        ));
+    }
 
-    if round_data.len() % (ROUND_KEY_SIZE / WORD_SIZE) != 0 {
+    // the schedule of the key size has Nr + 1 round keys
+    let last_round = round + required_round_keys - 1;
+    if last_round > key_size.encryption_rounds() {
+        return Err(format!(
+            "invalid round {}, the schedule of a {}-bit key ends with round key {}, {} consecutive round keys are required",
+            round,
+            key_size.byte_size() * 8,
+            key_size.encryption_rounds(),
+            required_round_keys
+        ));
+    }
+
+    // the known words of the schedule, starting at the first word of the given round
+    let first_index = round * round_key_words;
+    let mut words: Vec<Word> = round_keys[..required_round_keys]
+        .iter()
+        .flat_map(|round_key| <[Word; 4]>::from(*round_key))
+        .take(key_words)
+        .collect();
+
+    // word i - Nk = word i ^ the transformed word i - 1, slide the window of Nk known words
+    // back one word at a time until it starts at the cipher key
+    for start in (1..=first_index).rev() {
+        let index = start + key_words - 1;
+        let previous_word = transform_previous_word(words[key_words - 2], index, key_size);
+        let word = words[key_words - 1] ^ previous_word;
+        words.pop();
+        words.insert(0, word);
+    }
+
+    Ok(words.into_iter().flat_map(|word| word.bytes()).collect())
+}
+
+// for the first word of each expansion round the previous word is always rotated,
+// the bytes are substituted and the round constant is applied.
+// additionally, for keys longer than 192 bits (Rijndael's 224-bit keys and AES-256),
+// the previous words' bytes are substituted for the fifth word of each expansion round.
+fn transform_previous_word(previous_word: Word, index: usize, key_size: KeySize) -> Word {
+    let round_words = key_size.expansion_round_word_width();
+    let round = index / round_words;
+    let round_index = index % round_words;
+
+    if round_index == 0 {
+        sub_word(previous_word.rot_word()).apply_rcon(round)
+    } else if round_index == 4 && round_words > 6 {
+        sub_word(previous_word)
+    } else {
+        previous_word
+    }
+}
+
+// use the AESKEYGENASSIST instruction for the byte substitution if the CPU supports it
+fn sub_word(word: Word) -> Word {
+    #[cfg(target_arch = "x86_64")]
+    if aesni::is_supported() {
+        return aesni::sub_word(word);
+    }
+
+    word.sub_word()
+}
+
+// the round keys are collected with their final size, a reallocation would leave a copy of
+// the cipher key (round key 0) in the freed buffer
+fn make_round_keys(round_data: &[Word]) -> Result<Vec<Block>, String> {
+    if !round_data.len().is_multiple_of(ROUND_KEY_SIZE / WORD_SIZE) {
         return Err(format!(
             "round key data must be a multiple of 4 words, got {} words",
             round_data.len()
         ));
     }
 
-    round_data.chunks(4).for_each(|chunk| {
-        let key_data: [Word; 4] = chunk.try_into().unwrap();
-        round_keys.push(key_data.into());
-    });
-
-    Ok(round_keys)
+    Ok(round_data
+        .chunks_exact(ROUND_KEY_SIZE / WORD_SIZE)
+        .map(|chunk| <[Word; 4]>::try_from(chunk).unwrap().into())
+        .collect())
 }
@@ -1,33 +1,207 @@
+mod armor;
 mod expansion;
 pub mod size;
 mod tests;
 
+#[cfg(target_arch = "x86_64")]
+use super::backend::aesni;
 use super::{
-    constants::{KEY_SIZE_AES128, KEY_SIZE_AES192, KEY_SIZE_AES256},
+    backend::{bitslice, Backend},
+    constants::{
+        BLOCK_SIZE, KEY_CHECK_VALUE_SIZE, KEY_SIZE_AES128, KEY_SIZE_AES192, KEY_SIZE_AES256,
+    },
     datastructures::block::Block,
-    key::{expansion::expand_key, size::KeySize},
+    encoding::hex_encode,
+    key::{
+        expansion::{expand_key, invert_key_schedule, invert_round_keys},
+        size::KeySize,
+    },
+    modes::{common::equivalent_decrypt_block, gcm::generate_hash_subkey},
+    random::fill_random,
+    zeroize::{Zeroize, Zeroizing},
+};
+use std::{
+    fmt::{Debug, Display},
+    ops::Index,
 };
-use std::{fmt::Display, ops::Index};
 
-#[derive(Clone, Default)]
+// the key schedule is zeroized when the key is dropped, Display and Debug only show
+// a fingerprint of the key
+#[derive(Clone)]
 pub struct Key {
     pub key_size: KeySize,
+    // key_size.encryption_rounds(), unless the key was created for a reduced-round cipher
+    rounds: usize,
     round_keys: Vec<Block>,
+    // round keys of the equivalent inverse cipher, see get_dec_round_key
+    dec_round_keys: Vec<Block>,
+    backend: Backend,
+    // the broadcast round keys of the bitsliced backend, computed when it is selected
+    bitsliced_schedule: Option<bitslice::KeySchedule>,
+    #[cfg(target_arch = "x86_64")]
+    aesni_schedule: Option<aesni::KeySchedule>,
 }
 
 impl Key {
+    fn new(key_data: &[u8], key_size: KeySize) -> Result<Self, String> {
+        Key::with_rounds(key_data, key_size, key_size.encryption_rounds())
+    }
+
+    // a key with a shortened (or lengthened) schedule of rounds + 1 round keys, every
+    // backend runs the given number of rounds with it
+    pub(crate) fn with_rounds(
+        key_data: &[u8],
+        key_size: KeySize,
+        rounds: usize,
+    ) -> Result<Self, String> {
+        let round_keys = expand_key(key_data, key_size, rounds)?;
+
+        let key = Key {
+            key_size,
+            rounds,
+            #[cfg(target_arch = "x86_64")]
+            aesni_schedule: aesni::is_supported().then(|| aesni::KeySchedule::new(&round_keys)),
+            bitsliced_schedule: None,
+            dec_round_keys: invert_round_keys(&round_keys),
+            round_keys,
+            backend: Backend::default(),
+        };
+        Ok(key.with_backend(Backend::default()))
+    }
+
+    // opt-in to the non-standard Rijndael key lengths of 160 and 224 bits (Nk = 5 and 7),
+    // the 128, 192 and 256-bit keys are accepted as well. The block size stays 128 bits.
+    pub fn new_rijndael(key_data: &[u8]) -> Result<Self, String> {
+        Key::new(key_data, KeySize::from_byte_size(key_data.len(), true)?)
+    }
+
+    // a new random key from the operating system RNG
+    pub fn generate(key_size: KeySize) -> Result<Self, String> {
+        let mut key_data = Zeroizing::new(vec![0; key_size.byte_size()]);
+        fill_random(&mut key_data)?;
+        Key::new(&key_data, key_size)
+    }
+
+    pub fn rounds(&self) -> usize {
+        self.rounds
+    }
+
+    // every round key, for tests and tracing. Handle with care, the cipher key can be
+    // recovered from them (see recover_cipher_key)
+    pub fn expose_round_keys(&self) -> &[Block] {
+        &self.round_keys
+    }
+
+    pub fn expose_dec_round_keys(&self) -> &[Block] {
+        &self.dec_round_keys
+    }
+
+    // The first 3 bytes of the encryption of the zero block (the key check value),
+    // identifies the key without revealing it. The full block is GCM's hash subkey H
+    // and must not be published.
+    pub fn fingerprint(&self) -> String {
+        let check_value = generate_hash_subkey(self);
+        hex_encode(&check_value.bytes()[..KEY_CHECK_VALUE_SIZE])
+    }
+
+    // the cipher key the schedule was expanded from, i.e. its first Nk words. The buffer has
+    // its final size from the start, it's never reallocated
+    pub fn expose_cipher_key(&self) -> Zeroizing<Vec<u8>> {
+        let mut key_data = Zeroizing::new(vec![0; self.key_size.byte_size()]);
+        for (chunk, round_key) in key_data.chunks_mut(BLOCK_SIZE).zip(&self.round_keys) {
+            chunk.copy_from_slice(&round_key.bytes()[..chunk.len()]);
+        }
+        key_data
+    }
+
     pub fn get_round_key(&self, round: usize) -> Option<&Block> {
         self.round_keys.get(round)
     }
+
+    // the round keys with InvMixColumns applied to rounds 1 to Nr - 1, so decryption can
+    // use the same round structure as encryption (FIPS-197 "equivalent inverse cipher")
+    pub fn get_dec_round_key(&self, round: usize) -> Option<&Block> {
+        self.dec_round_keys.get(round)
+    }
+
+    // decrypt a single block with the equivalent inverse cipher, which runs the inverse
+    // steps in the order of the cipher with the round keys above. Same output as
+    // BlockCipher::decrypt_block, but always computed on a ColMatrix
+    pub fn equivalent_decrypt_block(&self, block: Block) -> Block {
+        equivalent_decrypt_block(block, self)
+    }
+
+    pub fn backend(&self) -> Backend {
+        self.backend
+    }
+
+    // set_backend broadcasts the round keys when the bitsliced backend is selected,
+    // must only be called then
+    pub(crate) fn bitsliced_schedule(&self) -> &bitslice::KeySchedule {
+        self.bitsliced_schedule
+            .as_ref()
+            .expect("the bitsliced schedule is computed when the backend is selected")
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    pub(crate) fn aesni_schedule(&self) -> Option<&aesni::KeySchedule> {
+        self.aesni_schedule.as_ref()
+    }
+
+    // select the implementation used to encrypt/decrypt blocks with this key,
+    // Auto is resolved right away so backend() reports the implementation in use.
+    // The bitsliced round keys are broadcast once here instead of for every call
+    pub fn with_backend(mut self, backend: Backend) -> Self {
+        self.set_backend(backend);
+        self
+    }
+
+    pub fn set_backend(&mut self, backend: Backend) {
+        self.backend = backend.resolve();
+        if self.backend == Backend::Bitsliced && self.bitsliced_schedule.is_none() {
+            self.bitsliced_schedule = Some(bitslice::KeySchedule::new(&self.round_keys));
+        }
+    }
+}
+
+// Recover the cipher key from the round key of the given round (round_keys[0]) and the
+// round keys following it. AES-128 needs a single round key, every longer key needs two
+// consecutive ones, e.g. key.get_round_key(i) and key.get_round_key(i + 1).
+pub fn recover_cipher_key(
+    round: usize,
+    round_keys: &[Block],
+    key_size: KeySize,
+) -> Result<Vec<u8>, String> {
+    invert_key_schedule(round, round_keys, key_size)
 }
 
 impl Display for Key {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for (i, key) in self.round_keys.iter().enumerate() {
-            writeln!(f, "{:02}: {}", i, key)?;
-        }
+        write!(
+            f,
+            "{}-bit key (fingerprint {})",
+            self.key_size.byte_size() * 8,
+            self.fingerprint()
+        )
+    }
+}
+
+impl Debug for Key {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Key")
+            .field("key_size", &self.key_size)
+            .field("rounds", &self.rounds)
+            .field("backend", &self.backend)
+            .field("fingerprint", &self.fingerprint())
+            .finish_non_exhaustive()
+    }
+}
 
-        Ok(())
+// the AES-NI and bitsliced schedules zeroize themselves
+impl Drop for Key {
+    fn drop(&mut self) {
+        self.round_keys.zeroize();
+        self.dec_round_keys.zeroize();
     }
 }
 
@@ -43,43 +217,24 @@ impl TryFrom<&[u8]> for Key {
     type Error = String;
 
     fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
-        let key_size = match value.len() {
-            KEY_SIZE_AES128 => KeySize::AES128,
-            KEY_SIZE_AES192 => KeySize::AES192,
-            KEY_SIZE_AES256 => KeySize::AES256,
-            _ => return Err(format!("Invalid key size: {}", value.len())),
-        };
-
-        Ok(Key {
-            key_size,
-            round_keys: expand_key(value, key_size)?,
-        })
+        Key::new(value, KeySize::from_byte_size(value.len(), false)?)
     }
 }
 
 impl From<[u8; KEY_SIZE_AES128]> for Key {
     fn from(value: [u8; KEY_SIZE_AES128]) -> Self {
-        Self {
-            key_size: KeySize::AES128,
-            round_keys: expand_key(&value, KeySize::AES128).unwrap(),
-        }
+        Key::new(&value, KeySize::AES128).unwrap()
     }
 }
 
 impl From<[u8; KEY_SIZE_AES192]> for Key {
     fn from(value: [u8; KEY_SIZE_AES192]) -> Self {
-        Self {
-            key_size: KeySize::AES192,
-            round_keys: expand_key(&value, KeySize::AES192).unwrap(),
-        }
+        Key::new(&value, KeySize::AES192).unwrap()
     }
 }
 
 impl From<[u8; KEY_SIZE_AES256]> for Key {
     fn from(value: [u8; KEY_SIZE_AES256]) -> Self {
-        Self {
-            key_size: KeySize::AES256,
-            round_keys: expand_key(&value, KeySize::AES256).unwrap(),
-        }
+        Key::new(&value, KeySize::AES256).unwrap()
     }
 }
@@ -1,14 +1,20 @@
 use crate::aes::constants::{
     ENCRYPTION_ROUNDS_AES128, ENCRYPTION_ROUNDS_AES192, ENCRYPTION_ROUNDS_AES256,
-    EXPANDED_KEY_SIZE_AES128, EXPANDED_KEY_SIZE_AES192, EXPANDED_KEY_SIZE_AES256, KEY_SIZE_AES128,
-    KEY_SIZE_AES192, KEY_SIZE_AES256, ROUND_WORDS_AES128, ROUND_WORDS_AES192, ROUND_WORDS_AES256,
-    WORD_SIZE,
+    ENCRYPTION_ROUNDS_RIJNDAEL160, ENCRYPTION_ROUNDS_RIJNDAEL224, EXPANDED_KEY_SIZE_AES128,
+    EXPANDED_KEY_SIZE_AES192, EXPANDED_KEY_SIZE_AES256, EXPANDED_KEY_SIZE_RIJNDAEL160,
+    EXPANDED_KEY_SIZE_RIJNDAEL224, KEY_SIZE_AES128, KEY_SIZE_AES192, KEY_SIZE_AES256,
+    KEY_SIZE_RIJNDAEL160, KEY_SIZE_RIJNDAEL224, ROUND_WORDS_AES128, ROUND_WORDS_AES192,
+    ROUND_WORDS_AES256, ROUND_WORDS_RIJNDAEL160, ROUND_WORDS_RIJNDAEL224, WORD_SIZE,
 };
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+// Rijndael160 and Rijndael224 are not part of AES, see is_standard
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum KeySize {
+    #[default]
     AES128,
+    Rijndael160,
     AES192,
+    Rijndael224,
     AES256,
 }
 
@@ -16,7 +22,9 @@ impl KeySize {
     pub fn byte_size(&self) -> usize {
         match self {
             KeySize::AES128 => KEY_SIZE_AES128,
+            KeySize::Rijndael160 => KEY_SIZE_RIJNDAEL160,
             KeySize::AES192 => KEY_SIZE_AES192,
+            KeySize::Rijndael224 => KEY_SIZE_RIJNDAEL224,
             KeySize::AES256 => KEY_SIZE_AES256,
         }
     }
@@ -24,7 +32,9 @@ impl KeySize {
     pub fn expanded_word_size(&self) -> usize {
         let byte_size = match self {
             KeySize::AES128 => EXPANDED_KEY_SIZE_AES128,
+            KeySize::Rijndael160 => EXPANDED_KEY_SIZE_RIJNDAEL160,
             KeySize::AES192 => EXPANDED_KEY_SIZE_AES192,
+            KeySize::Rijndael224 => EXPANDED_KEY_SIZE_RIJNDAEL224,
             KeySize::AES256 => EXPANDED_KEY_SIZE_AES256,
         };
         byte_size / WORD_SIZE
@@ -33,7 +43,9 @@ impl KeySize {
     pub fn expansion_round_word_width(&self) -> usize {
         match self {
             KeySize::AES128 => ROUND_WORDS_AES128,
+            KeySize::Rijndael160 => ROUND_WORDS_RIJNDAEL160,
             KeySize::AES192 => ROUND_WORDS_AES192,
+            KeySize::Rijndael224 => ROUND_WORDS_RIJNDAEL224,
             KeySize::AES256 => ROUND_WORDS_AES256,
         }
     }
@@ -41,14 +53,38 @@ impl KeySize {
     pub fn encryption_rounds(&self) -> usize {
         match self {
             KeySize::AES128 => ENCRYPTION_ROUNDS_AES128,
+            KeySize::Rijndael160 => ENCRYPTION_ROUNDS_RIJNDAEL160,
             KeySize::AES192 => ENCRYPTION_ROUNDS_AES192,
+            KeySize::Rijndael224 => ENCRYPTION_ROUNDS_RIJNDAEL224,
             KeySize::AES256 => ENCRYPTION_ROUNDS_AES256,
         }
     }
-}
 
-impl Default for KeySize {
-    fn default() -> Self {
-        Self::AES128
+    // whether FIPS-197 defines the key length, the others are only valid for Rijndael
+    pub fn is_standard(&self) -> bool {
+        matches!(self, KeySize::AES128 | KeySize::AES192 | KeySize::AES256)
+    }
+
+    // the key size for a key of the given length, non-standard lengths are only accepted
+    // if allow_non_standard is set
+    pub fn from_byte_size(byte_size: usize, allow_non_standard: bool) -> Result<Self, String> {
+        let key_size = match byte_size {
+            KEY_SIZE_AES128 => KeySize::AES128,
+            KEY_SIZE_RIJNDAEL160 => KeySize::Rijndael160,
+            KEY_SIZE_AES192 => KeySize::AES192,
+            KEY_SIZE_RIJNDAEL224 => KeySize::Rijndael224,
+            KEY_SIZE_AES256 => KeySize::AES256,
+            _ => return Err(format!("Invalid key size: {}", byte_size)),
+        };
+
+        if !key_size.is_standard() && !allow_non_standard {
+            return Err(format!(
+                "Invalid key size: {}, {}-bit keys are only supported by Rijndael (see Key::new_rijndael)",
+                byte_size,
+                byte_size * 8
+            ));
+        }
+
+        Ok(key_size)
     }
 }
@@ -1,6 +1,21 @@
 #[cfg(test)]
 mod test {
-    use crate::aes::key::Key;
+    use crate::aes::{
+        backend::Backend,
+        cipher::BlockCipher,
+        constants::BLOCK_SIZE,
+        datastructures::block::Block,
+        encoding::hex_decode,
+        key::{
+            expansion::{expand_key, invert_round_keys},
+            recover_cipher_key,
+            size::KeySize,
+            Key,
+        },
+        load_key_file,
+        rijndael::Rijndael,
+        save_key_file,
+    };
 
     #[test]
     fn test_key_expansion() {
@@ -128,4 +143,297 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn test_rijndael_key_sizes() {
+        // the Rijndael reference vectors for a 128-bit block, the key and the plaintext
+        // are truncated to the key and block length
+        let key_data =
+            hex_decode("2b7e151628aed2a6abf7158809cf4f3c762e7160f38b4da56a784d9045190cfe").unwrap();
+        let plaintext = Block::new(0x3243f6a8885a308d313198a2e0370734);
+        let test_cases = [
+            (KeySize::Rijndael160, 11, 0x231d844639b31b412211cfe93712b880),
+            (KeySize::Rijndael224, 13, 0x8faa8fe4dee9eb17caa4797502fc9d3f),
+        ];
+
+        for (key_size, rounds, expected_ciphertext) in test_cases {
+            let key_data = &key_data[..key_size.byte_size()];
+            assert!(!key_size.is_standard());
+            assert_eq!(key_size.encryption_rounds(), rounds);
+
+            // the non-standard lengths have to be requested explicitly
+            assert!(Key::try_from(key_data).is_err());
+            let key = Key::new_rijndael(key_data).unwrap();
+            assert!(key_size == key.key_size);
+            assert!(key.get_round_key(rounds).is_some());
+            assert!(key.get_round_key(rounds + 1).is_none());
+
+            // the key schedule matches the generalized Rijndael implementation
+            let rijndael = Rijndael::new(key_data, 16).unwrap();
+            for round in 0..=rounds {
+                let round_key: Vec<u8> = rijndael
+                    .round_key(round)
+                    .unwrap()
+                    .iter()
+                    .flat_map(|word| word.bytes())
+                    .collect();
+                assert_eq!(round_key, key[round].bytes());
+            }
+
+            for backend in Backend::ALL {
+                let key = key.clone().with_backend(backend);
+                let ciphertext = key.encrypt_block(plaintext);
+                assert_eq!(ciphertext, Block::new(expected_ciphertext));
+                assert_eq!(key.decrypt_block(ciphertext), plaintext);
+            }
+        }
+
+        // the standard lengths are accepted by both constructors
+        for key_size in [KeySize::AES128, KeySize::AES192, KeySize::AES256] {
+            let key_data = &key_data[..key_size.byte_size()];
+            assert!(key_size.is_standard());
+            assert_eq!(
+                Key::new_rijndael(key_data).unwrap()[0],
+                Key::try_from(key_data).unwrap()[0]
+            );
+        }
+        assert!(Key::new_rijndael(&key_data[..18]).is_err());
+    }
+
+    #[test]
+    fn test_recover_cipher_key() {
+        let key_data: Vec<u8> = (0..32).map(|i| i * 7 + 3).collect();
+        let key_sizes = [
+            (KeySize::AES128, 1),
+            (KeySize::Rijndael160, 2),
+            (KeySize::AES192, 2),
+            (KeySize::Rijndael224, 2),
+            (KeySize::AES256, 2),
+        ];
+
+        for (key_size, required_round_keys) in key_sizes {
+            let key_data = &key_data[..key_size.byte_size()];
+            let key = Key::new_rijndael(key_data).unwrap();
+            let rounds = key.rounds();
+
+            // every window of consecutive round keys leads back to the same cipher key
+            for round in 0..=rounds + 1 - required_round_keys {
+                let round_keys: Vec<Block> = (round..round + required_round_keys)
+                    .map(|round| key[round])
+                    .collect();
+                let recovered = recover_cipher_key(round, &round_keys, key_size).unwrap();
+                assert_eq!(recovered, key_data, "round {}", round);
+
+                // and expanding the recovered key reproduces the schedule
+                let recovered_key = Key::new_rijndael(&recovered).unwrap();
+                for round in 0..=rounds {
+                    assert_eq!(recovered_key[round], key[round]);
+                }
+            }
+
+            if required_round_keys > 1 {
+                assert!(recover_cipher_key(3, &[key[3]], key_size).is_err());
+            }
+
+            // the round keys have to be part of the schedule
+            let round_keys = vec![key[0]; required_round_keys];
+            let first_invalid_round = rounds + 2 - required_round_keys;
+            assert!(recover_cipher_key(first_invalid_round, &round_keys, key_size).is_err());
+            assert!(recover_cipher_key(1000, &round_keys, key_size).is_err());
+        }
+
+        // the last round key of the FIPS-197 Appendix A.1 expansion
+        let recovered = recover_cipher_key(
+            10,
+            &[Block::new(0xd014f9a8c9ee2589e13f0cc8b6630ca6)],
+            KeySize::AES128,
+        )
+        .unwrap();
+        assert_eq!(
+            recovered,
+            hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_key_redaction() {
+        let key = Key::from([
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d,
+            0x0e, 0x0f,
+        ]);
+
+        // the first bytes of the encryption of the zero block
+        assert_eq!(key.fingerprint(), "c6a13b");
+        assert_eq!(key.to_string(), "128-bit key (fingerprint c6a13b)");
+
+        // no round key (not even split into words) shows up in the output
+        let debug = format!("{:?}", key);
+        assert!(debug.contains("c6a13b"));
+        for round_key in key.expose_round_keys() {
+            let hex = format!("{:032x}", u128::from(*round_key));
+            for word in round_key.to_string().split(' ') {
+                assert!(!debug.contains(word) && !key.to_string().contains(word));
+            }
+            assert!(!debug.contains(&hex));
+        }
+
+        // the round keys remain accessible explicitly
+        assert_eq!(key.expose_round_keys().len(), 11);
+        assert_eq!(key.expose_round_keys()[10], key[10]);
+        assert_eq!(
+            key.expose_dec_round_keys()[5],
+            *key.get_dec_round_key(5).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_generate_key() {
+        for key_size in [
+            KeySize::AES128,
+            KeySize::Rijndael160,
+            KeySize::AES192,
+            KeySize::Rijndael224,
+            KeySize::AES256,
+        ] {
+            let first = Key::generate(key_size).unwrap();
+            let second = Key::generate(key_size).unwrap();
+            assert_eq!(first.key_size, key_size);
+            assert_eq!(first.rounds(), key_size.encryption_rounds());
+            assert_ne!(first.expose_round_keys(), second.expose_round_keys());
+
+            // the generated key round-trips through its schedule
+            let block = Block::new(0x00112233445566778899aabbccddeeff);
+            assert_eq!(first.decrypt_block(first.encrypt_block(block)), block);
+        }
+    }
+
+    #[test]
+    fn test_key_encoding() {
+        let key = Key::from_hex("000102030405060708090a0b0c0d0e0f").unwrap();
+        assert_eq!(key.fingerprint(), "c6a13b");
+        assert_eq!(*key.to_hex(), "000102030405060708090a0b0c0d0e0f");
+        assert_eq!(*key.to_base64(), "AAECAwQFBgcICQoLDA0ODw==");
+        assert_eq!(
+            Key::from_base64("AAECAwQFBgcICQoLDA0ODw==\n")
+                .unwrap()
+                .expose_round_keys(),
+            key.expose_round_keys()
+        );
+
+        let armored = key.to_armored().unwrap();
+        assert_eq!(
+            *armored,
+            "-----BEGIN AES KEY-----\n\
+             Key-Size: 128\n\
+             Check-Value: c6a13b\n\
+             AAECAwQFBgcICQoLDA0ODw==\n\
+             -----END AES KEY-----\n"
+        );
+
+        // the hex and base64 forms are restricted to AES, the key file isn't
+        assert!(Key::from_hex(&"00".repeat(20)).is_err());
+        for key_size in [KeySize::Rijndael160, KeySize::Rijndael224, KeySize::AES256] {
+            let key = Key::generate(key_size).unwrap();
+            let loaded = Key::from_armored(&key.to_armored().unwrap()).unwrap();
+            assert_eq!(loaded.key_size, key_size);
+            assert_eq!(loaded.expose_round_keys(), key.expose_round_keys());
+            assert_eq!(Key::from_hex(&key.to_hex()).is_ok(), key_size.is_standard());
+
+            // the buffers holding the key are allocated once, with their final size
+            let cipher_key = key.expose_cipher_key();
+            assert_eq!(cipher_key.len(), key_size.byte_size());
+            assert_eq!(cipher_key.capacity(), key_size.byte_size());
+            assert_eq!(key.to_hex().capacity(), 2 * key_size.byte_size());
+        }
+
+        let corruptions = [
+            // mistyped key
+            armored.replace("AAECAwQFBgcICQoLDA0ODw==", "AAECAwQFBgcICQoLDA0OEA=="),
+            // wrong check value
+            armored.replace("c6a13b", "c6a13c"),
+            // wrong key size
+            armored.replace("Key-Size: 128", "Key-Size: 192"),
+            armored.replace("Key-Size: 128", "Key-Size: 129"),
+            // missing headers or armor
+            armored.replace("Check-Value: c6a13b\n", ""),
+            armored.replace("-----END AES KEY-----", ""),
+            armored.replace("Key-Size", "Key-Length"),
+        ];
+        for corrupted in corruptions {
+            assert!(Key::from_armored(&corrupted).is_err(), "{}", corrupted);
+        }
+
+        // surrounding whitespace and upper case check values are accepted
+        let loaded = Key::from_armored(&format!("\n  {}\n", armored.replace("c6a13b", "C6A13B")));
+        assert_eq!(loaded.unwrap().fingerprint(), "c6a13b");
+    }
+
+    #[test]
+    fn test_key_schedule_allocation() {
+        let key_sizes = [
+            KeySize::AES128,
+            KeySize::Rijndael160,
+            KeySize::AES192,
+            KeySize::Rijndael224,
+            KeySize::AES256,
+        ];
+        for key_size in key_sizes {
+            let key_data: Vec<u8> = (0..key_size.byte_size() as u8).collect();
+            // reduced, standard and lengthened schedules
+            for rounds in [1, key_size.encryption_rounds(), 20] {
+                // round key 0 is the cipher key, the schedule is never reallocated
+                let round_keys = expand_key(&key_data, key_size, rounds).unwrap();
+                assert_eq!(round_keys.len(), rounds + 1);
+                assert_eq!(round_keys.capacity(), rounds + 1);
+                assert_eq!(round_keys[0].bytes()[..], key_data[..BLOCK_SIZE]);
+
+                let dec_round_keys = invert_round_keys(&round_keys);
+                assert_eq!(dec_round_keys.capacity(), rounds + 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_key_file() {
+        let path = std::env::temp_dir().join(format!("aes-rs-key-{}.key", std::process::id()));
+        let key = Key::generate(KeySize::AES192).unwrap();
+
+        save_key_file(&path, &key).unwrap();
+        let loaded = load_key_file(&path);
+        #[cfg(unix)]
+        let mode = std::os::unix::fs::PermissionsExt::mode(
+            &std::fs::metadata(&path).unwrap().permissions(),
+        );
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.unwrap().expose_round_keys(), key.expose_round_keys());
+        #[cfg(unix)]
+        assert_eq!(mode & 0o777, 0o600);
+        assert!(load_key_file(&path).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_key_file_existing_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = std::env::temp_dir().join(format!("aes-rs-key-{}-0644.key", std::process::id()));
+        std::fs::write(&path, "not a key").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+        // the existing, world-readable file is restricted before the key is written to it
+        let key = Key::generate(KeySize::AES256).unwrap();
+        save_key_file(&path, &key).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        let loaded = load_key_file(&path);
+
+        // key files beyond the size limit are rejected before they are read
+        std::fs::write(&path, " ".repeat(4097)).unwrap();
+        let oversized = load_key_file(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(loaded.unwrap().expose_round_keys(), key.expose_round_keys());
+        assert!(oversized.unwrap_err().contains("at most 4096 bytes"));
+    }
 }
@@ -1,25 +1,37 @@
+pub mod backend;
+pub mod cipher;
 pub mod config;
 mod constants;
-mod datastructures;
+pub mod datastructures;
+pub mod encoding;
 pub mod key;
 mod modes;
+pub mod random;
+pub mod reduced;
+pub mod rijndael;
+pub mod trace;
+pub mod zeroize;
 
 use self::{
-    config::{AESConfig, OperationMode},
+    backend::Backend,
+    cipher::BlockCipher,
+    config::{AESConfig, KeyPair, OperationMode},
+    constants::MAX_KEY_FILE_SIZE,
     datastructures::block::Block,
     key::Key,
-    modes::{cbc, cfb, ctr, ecb, gcm, ofb},
+    modes::{cbc, ccm, cfb, ctr, ecb, gcm, gcm_siv, ofb, parallel, siv, xts},
+    zeroize::Zeroizing,
 };
 use std::{
     fs::File,
-    io::{BufReader, BufWriter},
+    io::{BufReader, BufWriter, Read, Write},
 };
 
 #[allow(dead_code)]
-pub fn encrypt(
+pub fn encrypt<C: BlockCipher>(
     plaintext: &mut impl std::io::Read,
     ciphertext: &mut impl std::io::Write,
-    config: &AESConfig,
+    config: &AESConfig<C>,
 ) -> Result<usize, String> {
     match config.mode {
         OperationMode::ECB => ecb::encrypt(plaintext, ciphertext, config),
@@ -31,10 +43,10 @@ pub fn encrypt(
 }
 
 #[allow(dead_code)]
-pub fn decrypt(
+pub fn decrypt<C: BlockCipher>(
     ciphertext: &mut impl std::io::Read,
     plaintext: &mut impl std::io::Write,
-    config: &AESConfig,
+    config: &AESConfig<C>,
 ) -> Result<usize, String> {
     match config.mode {
         OperationMode::ECB => ecb::decrypt(ciphertext, plaintext, config),
@@ -46,10 +58,10 @@ pub fn decrypt(
 }
 
 #[allow(dead_code)]
-pub fn encrypt_file(
+pub fn encrypt_file<C: BlockCipher + Sync>(
     infile: &std::path::Path,
     outfile: &std::path::Path,
-    config: &AESConfig,
+    config: &AESConfig<C>,
 ) -> Result<usize, String> {
     let infile = File::open(infile).map_err(|err| err.to_string())?;
     let mut instream = BufReader::new(infile);
@@ -57,14 +69,20 @@ pub fn encrypt_file(
     let outfile = File::create(outfile).map_err(|err| err.to_string())?;
     let mut outstream = BufWriter::new(outfile);
 
-    encrypt(&mut instream, &mut outstream, config)
+    // the blocks of these modes are independent and can be encrypted on several threads
+    match config.mode {
+        OperationMode::ECB | OperationMode::CTR { iv: _ } if config.threads > 1 => {
+            parallel::encrypt(&mut instream, &mut outstream, config)
+        }
+        _ => encrypt(&mut instream, &mut outstream, config),
+    }
 }
 
 #[allow(dead_code)]
-pub fn decrypt_file(
+pub fn decrypt_file<C: BlockCipher + Sync>(
     infile: &std::path::Path,
     outfile: &std::path::Path,
-    config: &AESConfig,
+    config: &AESConfig<C>,
 ) -> Result<usize, String> {
     let infile = File::open(infile).map_err(|err| err.to_string())?;
     let mut instream = BufReader::new(infile);
@@ -72,42 +90,208 @@ pub fn decrypt_file(
     let outfile = File::create(outfile).map_err(|err| err.to_string())?;
     let mut outstream = BufWriter::new(outfile);
 
-    decrypt(&mut instream, &mut outstream, config)
+    // the blocks of these modes are independent and can be decrypted on several threads
+    match config.mode {
+        OperationMode::ECB | OperationMode::CBC { iv: _ } | OperationMode::CTR { iv: _ }
+            if config.threads > 1 =>
+        {
+            parallel::decrypt(&mut instream, &mut outstream, config)
+        }
+        _ => decrypt(&mut instream, &mut outstream, config),
+    }
+}
+
+// write the key in the armored key file format, on unix the file is only readable by
+// its owner
+pub fn save_key_file(path: &std::path::Path, key: &Key) -> Result<(), String> {
+    let armored = key.to_armored()?;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    let mut file = options.open(path).map_err(|err| err.to_string())?;
+    // the mode above only applies to new files, an existing file keeps its permissions
+    // until they are restricted here, before the key is written
+    #[cfg(unix)]
+    file.set_permissions(std::os::unix::fs::PermissionsExt::from_mode(0o600))
+        .map_err(|err| err.to_string())?;
+    file.write_all(armored.as_bytes())
+        .map_err(|err| err.to_string())
+}
+
+// the buffer is sized from the file's length and the read is limited to it, so it's never
+// reallocated and no copy of the key is left in a freed buffer
+pub fn load_key_file(path: &std::path::Path) -> Result<Key, String> {
+    let file = File::open(path).map_err(|err| err.to_string())?;
+    let length = file.metadata().map_err(|err| err.to_string())?.len();
+    if length > MAX_KEY_FILE_SIZE {
+        return Err(format!(
+            "invalid key file: {} bytes, a key file has at most {} bytes",
+            length, MAX_KEY_FILE_SIZE
+        ));
+    }
+
+    let mut armored = Zeroizing::new(String::with_capacity(length as usize));
+    file.take(length)
+        .read_to_string(&mut armored)
+        .map_err(|err| err.to_string())?;
+    Key::from_armored(&armored)
 }
 
 #[allow(dead_code)]
-pub fn encrypt_vec(input: &Vec<u8>, config: &AESConfig) -> Result<Vec<u8>, String> {
+pub fn encrypt_vec<C: BlockCipher>(
+    input: &Vec<u8>,
+    config: &AESConfig<C>,
+) -> Result<Vec<u8>, String> {
     let mut output = Vec::with_capacity(input.len());
     encrypt(&mut input.as_slice(), &mut output, config)?;
     Ok(output)
 }
 
 #[allow(dead_code)]
-pub fn decrypt_vec(input: &Vec<u8>, config: &AESConfig) -> Result<Vec<u8>, String> {
+pub fn decrypt_vec<C: BlockCipher>(
+    input: &Vec<u8>,
+    config: &AESConfig<C>,
+) -> Result<Vec<u8>, String> {
     let mut output = Vec::with_capacity(input.len());
     decrypt(&mut input.as_slice(), &mut output, config)?;
     Ok(output)
 }
 
+// GHASH uses the fastest constant-time multiplication the CPU supports (Backend::Auto),
+// whatever the cipher
 #[allow(dead_code)]
 pub fn authenticated_encrypt_gcm(
     plaintext: &mut impl std::io::Read,
     ciphertext: &mut impl std::io::Write,
-    key: &Key,
+    key: &impl BlockCipher,
     iv: &[u8],
     aad: &[u8],
 ) -> Result<(usize, Block), String> {
-    gcm::authenticated_encrypt(plaintext, ciphertext, key, iv, aad)
+    gcm::authenticated_encrypt(plaintext, ciphertext, key, Backend::Auto, iv, aad)
 }
 
 #[allow(dead_code)]
 pub fn authenticated_decrypt_gcm(
     ciphertext: &mut impl std::io::Read,
     plaintext: &mut impl std::io::Write,
-    key: &Key,
+    key: &impl BlockCipher,
     iv: &[u8],
     aad: &[u8],
     auth_tag: Block,
 ) -> Result<usize, String> {
-    gcm::authenticated_decrypt(ciphertext, plaintext, key, iv, aad, auth_tag)
+    gcm::authenticated_decrypt(ciphertext, plaintext, key, Backend::Auto, iv, aad, auth_tag)
+}
+
+// nonce-misuse-resistant variant of GCM, only for AES-128 and AES-256 keys
+#[allow(dead_code)]
+pub fn authenticated_encrypt_gcm_siv(
+    plaintext: &mut impl std::io::Read,
+    ciphertext: &mut impl std::io::Write,
+    key: &Key,
+    nonce: &[u8],
+    aad: &[u8],
+) -> Result<(usize, Block), String> {
+    gcm_siv::authenticated_encrypt(plaintext, ciphertext, key, nonce, aad)
+}
+
+#[allow(dead_code)]
+pub fn authenticated_decrypt_gcm_siv(
+    ciphertext: &mut impl std::io::Read,
+    plaintext: &mut impl std::io::Write,
+    key: &Key,
+    nonce: &[u8],
+    aad: &[u8],
+    auth_tag: Block,
+) -> Result<usize, String> {
+    gcm_siv::authenticated_decrypt(ciphertext, plaintext, key, nonce, aad, auth_tag)
+}
+
+#[allow(dead_code)]
+pub fn authenticated_encrypt_ccm(
+    plaintext: &mut impl std::io::Read,
+    ciphertext: &mut impl std::io::Write,
+    key: &impl BlockCipher,
+    nonce: &[u8],
+    aad: &[u8],
+    tag_size: usize,
+) -> Result<(usize, Vec<u8>), String> {
+    ccm::authenticated_encrypt(plaintext, ciphertext, key, nonce, aad, tag_size)
+}
+
+#[allow(dead_code)]
+pub fn authenticated_decrypt_ccm(
+    ciphertext: &mut impl std::io::Read,
+    plaintext: &mut impl std::io::Write,
+    key: &impl BlockCipher,
+    nonce: &[u8],
+    aad: &[u8],
+    auth_tag: &[u8],
+) -> Result<usize, String> {
+    ccm::authenticated_decrypt(ciphertext, plaintext, key, nonce, aad, auth_tag)
+}
+
+// deterministic, the same plaintext and AAD always produce the same ciphertext and
+// synthetic IV. A nonce can be passed as the last AAD component.
+#[allow(dead_code)]
+pub fn authenticated_encrypt_siv<C: BlockCipher>(
+    plaintext: &mut impl std::io::Read,
+    ciphertext: &mut impl std::io::Write,
+    key: &KeyPair<C>,
+    aad: &[&[u8]],
+) -> Result<(usize, Block), String> {
+    siv::authenticated_encrypt(plaintext, ciphertext, key, aad)
+}
+
+#[allow(dead_code)]
+pub fn authenticated_decrypt_siv<C: BlockCipher>(
+    ciphertext: &mut impl std::io::Read,
+    plaintext: &mut impl std::io::Write,
+    key: &KeyPair<C>,
+    aad: &[&[u8]],
+    siv: Block,
+) -> Result<usize, String> {
+    siv::authenticated_decrypt(ciphertext, plaintext, key, aad, siv)
+}
+
+// XTS encrypts in place, each sector_size bytes of the data are a data unit whose tweak is
+// its sector number (counting from first_sector). The last sector may be shorter.
+#[allow(dead_code)]
+pub fn encrypt_xts_sectors<C: BlockCipher>(
+    data: &mut [u8],
+    sector_size: usize,
+    first_sector: u128,
+    key: &KeyPair<C>,
+) -> Result<(), String> {
+    xts::encrypt_sectors(data, sector_size, first_sector, key)
+}
+
+#[allow(dead_code)]
+pub fn decrypt_xts_sectors<C: BlockCipher>(
+    data: &mut [u8],
+    sector_size: usize,
+    first_sector: u128,
+    key: &KeyPair<C>,
+) -> Result<(), String> {
+    xts::decrypt_sectors(data, sector_size, first_sector, key)
+}
+
+#[allow(dead_code)]
+pub fn encrypt_xts_data_unit<C: BlockCipher>(
+    data_unit: &mut [u8],
+    key: &KeyPair<C>,
+    tweak: u128,
+) -> Result<(), String> {
+    xts::encrypt_data_unit(data_unit, key, tweak)
+}
+
+#[allow(dead_code)]
+pub fn decrypt_xts_data_unit<C: BlockCipher>(
+    data_unit: &mut [u8],
+    key: &KeyPair<C>,
+    tweak: u128,
+) -> Result<(), String> {
+    xts::decrypt_data_unit(data_unit, key, tweak)
 }
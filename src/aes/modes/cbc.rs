@@ -1,19 +1,20 @@
 use crate::aes::{
+    cipher::BlockCipher,
     config::{AESConfig, OperationMode},
-    constants::BLOCK_SIZE,
+    constants::{BLOCK_SIZE, STREAM_BUFFER_SIZE},
     datastructures::block::Block,
-    modes::common::{decrypt_block, encrypt_block, pad_buffer, read_data, unpad_block, write_data},
+    modes::common::{load_blocks, pad_buffer, read_data, store_blocks, unpad_block, write_data},
+    zeroize::Zeroizing,
 };
-use std::collections::VecDeque;
 
-pub fn encrypt(
+pub fn encrypt<C: BlockCipher>(
     plaintext: &mut impl std::io::Read,
     ciphertext: &mut impl std::io::Write,
-    config: &AESConfig,
+    config: &AESConfig<C>,
 ) -> Result<usize, String> {
     let iv = ensure_cbc_mode(config)?;
 
-    let mut buf: [u8; BLOCK_SIZE] = [0; BLOCK_SIZE];
+    let mut buf = Zeroizing::new([0; BLOCK_SIZE]);
     let mut block_bytes_read;
     let mut total_bytes_written = 0;
 
@@ -23,89 +24,95 @@ pub fn encrypt(
     let mut previous_block = iv;
 
     loop {
-        block_bytes_read = read_data(plaintext, &mut buf)?;
+        block_bytes_read = read_data(plaintext, buf.as_mut_slice())?;
         if block_bytes_read != BLOCK_SIZE {
             break;
         }
 
-        plaintext_block = buf.into();
+        plaintext_block = (*buf).into();
         input_block = plaintext_block ^ previous_block;
-        ciphertext_block = encrypt_block(input_block, &config.key);
+        ciphertext_block = config.key.encrypt_block(input_block);
         previous_block = ciphertext_block;
 
         total_bytes_written += write_data(ciphertext, &ciphertext_block.bytes(), block_bytes_read)?;
     }
 
-    plaintext_block = pad_buffer(buf, block_bytes_read);
+    plaintext_block = pad_buffer(*buf, block_bytes_read);
     input_block = plaintext_block ^ previous_block;
-    ciphertext_block = encrypt_block(input_block, &config.key);
+    ciphertext_block = config.key.encrypt_block(input_block);
     total_bytes_written += write_data(ciphertext, &ciphertext_block.bytes(), BLOCK_SIZE)?;
 
     Ok(total_bytes_written)
 }
 
-pub fn decrypt(
+pub fn decrypt<C: BlockCipher>(
     ciphertext: &mut impl std::io::Read,
     plaintext: &mut impl std::io::Write,
-    config: &AESConfig,
+    config: &AESConfig<C>,
 ) -> Result<usize, String> {
     let iv = ensure_cbc_mode(config)?;
 
-    let mut buf = [0; BLOCK_SIZE];
+    let mut buf = Zeroizing::new(vec![0; STREAM_BUFFER_SIZE]);
     let mut total_bytes_written = 0;
-    let mut block_bytes_read;
+    let mut bytes_read;
 
-    let mut plaintext_block: Block;
-    let mut ciphertext_block: Block;
-    let mut output_block: Block;
+    let mut ciphertext_blocks: Zeroizing<Vec<Block>> =
+        Zeroizing::new(Vec::with_capacity(STREAM_BUFFER_SIZE / BLOCK_SIZE));
+    let mut plaintext_blocks: Zeroizing<Vec<Block>> =
+        Zeroizing::new(Vec::with_capacity(STREAM_BUFFER_SIZE / BLOCK_SIZE));
     let mut previous_block = iv;
-
-    let mut write_queue: VecDeque<Block> = VecDeque::new();
+    let mut last_block: Zeroizing<Option<Block>> = Zeroizing::new(None);
 
     loop {
-        block_bytes_read = read_data(ciphertext, &mut buf)?;
-        if block_bytes_read == 0 {
+        bytes_read = read_data(ciphertext, &mut buf)?;
+        if bytes_read == 0 {
             break;
-        } else if block_bytes_read != BLOCK_SIZE {
+        } else if bytes_read % BLOCK_SIZE != 0 {
             return Err(format!(
                 "invalid ciphertext length, the last block was {} long, expected 16 (block size)",
-                block_bytes_read
+                bytes_read % BLOCK_SIZE
             ));
         }
 
-        ciphertext_block = buf.into();
-        output_block = decrypt_block(ciphertext_block, &config.key);
-        plaintext_block = output_block ^ previous_block;
-        write_queue.push_front(plaintext_block);
+        // unlike encryption, the decryption of each block only depends on
+        // the ciphertext, so all blocks of the buffer can be decrypted at once
+        load_blocks(&buf[..bytes_read], &mut ciphertext_blocks);
+        plaintext_blocks.clone_from(&ciphertext_blocks);
+        config.key.decrypt_blocks(&mut plaintext_blocks);
+
+        for (output_block, ciphertext_block) in
+            plaintext_blocks.iter_mut().zip(ciphertext_blocks.iter())
+        {
+            *output_block ^= previous_block;
+            previous_block = *ciphertext_block;
+        }
 
-        previous_block = ciphertext_block;
-        // Delay writing by one iteration so the padding can be removed from the last block before writing
-        if write_queue.len() < 2 {
-            continue;
+        // Delay writing the last block so the padding can be removed from it before writing
+        let previous_last_block = last_block.replace(
+            plaintext_blocks
+                .pop()
+                .ok_or("couldn't fetch a block from the buffer".to_string())?,
+        );
+        if let Some(block) = previous_last_block {
+            total_bytes_written += write_data(plaintext, &block.bytes(), BLOCK_SIZE)?;
         }
 
-        total_bytes_written += write_data(
-            plaintext,
-            &write_queue
-                .pop_back()
-                .ok_or("couldn't fetch a block from the write queue".to_string())?
-                .bytes(),
-            BLOCK_SIZE,
-        )?;
+        store_blocks(&plaintext_blocks, &mut buf);
+        total_bytes_written += write_data(plaintext, &buf, plaintext_blocks.len() * BLOCK_SIZE)?;
     }
 
-    let last_block = write_queue
-        .pop_back()
+    let last_block = last_block
+        .take()
         .ok_or("couldn't fetch a block from the write queue".to_string())?;
-    let unpadded = unpad_block(last_block)?;
+    let unpadded = Zeroizing::new(unpad_block(last_block)?);
     total_bytes_written += write_data(plaintext, &unpadded, unpadded.len())?;
 
     Ok(total_bytes_written)
 }
 
-fn ensure_cbc_mode(config: &AESConfig) -> Result<Block, String> {
+fn ensure_cbc_mode<C: BlockCipher>(config: &AESConfig<C>) -> Result<Block, String> {
     match config.mode {
-        OperationMode::CBC { iv } => Ok(iv.into()),
+        OperationMode::CBC { ref iv } => Ok((*iv).into()),
         _ => Err(format!(
             "Invalid operation mode, expected CBC, got {:?}",
             config.mode
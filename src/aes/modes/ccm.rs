@@ -0,0 +1,157 @@
+use crate::aes::{
+    cipher::BlockCipher,
+    constants::{
+        BLOCK_SIZE, CCM_MAX_NONCE_SIZE, CCM_MIN_NONCE_SIZE, CCM_TAG_SIZES, STREAM_BUFFER_SIZE,
+    },
+    datastructures::block::Block,
+    modes::common::{read_all, tags_equal, write_data, xor_keystream, Counter},
+    zeroize::Zeroizing,
+};
+
+// CCM encodes the payload length in the first block of the CBC-MAC, so unlike GCM the
+// whole input is read before anything is written. The tag is tag_size bytes long. The
+// maximum payload length depends on the nonce size, see validate_parameters.
+pub fn authenticated_encrypt(
+    plaintext: &mut impl std::io::Read,
+    ciphertext: &mut impl std::io::Write,
+    key: &impl BlockCipher,
+    nonce: &[u8],
+    aad: &[u8],
+    tag_size: usize,
+) -> Result<(usize, Vec<u8>), String> {
+    let mut data = read_all(plaintext, u64::MAX)?;
+
+    let mac = cbc_mac(key, nonce, aad, &data, tag_size)?;
+    let tag_mask = ctr_crypt(&mut data, key, nonce);
+
+    let bytes_written = write_data(ciphertext, &data, data.len())?;
+    Ok((bytes_written, (mac ^ tag_mask).bytes()[..tag_size].to_vec()))
+}
+
+// the plaintext is only written if the authentication tag is valid
+pub fn authenticated_decrypt(
+    ciphertext: &mut impl std::io::Read,
+    plaintext: &mut impl std::io::Write,
+    key: &impl BlockCipher,
+    nonce: &[u8],
+    aad: &[u8],
+    auth_tag: &[u8],
+) -> Result<usize, String> {
+    let mut data = read_all(ciphertext, u64::MAX)?;
+
+    // the nonce and tag sizes are checked before the decryption
+    validate_parameters(nonce, auth_tag.len(), data.len())?;
+    let tag_mask = ctr_crypt(&mut data, key, nonce);
+    let mac = cbc_mac(key, nonce, aad, &data, auth_tag.len())?;
+    let tag = (mac ^ tag_mask).bytes();
+
+    // the computed tag is valid for the ciphertext whatever tag was provided, it must
+    // not be revealed
+    if !tags_equal(&tag[..auth_tag.len()], auth_tag) {
+        return Err("authentication failed".to_string());
+    }
+
+    write_data(plaintext, &data, data.len())
+}
+
+// SP 800-38C: tag sizes of 4 to 16 bytes (even), nonces of 7 to 13 bytes. The remaining
+// q = 15 - nonce length bytes of a block hold the payload length and the counter.
+fn validate_parameters(nonce: &[u8], tag_size: usize, payload_len: usize) -> Result<(), String> {
+    if !CCM_TAG_SIZES.contains(&tag_size) {
+        return Err(format!(
+            "invalid CCM tag size {}, expected one of {:?}",
+            tag_size, CCM_TAG_SIZES
+        ));
+    }
+
+    if !(CCM_MIN_NONCE_SIZE..=CCM_MAX_NONCE_SIZE).contains(&nonce.len()) {
+        return Err(format!(
+            "invalid CCM nonce size {}, expected {} to {} bytes",
+            nonce.len(),
+            CCM_MIN_NONCE_SIZE,
+            CCM_MAX_NONCE_SIZE
+        ));
+    }
+
+    let q = BLOCK_SIZE - 1 - nonce.len();
+    // Counter increments 32 bits, longer payloads would need a wider counter
+    if (payload_len as u128) >> (8 * q) != 0 || payload_len.div_ceil(BLOCK_SIZE) as u64 >= 1 << 32 {
+        return Err(format!(
+            "the payload is too long ({} bytes) for a {}-byte nonce",
+            payload_len,
+            nonce.len()
+        ));
+    }
+
+    Ok(())
+}
+
+// formatting function and CBC-MAC, SP 800-38C A.2 and 6.1
+fn cbc_mac(
+    key: &impl BlockCipher,
+    nonce: &[u8],
+    aad: &[u8],
+    payload: &[u8],
+    tag_size: usize,
+) -> Result<Block, String> {
+    validate_parameters(nonce, tag_size, payload.len())?;
+    let q = BLOCK_SIZE - 1 - nonce.len();
+
+    // B0: flags (Adata, encoded tag size, q - 1) | nonce | payload length in q bytes
+    let mut b_0 = [0; BLOCK_SIZE];
+    b_0[0] = ((!aad.is_empty() as u8) << 6) | (((tag_size - 2) / 2) as u8) << 3 | (q - 1) as u8;
+    b_0[1..=nonce.len()].copy_from_slice(nonce);
+    b_0[BLOCK_SIZE - q..].copy_from_slice(&(payload.len() as u128).to_be_bytes()[BLOCK_SIZE - q..]);
+
+    let mut mac = key.encrypt_block(b_0.into());
+    if !aad.is_empty() {
+        let mut encoded_aad = encode_aad_length(aad.len());
+        encoded_aad.extend_from_slice(aad);
+        mac = cbc_mac_chunks(mac, &encoded_aad, key);
+    }
+
+    Ok(cbc_mac_chunks(mac, payload, key))
+}
+
+// the data is padded with zeros to a multiple of the block size
+fn cbc_mac_chunks(mut mac: Block, data: &[u8], key: &impl BlockCipher) -> Block {
+    for chunk in data.chunks(BLOCK_SIZE) {
+        mac = key.encrypt_block(mac ^ chunk);
+    }
+
+    mac
+}
+
+// the AAD length takes 2, 6 or 10 bytes, depending on its size
+fn encode_aad_length(aad_len: usize) -> Vec<u8> {
+    let aad_len = aad_len as u64;
+    if aad_len < (1 << 16) - (1 << 8) {
+        (aad_len as u16).to_be_bytes().to_vec()
+    } else if aad_len < 1 << 32 {
+        [&[0xff, 0xfe], &(aad_len as u32).to_be_bytes()[..]].concat()
+    } else {
+        [&[0xff, 0xff], &aad_len.to_be_bytes()[..]].concat()
+    }
+}
+
+// CTR encryption with the counter blocks flags (q - 1) | nonce | counter, returns the
+// encryption of counter block 0 which masks the tag
+fn ctr_crypt(data: &mut [u8], key: &impl BlockCipher, nonce: &[u8]) -> Block {
+    let q = BLOCK_SIZE - 1 - nonce.len();
+    let mut ctr_0 = [0; BLOCK_SIZE];
+    ctr_0[0] = (q - 1) as u8;
+    ctr_0[1..=nonce.len()].copy_from_slice(nonce);
+
+    let mut counter = Counter::new(ctr_0.into());
+    let tag_mask = key.encrypt_block(counter.get_block());
+    counter.increment();
+
+    let mut keystream: Zeroizing<Vec<Block>> =
+        Zeroizing::new(Vec::with_capacity(STREAM_BUFFER_SIZE / BLOCK_SIZE));
+    for chunk in data.chunks_mut(STREAM_BUFFER_SIZE) {
+        counter.keystream(chunk.len(), &mut keystream, key);
+        xor_keystream(chunk, &keystream);
+    }
+
+    tag_mask
+}
@@ -1,109 +1,149 @@
 use crate::aes::{
-    config::{AESConfig, CFBSegmentSize, OperationMode},
+    cipher::BlockCipher,
+    config::{AESConfig, OperationMode},
+    constants::BLOCK_SIZE,
     datastructures::block::Block,
-    modes::common::{encrypt_block, read_data, write_data},
+    modes::common::{read_data, write_data},
+    zeroize::Zeroizing,
 };
 
-pub fn encrypt(
+const BLOCK_BITS: usize = BLOCK_SIZE * 8;
+
+pub fn encrypt<C: BlockCipher>(
     plaintext: &mut impl std::io::Read,
     ciphertext: &mut impl std::io::Write,
-    config: &AESConfig,
+    config: &AESConfig<C>,
 ) -> Result<usize, String> {
-    let (iv, seg_size) = ensure_cfb_mode(config)?;
-    let chunk_size = get_chunk_size(seg_size);
+    run_cfb(plaintext, ciphertext, config, false)
+}
 
-    let mut buf: Vec<u8> = vec![0; chunk_size];
-    let mut block_bytes_read;
+pub fn decrypt<C: BlockCipher>(
+    ciphertext: &mut impl std::io::Read,
+    plaintext: &mut impl std::io::Write,
+    config: &AESConfig<C>,
+) -> Result<usize, String> {
+    run_cfb(ciphertext, plaintext, config, true)
+}
+
+// Encryption and decryption only differ in which segment is fed back into the shift
+// register: the ciphertext segment is the output of the encryption and the input of
+// the decryption.
+fn run_cfb<C: BlockCipher>(
+    input: &mut impl std::io::Read,
+    output: &mut impl std::io::Write,
+    config: &AESConfig<C>,
+    decrypting: bool,
+) -> Result<usize, String> {
+    let (iv, seg_bits) = ensure_cfb_mode(config)?;
+
+    // a chunk holds a whole number of segments: one if s is a multiple of 8, otherwise
+    // s bytes hold 8 segments
+    let chunk_size = if seg_bits.is_multiple_of(8) {
+        seg_bits / 8
+    } else {
+        seg_bits
+    };
+
+    let mut buf = Zeroizing::new(vec![0; chunk_size]);
+    let mut bytes_read;
     let mut total_bytes_written = 0;
 
-    let mut input_block: Block;
+    let mut input_segment: u128;
+    let mut output_segment: u128;
     let mut output_block: Block;
-    let mut previous_block = iv;
+    let mut shift_register = iv;
 
     loop {
-        block_bytes_read = read_data(plaintext, &mut buf)?;
-        if block_bytes_read == 0 {
+        bytes_read = read_data(input, &mut buf)?;
+        if bytes_read == 0 {
             break;
         }
 
-        input_block = previous_block;
-        output_block = encrypt_block(input_block, &config.key);
-
-        // ciphertext block = cleartext XOR encrypted block, may be partial
-        buf ^= output_block;
-
-        total_bytes_written += write_data(ciphertext, &buf, block_bytes_read)?;
-
-        match seg_size {
-            CFBSegmentSize::Bit128 => previous_block = buf.clone().try_into()?,
-            CFBSegmentSize::Bit8 => {
-                // overwrite the 8 MSBs and then rotate to set the 8 LSBs
-                // to those of the current ciphertext block
-                previous_block.set_byte(0, buf[0]);
-                previous_block <<= 1;
-            }
+        // the last segment may be partial (fewer than s bits)
+        let data_bits = bytes_read * 8;
+        let mut bit_offset = 0;
+        while bit_offset < data_bits {
+            let segment_bits = seg_bits.min(data_bits - bit_offset);
+
+            output_block = config.key.encrypt_block(shift_register);
+            input_segment = read_bits(&buf, bit_offset, segment_bits);
+            // the s MSBs of the output block are XOR'd with the segment
+            output_segment =
+                input_segment ^ (u128::from(output_block) >> (BLOCK_BITS - segment_bits));
+            write_bits(&mut buf, bit_offset, segment_bits, output_segment);
+
+            let ciphertext_segment = if decrypting {
+                input_segment
+            } else {
+                output_segment
+            };
+            shift_register = shift_in(shift_register, ciphertext_segment, segment_bits);
+            bit_offset += segment_bits;
         }
+
+        total_bytes_written += write_data(output, &buf, bytes_read)?;
     }
 
     Ok(total_bytes_written)
 }
 
-pub fn decrypt(
-    ciphertext: &mut impl std::io::Read,
-    plaintext: &mut impl std::io::Write,
-    config: &AESConfig,
-) -> Result<usize, String> {
-    let (iv, seg_size) = ensure_cfb_mode(config)?;
-    let chunk_size = get_chunk_size(seg_size);
-
-    let mut buf: Vec<u8> = vec![0; chunk_size];
-    let mut block_bytes_read;
-    let mut total_bytes_written = 0;
-
-    let mut plaintext_block: Block;
-    let mut input_block: Block;
-    let mut output_block: Block;
-    let mut previous_block = iv;
+// shift the register s bits to the left, the segment becomes its s LSBs
+fn shift_in(shift_register: Block, segment: u128, bits: usize) -> Block {
+    if bits == BLOCK_BITS {
+        Block::new(segment)
+    } else {
+        Block::new(u128::from(shift_register) << bits | segment)
+    }
+}
 
-    loop {
-        block_bytes_read = read_data(ciphertext, &mut buf)?;
-        if block_bytes_read == 0 {
-            break;
-        }
+// the bits (at most 128) starting at the given bit offset (MSB first) as the LSBs of the result
+fn read_bits(buf: &[u8], bit_offset: usize, bits: usize) -> u128 {
+    if bit_offset.is_multiple_of(8) && bits.is_multiple_of(8) {
+        let start = bit_offset / 8;
+        return buf[start..start + bits / 8]
+            .iter()
+            .fold(0, |value, &byte| value << 8 | byte as u128);
+    }
 
-        input_block = previous_block;
-        output_block = encrypt_block(input_block, &config.key);
-        plaintext_block = output_block ^ buf.as_slice();
+    (bit_offset..bit_offset + bits).fold(0, |value, bit| {
+        value << 1 | ((buf[bit / 8] >> (7 - bit % 8)) & 1) as u128
+    })
+}
 
-        total_bytes_written += write_data(plaintext, &plaintext_block.bytes(), block_bytes_read)?;
+fn write_bits(buf: &mut [u8], bit_offset: usize, bits: usize, value: u128) {
+    if bit_offset.is_multiple_of(8) && bits.is_multiple_of(8) {
+        let start = bit_offset / 8;
+        let bytes = value.to_be_bytes();
+        buf[start..start + bits / 8].copy_from_slice(&bytes[BLOCK_SIZE - bits / 8..]);
+        return;
+    }
 
-        match seg_size {
-            CFBSegmentSize::Bit128 => previous_block = buf.clone().try_into()?,
-            CFBSegmentSize::Bit8 => {
-                // overwrite the 8 MSBs and then rotate to set the 8 LSBs
-                // to those of the current ciphertext block
-                previous_block.set_byte(0, buf[0]);
-                previous_block <<= 1;
-            }
+    for (index, bit) in (bit_offset..bit_offset + bits).enumerate() {
+        let mask = 1 << (7 - bit % 8);
+        if (value >> (bits - 1 - index)) & 1 == 1 {
+            buf[bit / 8] |= mask;
+        } else {
+            buf[bit / 8] &= !mask;
         }
     }
-
-    Ok(total_bytes_written)
 }
 
-fn ensure_cfb_mode(config: &AESConfig) -> Result<(Block, CFBSegmentSize), String> {
+fn ensure_cfb_mode<C: BlockCipher>(config: &AESConfig<C>) -> Result<(Block, usize), String> {
     match config.mode {
-        OperationMode::CFB { iv, seg_size } => Ok((iv.into(), seg_size)),
+        OperationMode::CFB {
+            ref iv,
+            ref seg_size,
+        } if (1..=BLOCK_BITS).contains(&seg_size.bits()) => Ok(((*iv).into(), seg_size.bits())),
+        OperationMode::CFB {
+            iv: _,
+            ref seg_size,
+        } => Err(format!(
+            "Invalid CFB segment size {} bits, expected 1 to 128",
+            seg_size.bits()
+        )),
         _ => Err(format!(
             "Invalid operation mode, expected CFB, got {:?}",
             config.mode
         )),
     }
 }
-
-fn get_chunk_size(segment_size: CFBSegmentSize) -> usize {
-    match segment_size {
-        CFBSegmentSize::Bit128 => 128 / 8,
-        CFBSegmentSize::Bit8 => 1,
-    }
-}
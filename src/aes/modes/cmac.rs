@@ -0,0 +1,83 @@
+use crate::aes::{
+    cipher::BlockCipher, constants::BLOCK_SIZE, datastructures::block::Block, zeroize::Zeroize,
+};
+
+// CMAC (NIST SP 800-38B). The last block is buffered until more data arrives, it is
+// XOR'd with the subkey K1 if it is complete or padded and XOR'd with K2 otherwise.
+pub struct Cmac<'a, C: BlockCipher> {
+    key: &'a C,
+    k1: Block,
+    k2: Block,
+    state: Block,
+    buffer: [u8; BLOCK_SIZE],
+    buffered: usize,
+}
+
+impl<'a, C: BlockCipher> Cmac<'a, C> {
+    pub fn new(key: &'a C) -> Self {
+        // the subkeys are derived from the encryption of the zero block
+        let l = key.encrypt_block(Block::new(0));
+        let k1 = dbl(l);
+
+        Self {
+            key,
+            k1,
+            k2: dbl(k1),
+            state: Block::default(),
+            buffer: [0; BLOCK_SIZE],
+            buffered: 0,
+        }
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            // a full buffer can only be processed once it's clear it isn't the last block
+            if self.buffered == BLOCK_SIZE {
+                self.state = self
+                    .key
+                    .encrypt_block(self.state ^ Block::from(self.buffer));
+                self.buffered = 0;
+            }
+
+            let len = data.len().min(BLOCK_SIZE - self.buffered);
+            self.buffer[self.buffered..self.buffered + len].copy_from_slice(&data[..len]);
+            self.buffered += len;
+            data = &data[len..];
+        }
+    }
+
+    pub fn finalize(&self) -> Block {
+        let last_block = if self.buffered == BLOCK_SIZE {
+            Block::from(self.buffer) ^ self.k1
+        } else {
+            let mut padded = [0; BLOCK_SIZE];
+            padded[..self.buffered].copy_from_slice(&self.buffer[..self.buffered]);
+            padded[self.buffered] = 0x80;
+            Block::from(padded) ^ self.k2
+        };
+
+        self.key.encrypt_block(self.state ^ last_block)
+    }
+}
+
+impl<C: BlockCipher> Drop for Cmac<'_, C> {
+    fn drop(&mut self) {
+        self.k1.zeroize();
+        self.k2.zeroize();
+        self.state.zeroize();
+        self.buffer.zeroize();
+    }
+}
+
+pub fn cmac(key: &impl BlockCipher, data: &[u8]) -> Block {
+    let mut cmac = Cmac::new(key);
+    cmac.update(data);
+    cmac.finalize()
+}
+
+// multiplication by x in GF(2^128) with the big-endian bit order of CMAC and S2V
+pub fn dbl(block: Block) -> Block {
+    let value = u128::from(block);
+    let reduction = if value >> 127 == 1 { 0x87 } else { 0 };
+    Block::new((value << 1) ^ reduction)
+}
@@ -1,56 +1,275 @@
+#[cfg(target_arch = "x86_64")]
+use crate::aes::backend::aesni;
 use crate::aes::{
-    constants::{BLOCK_SIZE, PADDING_BYTE, PADDING_MARKER},
+    backend::{bitslice, ttable, Backend},
+    cipher::BlockCipher,
+    constants::{BLOCK_SIZE, PADDING_BYTE, PADDING_MARKER, STREAM_BUFFER_SIZE},
     datastructures::{block::Block, colmat::ColMatrix},
     key::Key,
+    trace::{NoObserver, RoundObserver, Step},
+    zeroize::{Zeroize, Zeroizing},
 };
+use std::io::Read;
 
 pub fn encrypt_block(block: Block, key: &Key) -> Block {
-    let rounds = key.key_size.encryption_rounds();
+    match key.backend().resolve() {
+        Backend::ColMatrix => colmat_encrypt_block(block, key),
+        Backend::TTable => ttable::encrypt_block(block, key),
+        Backend::Bitsliced => bitslice::encrypt_block(block, key.bitsliced_schedule()),
+        Backend::AesNi => aesni_encrypt_block(block, key),
+        Backend::Auto => unreachable!("resolve() never returns Auto"),
+    }
+}
+
+pub fn decrypt_block(block: Block, key: &Key) -> Block {
+    let mut blocks = [block];
+    decrypt_blocks(&mut blocks, key);
+    blocks[0]
+}
+
+// encrypt several independent blocks in place, the backends interleave
+// the rounds of 4 or 8 blocks to make use of instruction-level parallelism
+pub fn encrypt_blocks(blocks: &mut [Block], key: &Key) {
+    match key.backend().resolve() {
+        Backend::ColMatrix => blocks
+            .iter_mut()
+            .for_each(|block| *block = colmat_encrypt_block(*block, key)),
+        Backend::TTable => ttable::encrypt_blocks(blocks, key),
+        Backend::Bitsliced => bitslice::encrypt_blocks(blocks, key.bitsliced_schedule()),
+        Backend::AesNi => aesni_encrypt_blocks(blocks, key),
+        Backend::Auto => unreachable!("resolve() never returns Auto"),
+    }
+}
+
+pub fn decrypt_blocks(blocks: &mut [Block], key: &Key) {
+    match key.backend().resolve() {
+        Backend::ColMatrix => blocks
+            .iter_mut()
+            .for_each(|block| *block = colmat_decrypt_block(*block, key)),
+        Backend::TTable => ttable::decrypt_blocks(blocks, key),
+        Backend::Bitsliced => bitslice::decrypt_blocks(blocks, key.bitsliced_schedule()),
+        Backend::AesNi => aesni_decrypt_blocks(blocks, key),
+        Backend::Auto => unreachable!("resolve() never returns Auto"),
+    }
+}
+
+// the key only carries a hardware schedule if the CPU supports AES-NI
+#[cfg(target_arch = "x86_64")]
+fn aesni_encrypt_block(block: Block, key: &Key) -> Block {
+    match key.aesni_schedule() {
+        Some(schedule) => aesni::encrypt_block(block, schedule),
+        None => colmat_encrypt_block(block, key),
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+fn aesni_encrypt_blocks(blocks: &mut [Block], key: &Key) {
+    match key.aesni_schedule() {
+        Some(schedule) => aesni::encrypt_blocks(blocks, schedule),
+        None => blocks
+            .iter_mut()
+            .for_each(|block| *block = colmat_encrypt_block(*block, key)),
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+fn aesni_decrypt_blocks(blocks: &mut [Block], key: &Key) {
+    match key.aesni_schedule() {
+        Some(schedule) => aesni::decrypt_blocks(blocks, schedule),
+        None => blocks
+            .iter_mut()
+            .for_each(|block| *block = colmat_decrypt_block(*block, key)),
+    }
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn aesni_encrypt_block(block: Block, key: &Key) -> Block {
+    colmat_encrypt_block(block, key)
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn aesni_encrypt_blocks(blocks: &mut [Block], key: &Key) {
+    blocks
+        .iter_mut()
+        .for_each(|block| *block = colmat_encrypt_block(*block, key));
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn aesni_decrypt_blocks(blocks: &mut [Block], key: &Key) {
+    blocks
+        .iter_mut()
+        .for_each(|block| *block = colmat_decrypt_block(*block, key));
+}
+
+fn colmat_encrypt_block(block: Block, key: &Key) -> Block {
+    colmat_encrypt_block_observed(block, key, &mut NoObserver)
+}
+
+fn colmat_decrypt_block(block: Block, key: &Key) -> Block {
+    colmat_decrypt_block_observed(block, key, &mut NoObserver)
+}
+
+// the observer sees the state after every step, NoObserver's empty methods are optimized away
+pub fn colmat_encrypt_block_observed(
+    block: Block,
+    key: &Key,
+    observer: &mut impl RoundObserver,
+) -> Block {
+    let rounds = key.rounds();
 
     let mut colmat = ColMatrix::new(block);
+    observer.observe(0, Step::Input, &colmat);
 
     // "0"th round: just apply the first block of the inital key
     colmat += key[0];
+    observer.observe(0, Step::AddRoundKey(key[0]), &colmat);
 
     for round in 1..rounds {
         colmat.sub_bytes();
+        observer.observe(round, Step::SubBytes, &colmat);
         colmat.shift_rows();
+        observer.observe(round, Step::ShiftRows, &colmat);
         colmat.mix_columns();
+        observer.observe(round, Step::MixColumns, &colmat);
         colmat += key[round];
+        observer.observe(round, Step::AddRoundKey(key[round]), &colmat);
     }
 
     // last round: do not mix columns
     colmat.sub_bytes();
+    observer.observe(rounds, Step::SubBytes, &colmat);
     colmat.shift_rows();
+    observer.observe(rounds, Step::ShiftRows, &colmat);
     colmat += key[rounds];
+    observer.observe(rounds, Step::AddRoundKey(key[rounds]), &colmat);
 
+    observer.observe(rounds, Step::Output, &colmat);
     colmat.block()
 }
 
-pub fn decrypt_block(block: Block, key: &Key) -> Block {
-    let rounds = key.key_size.encryption_rounds();
+// the rounds are counted in the order they are executed (as in FIPS-197 Appendix C),
+// round i of the decryption uses the round key Nr - i
+pub fn colmat_decrypt_block_observed(
+    block: Block,
+    key: &Key,
+    observer: &mut impl RoundObserver,
+) -> Block {
+    let rounds = key.rounds();
 
     let mut colmat = ColMatrix::new(block);
+    observer.observe(0, Step::InvInput, &colmat);
 
     // first round: do not apply inverse mix columns
     colmat += key[rounds];
+    observer.observe(0, Step::AddRoundKey(key[rounds]), &colmat);
     colmat.inv_shift_rows();
+    observer.observe(1, Step::InvShiftRows, &colmat);
     colmat.inv_sub_bytes();
+    observer.observe(1, Step::InvSubBytes, &colmat);
 
     for round in (1..rounds).rev() {
+        let trace_round = rounds - round;
         colmat += key[round];
+        observer.observe(trace_round, Step::AddRoundKey(key[round]), &colmat);
         colmat.inv_mix_columns();
+        observer.observe(trace_round, Step::InvMixColumns, &colmat);
         colmat.inv_shift_rows();
+        observer.observe(trace_round + 1, Step::InvShiftRows, &colmat);
         colmat.inv_sub_bytes();
+        observer.observe(trace_round + 1, Step::InvSubBytes, &colmat);
     }
 
     colmat += key[0];
+    observer.observe(rounds, Step::AddRoundKey(key[0]), &colmat);
+
+    observer.observe(rounds, Step::InvOutput, &colmat);
+    colmat.block()
+}
+
+// FIPS-197 "equivalent inverse cipher": the inverse operations in the order of the
+// cipher (InvSubBytes, InvShiftRows, InvMixColumns, AddRoundKey), which requires the
+// round keys with InvMixColumns applied. Same output as decrypt_block.
+pub fn equivalent_decrypt_block(block: Block, key: &Key) -> Block {
+    let rounds = key.rounds();
+    let dec_round_key = |round| *key.get_dec_round_key(round).unwrap();
+
+    let mut colmat = ColMatrix::new(block);
+
+    colmat += dec_round_key(rounds);
+
+    for round in (1..rounds).rev() {
+        colmat.inv_sub_bytes();
+        colmat.inv_shift_rows();
+        colmat.inv_mix_columns();
+        colmat += dec_round_key(round);
+    }
+
+    // last round: do not mix columns
+    colmat.inv_sub_bytes();
+    colmat.inv_shift_rows();
+    colmat += dec_round_key(0);
 
     colmat.block()
 }
 
+// Compare authentication tags in constant time: every byte is compared, so the time doesn't
+// depend on the position of the first difference. Tags of different lengths never match.
+pub fn tags_equal(a: &[u8], b: &[u8]) -> bool {
+    let difference = a
+        .iter()
+        .zip(b)
+        .fold(0, |difference, (a, b)| difference | (a ^ b));
+    a.len() == b.len() && difference == 0
+}
+
+// fill the buffer, fewer bytes are only returned at the end of the input stream
 pub fn read_data(src: &mut impl std::io::Read, buf: &mut [u8]) -> Result<usize, String> {
-    src.read(buf).map_err(|err| err.to_string())
+    let mut bytes_read = 0;
+
+    while bytes_read < buf.len() {
+        match src.read(&mut buf[bytes_read..]) {
+            Ok(0) => break,
+            Ok(bytes) => bytes_read += bytes,
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err.to_string()),
+        }
+    }
+
+    Ok(bytes_read)
+}
+
+// read the whole input, for the modes that compute the tag before writing anything. At most
+// max_size + 1 bytes are read, longer inputs are rejected.
+// The input is read in chunks of a fixed size. When the buffer is full, it's copied to a
+// buffer of twice the size and the old one is wiped, Vec's own reallocation would leave the
+// plaintext in the freed memory.
+pub fn read_all(src: &mut impl std::io::Read, max_size: u64) -> Result<Zeroizing<Vec<u8>>, String> {
+    let mut src = src.take(max_size.saturating_add(1));
+    let mut data = Zeroizing::new(Vec::with_capacity(STREAM_BUFFER_SIZE));
+    let mut chunk = Zeroizing::new(vec![0; STREAM_BUFFER_SIZE]);
+
+    loop {
+        let bytes_read = read_data(&mut src, &mut chunk)?;
+        if data.capacity() - data.len() < bytes_read {
+            let mut grown = Zeroizing::new(Vec::with_capacity(2 * data.capacity()));
+            grown.extend_from_slice(&data);
+            data = grown;
+        }
+        data.extend_from_slice(&chunk[..bytes_read]);
+
+        if bytes_read < chunk.len() {
+            break;
+        }
+    }
+
+    if data.len() as u64 > max_size {
+        return Err(format!(
+            "the input is too long, expected at most {} bytes",
+            max_size
+        ));
+    }
+
+    Ok(data)
 }
 
 pub fn write_data(
@@ -58,7 +277,46 @@ pub fn write_data(
     buf: &[u8],
     bytes: usize,
 ) -> Result<usize, String> {
-    dst.write(&buf[0..bytes]).map_err(|err| err.to_string())
+    dst.write_all(&buf[0..bytes])
+        .map_err(|err| err.to_string())?;
+    Ok(bytes)
+}
+
+// split the buffer into blocks, a partial last block is filled up with zeros. The full
+// blocks are converted as arrays, the generic partial conversion is only used for the tail
+pub fn load_blocks(buf: &[u8], blocks: &mut Vec<Block>) {
+    blocks.clear();
+    let mut chunks = buf.chunks_exact(BLOCK_SIZE);
+    blocks.extend(
+        chunks
+            .by_ref()
+            .map(|chunk| Block::from(<[u8; BLOCK_SIZE]>::try_from(chunk).unwrap())),
+    );
+    if !chunks.remainder().is_empty() {
+        blocks.push(Block::default() ^ chunks.remainder());
+    }
+}
+
+// write the blocks back to the buffer, a partial last chunk receives the first bytes of the block
+pub fn store_blocks(blocks: &[Block], buf: &mut [u8]) {
+    for (chunk, block) in buf.chunks_mut(BLOCK_SIZE).zip(blocks) {
+        if chunk.len() == BLOCK_SIZE {
+            chunk.copy_from_slice(&block.bytes());
+        } else {
+            chunk.copy_from_slice(&block.bytes()[..chunk.len()]);
+        }
+    }
+}
+
+// XOR the buffer with the encrypted counter blocks of a stream cipher mode, may be partial
+pub fn xor_keystream(buf: &mut [u8], keystream: &[Block]) {
+    for (mut chunk, block) in buf.chunks_mut(BLOCK_SIZE).zip(keystream) {
+        if let Ok(bytes) = <[u8; BLOCK_SIZE]>::try_from(&*chunk) {
+            chunk.copy_from_slice(&(Block::from(bytes) ^ block).bytes());
+        } else {
+            chunk ^= *block;
+        }
+    }
 }
 
 pub fn pad_buffer(mut buf: [u8; BLOCK_SIZE], start_index: usize) -> Block {
@@ -83,6 +341,8 @@ pub fn unpad_block(block: Block) -> Result<Vec<u8>, String> {
     Ok(block)
 }
 
+// the counter blocks are derived from the IV (in GCM, possibly via the hash subkey),
+// zeroized on drop
 pub struct Counter {
     block_data: u128,
     counter: u32,
@@ -107,7 +367,35 @@ impl Counter {
         self.counter = self.counter.wrapping_add(1);
     }
 
+    // advance the counter as if increment() had been called `blocks` times
+    pub fn skip(&mut self, blocks: usize) {
+        self.counter = self.counter.wrapping_add(blocks as u32);
+    }
+
     pub fn get_block(&self) -> Block {
         Block::new(self.block_data | self.counter as u128)
     }
+
+    // encrypt the next counter blocks at once, one for every (partial) block of the data
+    pub fn keystream(
+        &mut self,
+        data_len: usize,
+        keystream: &mut Vec<Block>,
+        cipher: &impl BlockCipher,
+    ) {
+        keystream.clear();
+        for _ in 0..data_len.div_ceil(BLOCK_SIZE) {
+            keystream.push(self.get_block());
+            self.increment();
+        }
+
+        cipher.encrypt_blocks(keystream);
+    }
+}
+
+impl Drop for Counter {
+    fn drop(&mut self) {
+        self.block_data.zeroize();
+        self.counter.zeroize();
+    }
 }
@@ -1,56 +1,60 @@
 use crate::aes::{
+    cipher::BlockCipher,
     config::{AESConfig, OperationMode},
-    constants::BLOCK_SIZE,
+    constants::{BLOCK_SIZE, STREAM_BUFFER_SIZE},
     datastructures::block::Block,
-    modes::common::{encrypt_block, read_data, write_data, Counter},
+    modes::common::{read_data, write_data, xor_keystream, Counter},
+    zeroize::Zeroizing,
 };
 
-pub fn encrypt(
+pub fn encrypt<C: BlockCipher>(
     plaintext: &mut impl std::io::Read,
     ciphertext: &mut impl std::io::Write,
-    config: &AESConfig,
+    config: &AESConfig<C>,
 ) -> Result<usize, String> {
     let iv = ensure_ctr_mode(config)?;
     let mut ctr = Counter::new(iv);
 
-    let mut buf = [0; BLOCK_SIZE];
-    let mut block_bytes_read;
+    let mut buf = Zeroizing::new(vec![0; STREAM_BUFFER_SIZE]);
+    let mut bytes_read;
     let mut total_bytes_written = 0;
 
-    let mut input_block: Block;
-    let mut output_block: Block;
-    let mut ciphertext_block: Block;
+    let mut output_blocks: Zeroizing<Vec<Block>> =
+        Zeroizing::new(Vec::with_capacity(STREAM_BUFFER_SIZE / BLOCK_SIZE));
 
     loop {
-        block_bytes_read = read_data(plaintext, &mut buf)?;
+        bytes_read = read_data(plaintext, &mut buf)?;
 
-        if block_bytes_read == 0 {
+        if bytes_read == 0 {
             break;
         }
 
-        input_block = ctr.get_block();
-        output_block = encrypt_block(input_block, &config.key);
-        ciphertext_block = output_block ^ buf.as_slice();
+        // the counter blocks do not depend on the data, encrypt a whole buffer's worth at once
+        ctr.keystream(bytes_read, &mut output_blocks, &config.key);
+        // ciphertext block = plaintext block XOR output block, the last one may be partial
+        xor_keystream(&mut buf[..bytes_read], &output_blocks);
 
-        total_bytes_written += write_data(ciphertext, &ciphertext_block.bytes(), block_bytes_read)?;
+        total_bytes_written += write_data(ciphertext, &buf, bytes_read)?;
 
-        ctr.increment();
+        if bytes_read != buf.len() {
+            break;
+        }
     }
 
     Ok(total_bytes_written)
 }
 
-pub fn decrypt(
+pub fn decrypt<C: BlockCipher>(
     ciphertext: &mut impl std::io::Read,
     plaintext: &mut impl std::io::Write,
-    config: &AESConfig,
+    config: &AESConfig<C>,
 ) -> Result<usize, String> {
     encrypt(ciphertext, plaintext, config)
 }
 
-fn ensure_ctr_mode(config: &AESConfig) -> Result<Block, String> {
+fn ensure_ctr_mode<C: BlockCipher>(config: &AESConfig<C>) -> Result<Block, String> {
     match config.mode {
-        OperationMode::CTR { iv } => Ok(iv.into()),
+        OperationMode::CTR { ref iv } => Ok((*iv).into()),
         _ => Err(format!(
             "Invalid operation mode, expected CTR, got {:?}",
             config.mode
@@ -1,100 +1,107 @@
 use crate::aes::{
+    cipher::BlockCipher,
     config::{AESConfig, OperationMode},
-    constants::BLOCK_SIZE,
+    constants::{BLOCK_SIZE, STREAM_BUFFER_SIZE},
     datastructures::block::Block,
-    modes::common::{decrypt_block, encrypt_block, pad_buffer, read_data, unpad_block, write_data},
+    modes::common::{load_blocks, pad_buffer, read_data, store_blocks, unpad_block, write_data},
+    zeroize::Zeroizing,
 };
-use std::collections::VecDeque;
 
-pub fn encrypt(
+pub fn encrypt<C: BlockCipher>(
     plaintext: &mut impl std::io::Read,
     ciphertext: &mut impl std::io::Write,
-    config: &AESConfig,
+    config: &AESConfig<C>,
 ) -> Result<usize, String> {
     ensure_ecb_mode(config)?;
 
-    let mut buf: [u8; BLOCK_SIZE] = [0; BLOCK_SIZE];
-    let mut block_bytes_read;
+    let mut buf = Zeroizing::new(vec![0; STREAM_BUFFER_SIZE]);
+    let mut bytes_read;
     let mut total_bytes_written = 0;
 
-    let mut plaintext_block: Block;
-    let mut ciphertext_block: Block;
+    let mut blocks: Zeroizing<Vec<Block>> =
+        Zeroizing::new(Vec::with_capacity(STREAM_BUFFER_SIZE / BLOCK_SIZE));
 
     loop {
-        block_bytes_read = read_data(plaintext, &mut buf)?;
-        if block_bytes_read != BLOCK_SIZE {
-            break;
+        bytes_read = read_data(plaintext, &mut buf)?;
+        let last_buffer = bytes_read != buf.len();
+
+        let full_blocks_len = bytes_read - bytes_read % BLOCK_SIZE;
+        load_blocks(&buf[..full_blocks_len], &mut blocks);
+
+        // the remaining bytes (possibly none) are padded to a full block
+        if last_buffer {
+            let mut last_block_data = Zeroizing::new([0; BLOCK_SIZE]);
+            last_block_data[..bytes_read - full_blocks_len]
+                .copy_from_slice(&buf[full_blocks_len..bytes_read]);
+            blocks.push(pad_buffer(*last_block_data, bytes_read - full_blocks_len));
         }
 
-        plaintext_block = buf.into();
-        ciphertext_block = encrypt_block(plaintext_block, &config.key);
+        config.key.encrypt_blocks(&mut blocks);
+        store_blocks(&blocks, &mut buf);
+        total_bytes_written += write_data(ciphertext, &buf, blocks.len() * BLOCK_SIZE)?;
 
-        total_bytes_written += write_data(ciphertext, &ciphertext_block.bytes(), BLOCK_SIZE)?;
+        if last_buffer {
+            break;
+        }
     }
 
-    plaintext_block = pad_buffer(buf, block_bytes_read);
-    ciphertext_block = encrypt_block(plaintext_block, &config.key);
-    total_bytes_written += write_data(ciphertext, &ciphertext_block.bytes(), BLOCK_SIZE)?;
-
     Ok(total_bytes_written)
 }
 
-pub fn decrypt(
+pub fn decrypt<C: BlockCipher>(
     ciphertext: &mut impl std::io::Read,
     plaintext: &mut impl std::io::Write,
-    config: &AESConfig,
+    config: &AESConfig<C>,
 ) -> Result<usize, String> {
     ensure_ecb_mode(config)?;
 
-    let mut buf: [u8; BLOCK_SIZE] = [0; BLOCK_SIZE];
+    let mut buf = Zeroizing::new(vec![0; STREAM_BUFFER_SIZE]);
     let mut total_bytes_written = 0;
-    let mut block_bytes_read;
-
-    let mut plaintext_block: Block;
-    let mut ciphertext_block: Block;
+    let mut bytes_read;
 
-    let mut write_queue: VecDeque<Block> = VecDeque::new();
+    let mut blocks: Zeroizing<Vec<Block>> =
+        Zeroizing::new(Vec::with_capacity(STREAM_BUFFER_SIZE / BLOCK_SIZE));
+    let mut last_block: Zeroizing<Option<Block>> = Zeroizing::new(None);
 
     loop {
-        block_bytes_read = read_data(ciphertext, &mut buf)?;
+        bytes_read = read_data(ciphertext, &mut buf)?;
 
-        if block_bytes_read == 0 {
+        if bytes_read == 0 {
             break;
-        } else if block_bytes_read != BLOCK_SIZE {
+        } else if bytes_read % BLOCK_SIZE != 0 {
             return Err(format!(
                 "invalid ciphertext length, the last block was {} long, expected 16 (block size)",
-                block_bytes_read
+                bytes_read % BLOCK_SIZE
             ));
         }
-        ciphertext_block = buf.into();
-        plaintext_block = decrypt_block(ciphertext_block, &config.key);
-        write_queue.push_front(plaintext_block);
 
-        // Delay writing by one iteration so the padding can be removed from the last block before writing
-        if write_queue.len() < 2 {
-            continue;
+        load_blocks(&buf[..bytes_read], &mut blocks);
+        config.key.decrypt_blocks(&mut blocks);
+
+        // Delay writing the last block so the padding can be removed from it before writing
+        let previous_last_block = last_block.replace(
+            blocks
+                .pop()
+                .ok_or("couldn't fetch a block from the buffer".to_string())?,
+        );
+        if let Some(block) = previous_last_block {
+            total_bytes_written += write_data(plaintext, &block.bytes(), BLOCK_SIZE)?;
         }
 
-        total_bytes_written += write_data(
-            plaintext,
-            &write_queue
-                .pop_back()
-                .ok_or("couldn't fetch a block from the write queue".to_string())?
-                .bytes(),
-            BLOCK_SIZE,
-        )?;
+        store_blocks(&blocks, &mut buf);
+        total_bytes_written += write_data(plaintext, &buf, blocks.len() * BLOCK_SIZE)?;
     }
 
-    let last_block = write_queue
-        .pop_back()
+    let last_block = last_block
+        .take()
         .ok_or("couldn't fetch a block from the write queue".to_string())?;
-    let unpadded = unpad_block(last_block)?;
+    let unpadded = Zeroizing::new(unpad_block(last_block)?);
     total_bytes_written += write_data(plaintext, &unpadded, unpadded.len())?;
 
     Ok(total_bytes_written)
 }
 
-fn ensure_ecb_mode(config: &AESConfig) -> Result<(), String> {
+fn ensure_ecb_mode<C: BlockCipher>(config: &AESConfig<C>) -> Result<(), String> {
     match config.mode {
         OperationMode::ECB => Ok(()),
         _ => Err(format!(
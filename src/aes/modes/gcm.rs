@@ -1,36 +1,47 @@
 use crate::aes::{
-    constants::BLOCK_SIZE,
-    datastructures::block::Block,
-    key::Key,
-    modes::common::{encrypt_block, read_data, write_data, Counter},
+    backend::Backend,
+    cipher::BlockCipher,
+    constants::{BLOCK_SIZE, STREAM_BUFFER_SIZE},
+    datastructures::{block::Block, ghash::GHashKey},
+    modes::common::{read_data, tags_equal, write_data, xor_keystream, Counter},
+    zeroize::Zeroizing,
 };
 
+// The GHASH multiplication is independent of the block cipher, ghash_backend selects the
+// method (see GHashKey) like the backend of a Key selects the cipher implementation.
 pub fn authenticated_encrypt(
     plaintext: &mut impl std::io::Read,
     ciphertext: &mut impl std::io::Write,
-    key: &Key,
+    key: &impl BlockCipher,
+    ghash_backend: Backend,
     iv: &[u8],
     aad: &[u8],
 ) -> Result<(usize, Block), String> {
-    run_authenticated_cipher_operation(plaintext, ciphertext, key, iv, aad, false)
+    run_authenticated_cipher_operation(plaintext, ciphertext, key, ghash_backend, iv, aad, false)
 }
 
 pub fn authenticated_decrypt(
     ciphertext: &mut impl std::io::Read,
     plaintext: &mut impl std::io::Write,
-    key: &Key,
+    key: &impl BlockCipher,
+    ghash_backend: Backend,
     iv: &[u8],
     aad: &[u8],
     auth_tag: Block,
 ) -> Result<usize, String> {
-    let (bytes_written, t) =
-        run_authenticated_cipher_operation(ciphertext, plaintext, key, iv, aad, true)?;
-
-    if t != auth_tag {
-        Err(format!(
-            "the authentication tags differed! provided: {} | got: {}",
-            auth_tag, t,
-        ))
+    let (bytes_written, t) = run_authenticated_cipher_operation(
+        ciphertext,
+        plaintext,
+        key,
+        ghash_backend,
+        iv,
+        aad,
+        true,
+    )?;
+
+    // the computed tag must not be revealed, it would authenticate the ciphertext
+    if !tags_equal(&t.bytes(), &auth_tag.bytes()) {
+        Err("authentication failed".to_string())
     } else {
         Ok(bytes_written)
     }
@@ -41,13 +52,14 @@ pub fn authenticated_decrypt(
 fn run_authenticated_cipher_operation(
     intext: &mut impl std::io::Read,
     outtext: &mut impl std::io::Write,
-    key: &Key,
+    key: &impl BlockCipher,
+    ghash_backend: Backend,
     iv: &[u8],
     aad: &[u8],
     decrypt: bool,
 ) -> Result<(usize, Block), String> {
-    let hash_subkey = generate_hash_subkey(key);
-    let j_0 = generate_initial_ctr_block(iv, hash_subkey);
+    let hash_subkey = GHashKey::new(generate_hash_subkey(key), ghash_backend);
+    let j_0 = generate_initial_ctr_block(iv, &hash_subkey);
 
     let mut counter = Counter::new(j_0);
     // save for calculating the authentication tag T later
@@ -56,7 +68,7 @@ fn run_authenticated_cipher_operation(
     counter.increment();
 
     let padded_aad = pad_to_multiple_of_128(aad);
-    let aad_ghash = ghash(&padded_aad, hash_subkey);
+    let aad_ghash = ghash(&padded_aad, &hash_subkey);
     let aad_len = (aad.len() * 8) as u128;
 
     let (ghash_value, bytes_written) = gctr(
@@ -64,7 +76,7 @@ fn run_authenticated_cipher_operation(
         outtext,
         counter,
         aad_ghash,
-        hash_subkey,
+        &hash_subkey,
         key,
         decrypt,
     )?;
@@ -74,14 +86,14 @@ fn run_authenticated_cipher_operation(
 
     // 64 MSBs: AAD length, 64 LSBs: length of the ciphertext
     let last_ghash_block = Block::new((aad_len << 64) | ciphertext_len);
-    let s = next_ghash(ghash_value, last_ghash_block, hash_subkey);
-    let t = encrypt_block(initial_counter_block, key) ^ s;
+    let s = next_ghash(ghash_value, last_ghash_block, &hash_subkey);
+    let t = key.encrypt_block(initial_counter_block) ^ s;
 
     Ok((bytes_written, t))
 }
 
 // as described in NIST Special Publication 800-38D, section 6.4
-fn ghash(input: &[Block], hash_subkey: Block) -> Block {
+fn ghash(input: &[Block], hash_subkey: &GHashKey) -> Block {
     let mut output = Block::default();
 
     input.iter().for_each(|next| {
@@ -91,8 +103,8 @@ fn ghash(input: &[Block], hash_subkey: Block) -> Block {
     output
 }
 
-fn next_ghash(current: Block, next: Block, hash_subkey: Block) -> Block {
-    (current ^ next) * hash_subkey
+fn next_ghash(current: Block, next: Block, hash_subkey: &GHashKey) -> Block {
+    hash_subkey.mul(current ^ next)
 }
 
 // as described in NIST Special Publication 800-38D, section 6.5
@@ -102,67 +114,64 @@ fn gctr(
     outtext: &mut impl std::io::Write,
     mut counter: Counter,
     mut ghash_block: Block,
-    hash_subkey: Block,
-    key: &Key,
+    hash_subkey: &GHashKey,
+    key: &impl BlockCipher,
     decrypt: bool,
 ) -> Result<(Block, usize), String> {
-    let mut buf = [0; BLOCK_SIZE];
-    let mut block_bytes_read;
+    let mut buf = Zeroizing::new(vec![0; STREAM_BUFFER_SIZE]);
+    let mut bytes_read;
     let mut total_bytes_written = 0;
 
-    let mut in_block: Block;
-    let mut out_block: Block;
-    let mut cipher_input_block: Block;
-    let mut cipher_output_block: Block;
+    let mut cipher_output_blocks: Zeroizing<Vec<Block>> =
+        Zeroizing::new(Vec::with_capacity(STREAM_BUFFER_SIZE / BLOCK_SIZE));
 
     loop {
-        block_bytes_read = read_data(intext, &mut buf)?;
+        bytes_read = read_data(intext, &mut buf)?;
 
-        if block_bytes_read == 0 {
+        if bytes_read == 0 {
             break;
         }
 
-        in_block = buf.into();
+        // the hash is always calculated over the ciphertext, a partial last block is padded with zeros
         if decrypt {
-            if block_bytes_read != BLOCK_SIZE {
-                let mut ciphertext_bytes = in_block.bytes();
-                ciphertext_bytes[block_bytes_read..].fill(0);
-                in_block = ciphertext_bytes.into();
-            }
-            ghash_block = next_ghash(ghash_block, in_block, hash_subkey);
+            ghash_block = ghash_chunks(ghash_block, &buf[..bytes_read], hash_subkey);
         }
 
-        cipher_input_block = counter.get_block();
-        cipher_output_block = encrypt_block(cipher_input_block, key);
-        out_block = cipher_output_block ^ in_block;
+        counter.keystream(bytes_read, &mut cipher_output_blocks, key);
+        xor_keystream(&mut buf[..bytes_read], &cipher_output_blocks);
 
-        total_bytes_written += write_data(outtext, &out_block.bytes(), block_bytes_read)?;
+        total_bytes_written += write_data(outtext, &buf, bytes_read)?;
 
         if !decrypt {
-            if block_bytes_read != BLOCK_SIZE {
-                let mut ciphertext_bytes = out_block.bytes();
-                ciphertext_bytes[block_bytes_read..].fill(0);
-                out_block = ciphertext_bytes.into();
-            }
-            ghash_block = next_ghash(ghash_block, out_block, hash_subkey);
+            ghash_block = ghash_chunks(ghash_block, &buf[..bytes_read], hash_subkey);
         }
 
-        counter.increment();
+        if bytes_read != buf.len() {
+            break;
+        }
     }
 
     Ok((ghash_block, total_bytes_written))
 }
 
+fn ghash_chunks(mut ghash_block: Block, data: &[u8], hash_subkey: &GHashKey) -> Block {
+    for chunk in data.chunks(BLOCK_SIZE) {
+        ghash_block = next_ghash(ghash_block, Block::default() ^ chunk, hash_subkey);
+    }
+
+    ghash_block
+}
+
 // =================================================================
 //                     helper functions
 // =================================================================
-fn generate_hash_subkey(key: &Key) -> Block {
+pub fn generate_hash_subkey(key: &impl BlockCipher) -> Block {
     // the hash subkey H is derived from the forward encryption of the 0 block
     let input = Block::new(0);
-    encrypt_block(input, key)
+    key.encrypt_block(input)
 }
 
-fn generate_initial_ctr_block(iv: &[u8], hash_subkey: Block) -> Block {
+fn generate_initial_ctr_block(iv: &[u8], hash_subkey: &GHashKey) -> Block {
     let mut block_data = [0; BLOCK_SIZE];
 
     if iv.len() == 96 / 8 {
@@ -0,0 +1,157 @@
+use crate::aes::{
+    cipher::BlockCipher,
+    constants::{
+        BLOCK_SIZE, GCM_SIV_MAX_INPUT_SIZE, GCM_SIV_NONCE_SIZE, KEY_SIZE_AES128, STREAM_BUFFER_SIZE,
+    },
+    datastructures::{block::Block, ghash::Polyval},
+    key::{size::KeySize, Key},
+    modes::common::{read_all, tags_equal, write_data, xor_keystream},
+    zeroize::{Zeroize, Zeroizing},
+};
+
+// AES-GCM-SIV (RFC 8452): the tag is computed over the plaintext first and then used as
+// the IV of the encryption, so the whole input is read before anything is written. Reusing
+// a nonce only reveals whether two messages (with the same AAD) were identical.
+pub fn authenticated_encrypt(
+    plaintext: &mut impl std::io::Read,
+    ciphertext: &mut impl std::io::Write,
+    key: &Key,
+    nonce: &[u8],
+    aad: &[u8],
+) -> Result<(usize, Block), String> {
+    check_parameters(key, nonce, aad)?;
+    let mut data = read_all(plaintext, GCM_SIV_MAX_INPUT_SIZE)?;
+    let (auth_key, enc_key) = derive_keys(key, nonce)?;
+
+    let tag = calculate_tag(&auth_key, &enc_key, nonce, aad, &data);
+    ctr_crypt(&mut data, &enc_key, tag);
+
+    let bytes_written = write_data(ciphertext, &data, data.len())?;
+    Ok((bytes_written, tag))
+}
+
+// the plaintext is only written if the authentication tag is valid
+pub fn authenticated_decrypt(
+    ciphertext: &mut impl std::io::Read,
+    plaintext: &mut impl std::io::Write,
+    key: &Key,
+    nonce: &[u8],
+    aad: &[u8],
+    auth_tag: Block,
+) -> Result<usize, String> {
+    check_parameters(key, nonce, aad)?;
+    let mut data = read_all(ciphertext, GCM_SIV_MAX_INPUT_SIZE)?;
+    let (auth_key, enc_key) = derive_keys(key, nonce)?;
+
+    ctr_crypt(&mut data, &enc_key, auth_tag);
+    let tag = calculate_tag(&auth_key, &enc_key, nonce, aad, &data);
+
+    // the computed tag must not be revealed, it would authenticate the ciphertext
+    if !tags_equal(&tag.bytes(), &auth_tag.bytes()) {
+        return Err("authentication failed".to_string());
+    }
+
+    write_data(plaintext, &data, data.len())
+}
+
+// the parameters are checked before the input is read
+fn check_parameters(key: &Key, nonce: &[u8], aad: &[u8]) -> Result<(), String> {
+    if nonce.len() != GCM_SIV_NONCE_SIZE {
+        return Err(format!(
+            "invalid GCM-SIV nonce size {}, expected 12 bytes",
+            nonce.len()
+        ));
+    }
+    if !matches!(key.key_size, KeySize::AES128 | KeySize::AES256) {
+        return Err(format!(
+            "GCM-SIV is only defined for 128 and 256-bit keys, got {:?}",
+            key.key_size
+        ));
+    }
+    if aad.len() as u64 > GCM_SIV_MAX_INPUT_SIZE {
+        return Err(format!(
+            "the AAD is too long ({} bytes), expected at most 2^36 bytes",
+            aad.len()
+        ));
+    }
+
+    Ok(())
+}
+
+// Derive the message authentication key and the message encryption key from the
+// key-generating key (RFC 8452, Section 4): the first 8 bytes of the encryption of
+// LE32(i) | nonce for i = 0, 1 and 2 to 3 (AES-128) or 2 to 5 (AES-256).
+fn derive_keys(key: &Key, nonce: &[u8]) -> Result<(Zeroizing<[u8; BLOCK_SIZE]>, Key), String> {
+    let derived_blocks = 2 + key.key_size.byte_size() / 8;
+    let mut derived = Zeroizing::new(Vec::with_capacity(derived_blocks * 8));
+    for counter in 0..derived_blocks as u32 {
+        let mut input = [0; BLOCK_SIZE];
+        input[..4].copy_from_slice(&counter.to_le_bytes());
+        input[4..].copy_from_slice(nonce);
+
+        let mut output = key.encrypt_block(input.into()).bytes();
+        derived.extend_from_slice(&output[..8]);
+        output.zeroize();
+    }
+
+    let mut auth_key = Zeroizing::new([0; BLOCK_SIZE]);
+    auth_key.copy_from_slice(&derived[..KEY_SIZE_AES128]);
+    let enc_key = Key::try_from(&derived[KEY_SIZE_AES128..])?.with_backend(key.backend());
+
+    Ok((auth_key, enc_key))
+}
+
+// POLYVAL over the padded AAD, the padded plaintext and their bit lengths, XOR'd with the
+// nonce and encrypted with the MSB of the last byte cleared
+fn calculate_tag(
+    auth_key: &[u8; BLOCK_SIZE],
+    enc_key: &Key,
+    nonce: &[u8],
+    aad: &[u8],
+    plaintext: &[u8],
+) -> Block {
+    let mut polyval = Polyval::new(*auth_key, enc_key.backend());
+    polyval.update(aad);
+    polyval.update(plaintext);
+
+    let mut length_block = [0; BLOCK_SIZE];
+    length_block[..8].copy_from_slice(&(aad.len() as u64 * 8).to_le_bytes());
+    length_block[8..].copy_from_slice(&(plaintext.len() as u64 * 8).to_le_bytes());
+    polyval.update(&length_block);
+
+    let mut s_s = Zeroizing::new(polyval.finalize());
+    s_s.iter_mut()
+        .zip(nonce)
+        .for_each(|(byte, nonce_byte)| *byte ^= nonce_byte);
+    s_s[BLOCK_SIZE - 1] &= 0x7f;
+
+    enc_key.encrypt_block((*s_s).into())
+}
+
+// CTR mode with the tag (MSB of the last byte set) as the initial counter block, the
+// first 4 bytes are a little-endian 32-bit counter that wraps around
+fn ctr_crypt(data: &mut [u8], enc_key: &Key, tag: Block) {
+    let mut counter_block = Zeroizing::new(tag.bytes());
+    counter_block[BLOCK_SIZE - 1] |= 0x80;
+    let mut counter = u32::from_le_bytes([
+        counter_block[0],
+        counter_block[1],
+        counter_block[2],
+        counter_block[3],
+    ]);
+
+    let mut keystream: Zeroizing<Vec<Block>> =
+        Zeroizing::new(Vec::with_capacity(STREAM_BUFFER_SIZE / BLOCK_SIZE));
+
+    for chunk in data.chunks_mut(STREAM_BUFFER_SIZE) {
+        keystream.clear();
+        for _ in 0..chunk.len().div_ceil(BLOCK_SIZE) {
+            counter_block[..4].copy_from_slice(&counter.to_le_bytes());
+            keystream.push((*counter_block).into());
+            counter = counter.wrapping_add(1);
+        }
+
+        enc_key.encrypt_blocks(&mut keystream);
+        xor_keystream(chunk, &keystream);
+    }
+}
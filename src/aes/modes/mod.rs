@@ -1,8 +1,14 @@
 pub mod cbc;
+pub mod ccm;
 pub mod cfb;
+pub mod cmac;
 pub mod common;
 pub mod ctr;
 pub mod ecb;
 pub mod gcm;
+pub mod gcm_siv;
 pub mod ofb;
+pub mod parallel;
+pub mod siv;
 mod tests;
+pub mod xts;
@@ -1,18 +1,20 @@
 use crate::aes::{
+    cipher::BlockCipher,
     config::{AESConfig, OperationMode},
     constants::BLOCK_SIZE,
     datastructures::block::Block,
-    modes::common::{encrypt_block, read_data, write_data},
+    modes::common::{read_data, write_data},
+    zeroize::Zeroizing,
 };
 
-pub fn encrypt(
+pub fn encrypt<C: BlockCipher>(
     plaintext: &mut impl std::io::Read,
     ciphertext: &mut impl std::io::Write,
-    config: &AESConfig,
+    config: &AESConfig<C>,
 ) -> Result<usize, String> {
     let iv = ensure_ofb_mode(config)?;
 
-    let mut buf = [0; BLOCK_SIZE];
+    let mut buf = Zeroizing::new([0; BLOCK_SIZE]);
     let mut block_bytes_read;
     let mut total_bytes_written = 0;
 
@@ -23,14 +25,14 @@ pub fn encrypt(
     let mut previous_block = iv;
 
     loop {
-        block_bytes_read = read_data(plaintext, &mut buf)?;
+        block_bytes_read = read_data(plaintext, buf.as_mut_slice())?;
         if block_bytes_read == 0 {
             break;
         }
 
-        plaintext_block = buf.into();
+        plaintext_block = (*buf).into();
         input_block = previous_block;
-        output_block = encrypt_block(input_block, &config.key);
+        output_block = config.key.encrypt_block(input_block);
         ciphertext_block = plaintext_block ^ output_block;
         previous_block = output_block;
 
@@ -40,17 +42,17 @@ pub fn encrypt(
     Ok(total_bytes_written)
 }
 
-pub fn decrypt(
+pub fn decrypt<C: BlockCipher>(
     ciphertext: &mut impl std::io::Read,
     plaintext: &mut impl std::io::Write,
-    config: &AESConfig,
+    config: &AESConfig<C>,
 ) -> Result<usize, String> {
     encrypt(ciphertext, plaintext, config)
 }
 
-fn ensure_ofb_mode(config: &AESConfig) -> Result<Block, String> {
+fn ensure_ofb_mode<C: BlockCipher>(config: &AESConfig<C>) -> Result<Block, String> {
     match config.mode {
-        OperationMode::OFB { iv } => Ok(iv.into()),
+        OperationMode::OFB { ref iv } => Ok((*iv).into()),
         _ => Err(format!(
             "Invalid operation mode, expected OFB, got {:?}",
             config.mode
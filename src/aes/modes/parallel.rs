@@ -0,0 +1,312 @@
+use crate::aes::{
+    cipher::BlockCipher,
+    config::{AESConfig, OperationMode},
+    constants::{BLOCK_SIZE, PARALLEL_CHUNK_SIZE},
+    datastructures::block::Block,
+    modes::common::{
+        load_blocks, pad_buffer, read_data, store_blocks, unpad_block, write_data, xor_keystream,
+        Counter,
+    },
+    zeroize::Zeroizing,
+};
+use std::{
+    collections::BTreeMap,
+    sync::{mpsc, Mutex},
+};
+
+// a part of the input, transformed by one of the worker threads
+struct Chunk {
+    index: usize,
+    data: Zeroizing<Vec<u8>>,
+    // the ciphertext block preceding the chunk (the IV for the first chunk), used by CBC mode
+    previous_block: Block,
+    last: bool,
+}
+
+impl Chunk {
+    // position of the chunk's first block in the whole stream
+    fn block_offset(&self) -> usize {
+        self.index * (PARALLEL_CHUNK_SIZE / BLOCK_SIZE)
+    }
+}
+
+type ChunkResult = (usize, Result<Zeroizing<Vec<u8>>, String>);
+
+// only ECB and CTR mode can be encrypted in parallel
+pub fn encrypt<C: BlockCipher + Sync>(
+    plaintext: &mut impl std::io::Read,
+    ciphertext: &mut impl std::io::Write,
+    config: &AESConfig<C>,
+) -> Result<usize, String> {
+    match config.mode {
+        OperationMode::ECB => process(plaintext, ciphertext, config, false, ecb_encrypt),
+        OperationMode::CTR { iv: _ } => process(plaintext, ciphertext, config, false, ctr_apply),
+        _ => Err(format!(
+            "Invalid operation mode, expected ECB or CTR, got {:?}",
+            config.mode
+        )),
+    }
+}
+
+// ECB, CBC and CTR mode can be decrypted in parallel
+pub fn decrypt<C: BlockCipher + Sync>(
+    ciphertext: &mut impl std::io::Read,
+    plaintext: &mut impl std::io::Write,
+    config: &AESConfig<C>,
+) -> Result<usize, String> {
+    match config.mode {
+        OperationMode::ECB => process(ciphertext, plaintext, config, true, ecb_decrypt),
+        OperationMode::CBC { iv: _ } => process(ciphertext, plaintext, config, true, cbc_decrypt),
+        OperationMode::CTR { iv: _ } => process(ciphertext, plaintext, config, false, ctr_apply),
+        _ => Err(format!(
+            "Invalid operation mode, expected ECB, CBC or CTR, got {:?}",
+            config.mode
+        )),
+    }
+}
+
+// Split the input into chunks and transform them on a pool of worker threads. The calling
+// thread reads the chunks and writes the results in their original order. At most two chunks
+// per thread are in flight, so the memory usage does not depend on the input size.
+fn process<C: BlockCipher + Sync>(
+    src: &mut impl std::io::Read,
+    dst: &mut impl std::io::Write,
+    config: &AESConfig<C>,
+    unpad: bool,
+    transform: fn(&mut Chunk, &AESConfig<C>) -> Result<(), String>,
+) -> Result<usize, String> {
+    let threads = config.threads.max(1);
+    let (chunk_tx, chunk_rx) = mpsc::channel::<Chunk>();
+    let chunk_rx = Mutex::new(chunk_rx);
+
+    std::thread::scope(|scope| {
+        // owned by the closure, so the workers also stop when an error is returned early
+        let chunk_tx = chunk_tx;
+        let (result_tx, result_rx) = mpsc::channel::<ChunkResult>();
+
+        for _ in 0..threads {
+            let chunk_rx = &chunk_rx;
+            let result_tx = result_tx.clone();
+
+            scope.spawn(move || {
+                // the channel is closed once all chunks have been read (or an error occurred)
+                while let Some(mut chunk) = receive_chunk(chunk_rx) {
+                    let result = transform(&mut chunk, config).map(|_| chunk.data);
+                    if result_tx.send((chunk.index, result)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(result_tx);
+
+        let mut writer = OrderedWriter::new(dst, unpad);
+        let mut previous_block = initial_block(config);
+        let mut chunks_sent = 0;
+        let mut last = false;
+
+        while !last {
+            // a slow chunk holds back the output of all later ones, keep receiving until
+            // there's room for another chunk
+            while chunks_sent - writer.next_index >= 2 * threads {
+                writer.receive(&result_rx)?;
+            }
+            debug_assert!(writer.pending.len() < 2 * threads);
+
+            let mut data = Zeroizing::new(vec![0; PARALLEL_CHUNK_SIZE]);
+            let bytes_read = read_data(src, &mut data)?;
+            data.truncate(bytes_read);
+            last = bytes_read != PARALLEL_CHUNK_SIZE;
+
+            let chunk = Chunk {
+                index: chunks_sent,
+                previous_block,
+                last,
+                data,
+            };
+            // a full chunk always ends on a block boundary
+            if !last {
+                previous_block = Block::try_from(&chunk.data[PARALLEL_CHUNK_SIZE - BLOCK_SIZE..])?;
+            }
+
+            chunk_tx
+                .send(chunk)
+                .map_err(|_| "the worker threads stopped unexpectedly".to_string())?;
+            chunks_sent += 1;
+        }
+        drop(chunk_tx);
+
+        while writer.next_index < chunks_sent {
+            writer.receive(&result_rx)?;
+        }
+
+        writer.finish()
+    })
+}
+
+fn receive_chunk(chunk_rx: &Mutex<mpsc::Receiver<Chunk>>) -> Option<Chunk> {
+    chunk_rx.lock().ok()?.recv().ok()
+}
+
+fn initial_block<C: BlockCipher>(config: &AESConfig<C>) -> Block {
+    match config.mode {
+        OperationMode::CBC { ref iv } => (*iv).into(),
+        _ => Block::default(),
+    }
+}
+
+// =================================================================
+//                     chunk transformations
+// =================================================================
+
+fn ecb_encrypt<C: BlockCipher>(chunk: &mut Chunk, config: &AESConfig<C>) -> Result<(), String> {
+    let full_blocks_len = chunk.data.len() - chunk.data.len() % BLOCK_SIZE;
+
+    // the remaining bytes (possibly none) of the last chunk are padded to a full block
+    if chunk.last {
+        let mut last_block_data = Zeroizing::new([0; BLOCK_SIZE]);
+        last_block_data[..chunk.data.len() - full_blocks_len]
+            .copy_from_slice(&chunk.data[full_blocks_len..]);
+        let padded = pad_buffer(*last_block_data, chunk.data.len() - full_blocks_len);
+
+        chunk.data.truncate(full_blocks_len);
+        chunk.data.extend(padded.bytes());
+    }
+
+    let mut blocks = Zeroizing::new(Vec::with_capacity(chunk.data.len() / BLOCK_SIZE));
+    load_blocks(&chunk.data, &mut blocks);
+    config.key.encrypt_blocks(&mut blocks);
+    store_blocks(&blocks, &mut chunk.data);
+
+    Ok(())
+}
+
+fn ecb_decrypt<C: BlockCipher>(chunk: &mut Chunk, config: &AESConfig<C>) -> Result<(), String> {
+    ensure_full_blocks(chunk)?;
+
+    let mut blocks = Zeroizing::new(Vec::with_capacity(chunk.data.len() / BLOCK_SIZE));
+    load_blocks(&chunk.data, &mut blocks);
+    config.key.decrypt_blocks(&mut blocks);
+    store_blocks(&blocks, &mut chunk.data);
+
+    Ok(())
+}
+
+fn cbc_decrypt<C: BlockCipher>(chunk: &mut Chunk, config: &AESConfig<C>) -> Result<(), String> {
+    ensure_full_blocks(chunk)?;
+
+    let mut ciphertext_blocks = Vec::with_capacity(chunk.data.len() / BLOCK_SIZE);
+    load_blocks(&chunk.data, &mut ciphertext_blocks);
+    let mut plaintext_blocks = Zeroizing::new(ciphertext_blocks.clone());
+    config.key.decrypt_blocks(&mut plaintext_blocks);
+
+    let mut previous_block = chunk.previous_block;
+    for (output_block, ciphertext_block) in plaintext_blocks.iter_mut().zip(ciphertext_blocks) {
+        *output_block ^= previous_block;
+        previous_block = ciphertext_block;
+    }
+    store_blocks(&plaintext_blocks, &mut chunk.data);
+
+    Ok(())
+}
+
+// encryption and decryption are the same operation, every chunk starts its own counter
+fn ctr_apply<C: BlockCipher>(chunk: &mut Chunk, config: &AESConfig<C>) -> Result<(), String> {
+    let OperationMode::CTR { ref iv } = config.mode else {
+        return Err(format!(
+            "Invalid operation mode, expected CTR, got {:?}",
+            config.mode
+        ));
+    };
+
+    let mut ctr = Counter::new((*iv).into());
+    ctr.skip(chunk.block_offset());
+
+    let mut output_blocks =
+        Zeroizing::new(Vec::with_capacity(chunk.data.len().div_ceil(BLOCK_SIZE)));
+    ctr.keystream(chunk.data.len(), &mut output_blocks, &config.key);
+    xor_keystream(&mut chunk.data, &output_blocks);
+
+    Ok(())
+}
+
+fn ensure_full_blocks(chunk: &Chunk) -> Result<(), String> {
+    match chunk.data.len() % BLOCK_SIZE {
+        0 => Ok(()),
+        remainder => Err(format!(
+            "invalid ciphertext length, the last block was {} long, expected 16 (block size)",
+            remainder
+        )),
+    }
+}
+
+// =================================================================
+//                        ordered output
+// =================================================================
+
+// Writes the transformed chunks in their original order. With padding, the last block of the
+// output is held back until the end of the stream, so the padding can be removed from it.
+struct OrderedWriter<'a, W: std::io::Write> {
+    dst: &'a mut W,
+    unpad: bool,
+    next_index: usize,
+    pending: BTreeMap<usize, Zeroizing<Vec<u8>>>,
+    last_block: Zeroizing<Option<Block>>,
+    total_bytes_written: usize,
+}
+
+impl<'a, W: std::io::Write> OrderedWriter<'a, W> {
+    fn new(dst: &'a mut W, unpad: bool) -> Self {
+        Self {
+            dst,
+            unpad,
+            next_index: 0,
+            pending: BTreeMap::new(),
+            last_block: Zeroizing::new(None),
+            total_bytes_written: 0,
+        }
+    }
+
+    // wait for the next result of a worker thread and write every chunk that is now in order
+    fn receive(&mut self, result_rx: &mpsc::Receiver<ChunkResult>) -> Result<(), String> {
+        let (index, result) = result_rx
+            .recv()
+            .map_err(|_| "the worker threads stopped unexpectedly".to_string())?;
+        self.pending.insert(index, result?);
+
+        while let Some(data) = self.pending.remove(&self.next_index) {
+            self.write(&data)?;
+            self.next_index += 1;
+        }
+
+        Ok(())
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<(), String> {
+        if !self.unpad || data.is_empty() {
+            self.total_bytes_written += write_data(self.dst, data, data.len())?;
+            return Ok(());
+        }
+
+        if let Some(block) = self.last_block.take() {
+            self.total_bytes_written += write_data(self.dst, &block.bytes(), BLOCK_SIZE)?;
+        }
+        let (data, last_block) = data.split_at(data.len() - BLOCK_SIZE);
+        self.total_bytes_written += write_data(self.dst, data, data.len())?;
+        *self.last_block = Some(Block::try_from(last_block)?);
+
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<usize, String> {
+        if self.unpad {
+            let last_block = self
+                .last_block
+                .ok_or("couldn't fetch a block from the write queue".to_string())?;
+            let unpadded = Zeroizing::new(unpad_block(last_block)?);
+            self.total_bytes_written += write_data(self.dst, &unpadded, unpadded.len())?;
+        }
+
+        Ok(self.total_bytes_written)
+    }
+}
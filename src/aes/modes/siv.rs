@@ -0,0 +1,104 @@
+use crate::aes::{
+    cipher::BlockCipher,
+    config::KeyPair,
+    constants::{BLOCK_SIZE, SIV_MAX_AAD_COMPONENTS, SIV_MAX_INPUT_SIZE, STREAM_BUFFER_SIZE},
+    datastructures::block::Block,
+    modes::{
+        cmac::{cmac, dbl, Cmac},
+        common::{read_all, tags_equal, write_data, xor_keystream, Counter},
+    },
+    zeroize::Zeroizing,
+};
+
+// AES-SIV (RFC 5297): the synthetic IV is computed with S2V over the AAD components and
+// the plaintext, it serves as the authentication tag and as the initial counter block.
+// The encryption is deterministic, a nonce can be passed as the last AAD component.
+// The whole input is read before anything is written.
+pub fn authenticated_encrypt<C: BlockCipher>(
+    plaintext: &mut impl std::io::Read,
+    ciphertext: &mut impl std::io::Write,
+    key: &KeyPair<C>,
+    aad: &[&[u8]],
+) -> Result<(usize, Block), String> {
+    let mut data = read_all(plaintext, SIV_MAX_INPUT_SIZE)?;
+    check_aad(aad)?;
+
+    let siv = s2v(&key.first, aad, &data);
+    ctr_crypt(&mut data, &key.second, siv);
+
+    let bytes_written = write_data(ciphertext, &data, data.len())?;
+    Ok((bytes_written, siv))
+}
+
+// the plaintext is only written if the synthetic IV is valid
+pub fn authenticated_decrypt<C: BlockCipher>(
+    ciphertext: &mut impl std::io::Read,
+    plaintext: &mut impl std::io::Write,
+    key: &KeyPair<C>,
+    aad: &[&[u8]],
+    siv: Block,
+) -> Result<usize, String> {
+    let mut data = read_all(ciphertext, SIV_MAX_INPUT_SIZE)?;
+    check_aad(aad)?;
+
+    ctr_crypt(&mut data, &key.second, siv);
+    let expected_siv = s2v(&key.first, aad, &data);
+
+    // the computed synthetic IV must not be revealed, it would authenticate the ciphertext
+    if !tags_equal(&expected_siv.bytes(), &siv.bytes()) {
+        return Err("authentication failed".to_string());
+    }
+
+    write_data(plaintext, &data, data.len())
+}
+
+fn check_aad(aad: &[&[u8]]) -> Result<(), String> {
+    if aad.len() > SIV_MAX_AAD_COMPONENTS {
+        return Err(format!(
+            "too many AAD components: {}, expected at most {}",
+            aad.len(),
+            SIV_MAX_AAD_COMPONENTS
+        ));
+    }
+
+    Ok(())
+}
+
+// as described in RFC 5297, section 2.4. The plaintext is always the last string, so
+// the special case for zero strings doesn't apply
+pub fn s2v(mac_key: &impl BlockCipher, aad: &[&[u8]], plaintext: &[u8]) -> Block {
+    let mut d = cmac(mac_key, &[0; BLOCK_SIZE]);
+    for component in aad {
+        d = dbl(d) ^ cmac(mac_key, component);
+    }
+
+    let mut mac = Cmac::new(mac_key);
+    if plaintext.len() >= BLOCK_SIZE {
+        // xorend: D is XOR'd into the last 16 bytes of the plaintext
+        let (head, tail) = plaintext.split_at(plaintext.len() - BLOCK_SIZE);
+        mac.update(head);
+        mac.update(&(d ^ tail).bytes());
+    } else {
+        let mut padded = Zeroizing::new([0; BLOCK_SIZE]);
+        padded[..plaintext.len()].copy_from_slice(plaintext);
+        padded[plaintext.len()] = 0x80;
+        mac.update(&(dbl(d) ^ Block::from(*padded)).bytes());
+    }
+
+    mac.finalize()
+}
+
+// CTR mode starting at the synthetic IV with bits 31 and 63 cleared, so implementations
+// with 64-bit counter arithmetic don't need to handle a carry
+fn ctr_crypt(data: &mut [u8], ctr_key: &impl BlockCipher, siv: Block) {
+    let q = u128::from(siv) & !(1 << 63 | 1 << 31);
+    let mut counter = Counter::new(Block::new(q));
+
+    let mut keystream: Zeroizing<Vec<Block>> =
+        Zeroizing::new(Vec::with_capacity(STREAM_BUFFER_SIZE / BLOCK_SIZE));
+
+    for chunk in data.chunks_mut(STREAM_BUFFER_SIZE) {
+        counter.keystream(chunk.len(), &mut keystream, ctr_key);
+        xor_keystream(chunk, &keystream);
+    }
+}
@@ -1,11 +1,31 @@
 #[cfg(test)]
 mod test {
     use crate::aes::{
-        config::{AESConfig, CFBSegmentSize, OperationMode},
-        constants::BLOCK_SIZE,
+        backend::Backend,
+        cipher::BlockCipher,
+        config::{AESConfig, CFBSegmentSize, KeyPair, OperationMode},
+        constants::{BLOCK_SIZE, PARALLEL_CHUNK_SIZE, STREAM_BUFFER_SIZE},
+        datastructures::block::Block,
+        encoding::hex_decode,
         key::{size::KeySize, Key},
-        modes::{cbc, cfb, ctr, ecb, gcm, ofb},
+        modes::{
+            cbc, ccm, cfb, cmac,
+            common::{encrypt_block, read_all, tags_equal},
+            ctr, ecb, gcm, gcm_siv, ofb, parallel, siv, xts,
+        },
     };
+    use std::{
+        cell::RefCell,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    // every backend has to reproduce the same test vectors
+    const BACKENDS: [Backend; 4] = [
+        Backend::ColMatrix,
+        Backend::TTable,
+        Backend::Bitsliced,
+        Backend::AesNi,
+    ];
 
     #[test]
     fn test_aes128_ecb() {
@@ -57,18 +77,31 @@ mod test {
 
     fn run_ecb(expected: Vec<u8>, key: Key) {
         let plaintext = get_nist_test_plaintext();
-        let config = AESConfig::new(key, OperationMode::ECB);
+        for backend in BACKENDS {
+            let key = key.clone().with_backend(backend);
+            let config = AESConfig::new(key, OperationMode::ECB);
 
-        let mut ciphertext = Vec::new();
-        ecb::encrypt(&mut plaintext.as_slice(), &mut ciphertext, &config).unwrap();
+            let mut ciphertext = Vec::new();
+            ecb::encrypt(&mut plaintext.as_slice(), &mut ciphertext, &config).unwrap();
 
-        let mut ciphertext_no_padding = ciphertext.clone();
-        ciphertext_no_padding.truncate(ciphertext_no_padding.len() - 16);
-        assert_eq!(ciphertext_no_padding, expected);
+            let mut ciphertext_no_padding = ciphertext.clone();
+            ciphertext_no_padding.truncate(ciphertext_no_padding.len() - 16);
+            assert_eq!(ciphertext_no_padding, expected);
 
-        let mut decrypted = Vec::new();
-        ecb::decrypt(&mut ciphertext.as_slice(), &mut decrypted, &config).unwrap();
+            let mut decrypted = Vec::new();
+            ecb::decrypt(&mut ciphertext.as_slice(), &mut decrypted, &config).unwrap();
 
+            assert_eq!(decrypted, plaintext);
+        }
+
+        // the equivalent inverse cipher decrypts the vectors as well
+        let decrypted: Vec<u8> = expected
+            .chunks(BLOCK_SIZE)
+            .flat_map(|chunk| {
+                key.equivalent_decrypt_block(chunk.try_into().unwrap())
+                    .bytes()
+            })
+            .collect();
         assert_eq!(decrypted, plaintext);
     }
 
@@ -123,17 +156,31 @@ mod test {
     fn run_cbc(expected: Vec<u8>, key: Key) {
         let plaintext = get_nist_test_plaintext();
         let iv = get_nist_test_iv();
-        let config = AESConfig::new(key, OperationMode::CBC { iv });
+        for backend in BACKENDS {
+            let key = key.clone().with_backend(backend);
+            let config = AESConfig::new(key, OperationMode::CBC { iv });
 
-        let mut ciphertext = Vec::new();
-        cbc::encrypt(&mut plaintext.as_slice(), &mut ciphertext, &config).unwrap();
+            let mut ciphertext = Vec::new();
+            cbc::encrypt(&mut plaintext.as_slice(), &mut ciphertext, &config).unwrap();
 
-        let mut ciphertext_no_padding = ciphertext.clone();
-        ciphertext_no_padding.truncate(ciphertext_no_padding.len() - 16);
-        assert_eq!(ciphertext_no_padding, expected);
+            let mut ciphertext_no_padding = ciphertext.clone();
+            ciphertext_no_padding.truncate(ciphertext_no_padding.len() - 16);
+            assert_eq!(ciphertext_no_padding, expected);
 
+            let mut decrypted = Vec::new();
+            cbc::decrypt(&mut ciphertext.as_slice(), &mut decrypted, &config).unwrap();
+            assert_eq!(decrypted, plaintext);
+        }
+
+        // the equivalent inverse cipher decrypts the vectors as well
+        let mut previous_block = Block::from(iv);
         let mut decrypted = Vec::new();
-        cbc::decrypt(&mut ciphertext.as_slice(), &mut decrypted, &config).unwrap();
+        for chunk in expected.chunks(BLOCK_SIZE) {
+            let ciphertext_block = Block::try_from(chunk).unwrap();
+            decrypted
+                .extend((key.equivalent_decrypt_block(ciphertext_block) ^ previous_block).bytes());
+            previous_block = ciphertext_block;
+        }
         assert_eq!(decrypted, plaintext);
     }
 
@@ -191,15 +238,18 @@ mod test {
     fn run_ofb(expected: Vec<u8>, key: Key) {
         let plaintext = get_nist_test_plaintext();
         let iv = get_nist_test_iv();
-        let config = AESConfig::new(key, OperationMode::OFB { iv });
+        for backend in BACKENDS {
+            let key = key.clone().with_backend(backend);
+            let config = AESConfig::new(key, OperationMode::OFB { iv });
 
-        let mut ciphertext = Vec::new();
-        ofb::encrypt(&mut plaintext.as_slice(), &mut ciphertext, &config).unwrap();
-        assert_eq!(ciphertext, expected);
+            let mut ciphertext = Vec::new();
+            ofb::encrypt(&mut plaintext.as_slice(), &mut ciphertext, &config).unwrap();
+            assert_eq!(ciphertext, expected);
 
-        let mut decrypted = Vec::new();
-        ofb::decrypt(&mut ciphertext.as_slice(), &mut decrypted, &config).unwrap();
-        assert_eq!(decrypted, plaintext);
+            let mut decrypted = Vec::new();
+            ofb::decrypt(&mut ciphertext.as_slice(), &mut decrypted, &config).unwrap();
+            assert_eq!(decrypted, plaintext);
+        }
     }
 
     fn run_partial_ofb(mut expected: Vec<u8>, key: Key) {
@@ -210,15 +260,18 @@ mod test {
         expected.pop();
 
         let iv = get_nist_test_iv();
-        let config = AESConfig::new(key, OperationMode::OFB { iv });
+        for backend in BACKENDS {
+            let key = key.clone().with_backend(backend);
+            let config = AESConfig::new(key, OperationMode::OFB { iv });
 
-        let mut ciphertext = Vec::new();
-        ofb::encrypt(&mut plaintext.as_slice(), &mut ciphertext, &config).unwrap();
-        assert_eq!(ciphertext, expected);
+            let mut ciphertext = Vec::new();
+            ofb::encrypt(&mut plaintext.as_slice(), &mut ciphertext, &config).unwrap();
+            assert_eq!(ciphertext, expected);
 
-        let mut decrypted = Vec::new();
-        ofb::decrypt(&mut ciphertext.as_slice(), &mut decrypted, &config).unwrap();
-        assert_eq!(decrypted, plaintext);
+            let mut decrypted = Vec::new();
+            ofb::decrypt(&mut ciphertext.as_slice(), &mut decrypted, &config).unwrap();
+            assert_eq!(decrypted, plaintext);
+        }
     }
 
     #[test]
@@ -275,21 +328,24 @@ mod test {
     fn run_cfb_128(expected: Vec<u8>, key: Key) {
         let plaintext = get_nist_test_plaintext();
         let iv = get_nist_test_iv();
-        let config = AESConfig::new(
-            key,
-            OperationMode::CFB {
-                iv,
-                seg_size: CFBSegmentSize::Bit128,
-            },
-        );
-
-        let mut ciphertext = Vec::new();
-        cfb::encrypt(&mut plaintext.as_slice(), &mut ciphertext, &config).unwrap();
-        assert_eq!(ciphertext, expected);
-
-        let mut decrypted = Vec::new();
-        cfb::decrypt(&mut ciphertext.as_slice(), &mut decrypted, &config).unwrap();
-        assert_eq!(decrypted, plaintext);
+        for backend in BACKENDS {
+            let key = key.clone().with_backend(backend);
+            let config = AESConfig::new(
+                key,
+                OperationMode::CFB {
+                    iv,
+                    seg_size: CFBSegmentSize::Bit128,
+                },
+            );
+
+            let mut ciphertext = Vec::new();
+            cfb::encrypt(&mut plaintext.as_slice(), &mut ciphertext, &config).unwrap();
+            assert_eq!(ciphertext, expected);
+
+            let mut decrypted = Vec::new();
+            cfb::decrypt(&mut ciphertext.as_slice(), &mut decrypted, &config).unwrap();
+            assert_eq!(decrypted, plaintext);
+        }
     }
 
     fn run_partial_cfb_128(mut expected: Vec<u8>, key: Key) {
@@ -300,30 +356,30 @@ mod test {
         expected.pop();
 
         let iv = get_nist_test_iv();
-        let config = AESConfig::new(
-            key,
-            OperationMode::CFB {
-                iv,
-                seg_size: CFBSegmentSize::Bit128,
-            },
-        );
-
-        println!("{}", plaintext.as_slice().len());
-
-        let mut ciphertext = Vec::new();
-        cfb::encrypt(&mut plaintext.as_slice(), &mut ciphertext, &config).unwrap();
-        assert_eq!(ciphertext, expected);
-
-        let mut decrypted = Vec::new();
-        cfb::decrypt(&mut ciphertext.as_slice(), &mut decrypted, &config).unwrap();
-        assert_eq!(decrypted, plaintext);
+        for backend in BACKENDS {
+            let key = key.clone().with_backend(backend);
+            let config = AESConfig::new(
+                key,
+                OperationMode::CFB {
+                    iv,
+                    seg_size: CFBSegmentSize::Bit128,
+                },
+            );
+
+            let mut ciphertext = Vec::new();
+            cfb::encrypt(&mut plaintext.as_slice(), &mut ciphertext, &config).unwrap();
+            assert_eq!(ciphertext, expected);
+
+            let mut decrypted = Vec::new();
+            cfb::decrypt(&mut ciphertext.as_slice(), &mut decrypted, &config).unwrap();
+            assert_eq!(decrypted, plaintext);
+        }
     }
 
     #[test]
     fn test_aes128_cfb_8() {
         let key = get_nist_test_key_128();
-        let expected_ciphertext =
-            string_to_vec(concat!("3B79424C 9C0DD436 BACE9E0E D4586A4F").to_string());
+        let expected_ciphertext = string_to_vec("3B79424C 9C0DD436 BACE9E0E D4586A4F".to_string());
 
         run_cfb_8(expected_ciphertext.clone(), key.clone());
         run_partial_cfb_8(expected_ciphertext, key);
@@ -332,8 +388,7 @@ mod test {
     #[test]
     fn test_aes192_cfb_8() {
         let key = get_nist_test_key_192();
-        let expected_ciphertext =
-            string_to_vec(concat!("CDA2521E F0A905CA 44CD057C BF0D47A0").to_string());
+        let expected_ciphertext = string_to_vec("CDA2521E F0A905CA 44CD057C BF0D47A0".to_string());
 
         run_cfb_8(expected_ciphertext.clone(), key.clone());
         run_partial_cfb_8(expected_ciphertext, key);
@@ -342,8 +397,7 @@ mod test {
     #[test]
     fn test_aes256_cfb_8() {
         let key = get_nist_test_key_256();
-        let expected_ciphertext =
-            string_to_vec(concat!("DC1F1A85 20A64DB5 5FCC8AC5 54844E88").to_string());
+        let expected_ciphertext = string_to_vec("DC1F1A85 20A64DB5 5FCC8AC5 54844E88".to_string());
 
         run_cfb_8(expected_ciphertext.clone(), key.clone());
         run_partial_cfb_8(expected_ciphertext, key);
@@ -354,21 +408,24 @@ mod test {
         plaintext.truncate(expected.len());
 
         let iv = get_nist_test_iv();
-        let config = AESConfig::new(
-            key,
-            OperationMode::CFB {
-                iv,
-                seg_size: CFBSegmentSize::Bit8,
-            },
-        );
-
-        let mut ciphertext = Vec::new();
-        cfb::encrypt(&mut plaintext.as_slice(), &mut ciphertext, &config).unwrap();
-        assert_eq!(ciphertext, expected);
-
-        let mut decrypted = Vec::new();
-        cfb::decrypt(&mut ciphertext.as_slice(), &mut decrypted, &config).unwrap();
-        assert_eq!(decrypted, plaintext);
+        for backend in BACKENDS {
+            let key = key.clone().with_backend(backend);
+            let config = AESConfig::new(
+                key,
+                OperationMode::CFB {
+                    iv,
+                    seg_size: CFBSegmentSize::Bit8,
+                },
+            );
+
+            let mut ciphertext = Vec::new();
+            cfb::encrypt(&mut plaintext.as_slice(), &mut ciphertext, &config).unwrap();
+            assert_eq!(ciphertext, expected);
+
+            let mut decrypted = Vec::new();
+            cfb::decrypt(&mut ciphertext.as_slice(), &mut decrypted, &config).unwrap();
+            assert_eq!(decrypted, plaintext);
+        }
     }
 
     fn run_partial_cfb_8(mut expected: Vec<u8>, key: Key) {
@@ -377,21 +434,336 @@ mod test {
         expected.pop();
 
         let iv = get_nist_test_iv();
-        let config = AESConfig::new(
-            key,
-            OperationMode::CFB {
-                iv,
-                seg_size: CFBSegmentSize::Bit8,
-            },
+        for backend in BACKENDS {
+            let key = key.clone().with_backend(backend);
+            let config = AESConfig::new(
+                key,
+                OperationMode::CFB {
+                    iv,
+                    seg_size: CFBSegmentSize::Bit8,
+                },
+            );
+
+            let mut ciphertext = Vec::new();
+            cfb::encrypt(&mut plaintext.as_slice(), &mut ciphertext, &config).unwrap();
+            assert_eq!(ciphertext, expected);
+
+            let mut decrypted = Vec::new();
+            cfb::decrypt(&mut ciphertext.as_slice(), &mut decrypted, &config).unwrap();
+            assert_eq!(decrypted, plaintext);
+        }
+    }
+
+    // SP 800-38A F.3.1, F.3.3 and F.3.5, the 16 bits of the CFB1 examples are 2 bytes
+    #[test]
+    fn test_aes128_cfb_1() {
+        let key = get_nist_test_key_128();
+        run_cfb_segments(string_to_vec("68B3".to_string()), key, CFBSegmentSize::Bit1);
+    }
+
+    #[test]
+    fn test_aes192_cfb_1() {
+        let key = get_nist_test_key_192();
+        run_cfb_segments(string_to_vec("9359".to_string()), key, CFBSegmentSize::Bit1);
+    }
+
+    #[test]
+    fn test_aes256_cfb_1() {
+        let key = get_nist_test_key_256();
+        run_cfb_segments(string_to_vec("9029".to_string()), key, CFBSegmentSize::Bit1);
+    }
+
+    // No standard covers s = 7, 12, 64 and 100 (SP 800-38A only has examples for s = 1, 8
+    // and 128). The vectors were computed with an independent bit-level CFB written in Python
+    // on top of AES-ECB from the `cryptography` package. It reproduces the SP 800-38A F.3
+    // CFB1, CFB8 and CFB128 examples and agrees with the package's own CFB8 and CFB modes.
+    #[test]
+    fn test_cfb_segment_sizes() {
+        let test_cases = [
+            (
+                7,
+                get_nist_test_key_128(),
+                concat!(
+                    "3AE5A199 983FCF3C 11424BD0 37A157C4 2B8943F4 77EBE5E8 2CE9D305 804BDBA0",
+                    "FD220744 F1A149E5 F71E1326 DEE4DEA1 25E9849E 4FDF5400 6F2CA350 5F83CF6B",
+                ),
+            ),
+            (
+                7,
+                get_nist_test_key_192(),
+                concat!(
+                    "CCAE8C8F ED42302F 5EFBD656 FDC7F966 33087867 F26F1F7F 6C97D16B 0F8C8350",
+                    "1C5A9DCE 2F8A7AEC 5423A384 35FEC743 A1FCCA50 BEB7B6D0 9A6C9DF4 977E8E6B",
+                ),
+            ),
+            (
+                7,
+                get_nist_test_key_256(),
+                concat!(
+                    "DCA6B654 02AD0B3B 19865D24 1B3E94A4 BA0509A9 D74415BC 1007E568 43BB688B",
+                    "A2FDFBF8 EF42B2E6 521E6139 30FCFBA4 5052C936 D28A097D 302209D0 522234DF",
+                ),
+            ),
+            (
+                12,
+                get_nist_test_key_128(),
+                concat!(
+                    "3B3F19E9 F2D7D2C6 0084B11D 173954F1 43445850 6AA7A5D3 77F34E91 785A1117",
+                    "D63AA4EE A98D5190 56C8FC76 590670AE 223826EF EDA8489F 0B71A500 2E3AC845",
+                ),
+            ),
+            (
+                12,
+                get_nist_test_key_192(),
+                concat!(
+                    "CDC3CC75 4B606864 97772754 7EB5A46B 7C48F993 16934FD3 CCCFD795 C2485AC4",
+                    "A5B466C0 16E8A6EB DB034612 18239B92 EA98DA69 B0269C1A DAC0DEE5 6C93D045",
+                ),
+            ),
+            (
+                12,
+                get_nist_test_key_256(),
+                concat!(
+                    "DC7B0299 469BDBA9 DEF4064E 10A2B816 3E883084 C1AA29ED 10F8EB7D 64C57864",
+                    "980D9255 EC20504C C85B6625 AF318FD0 CF761E47 B0E113CB B7AE9C3A A5A51786",
+                ),
+            ),
+            (
+                64,
+                get_nist_test_key_128(),
+                concat!(
+                    "3B3FD92E B72DAD20 764BC8B4 0EE0DE40 F857AB76 F3E7BC33 332265FF 0594B12E",
+                    "6C8BF2F3 FC1BA87B 2F124A56 F7FE88D2 341F1D05 35F0D56E 58287BBE C2952B2A",
+                ),
+            ),
+            (
+                64,
+                get_nist_test_key_192(),
+                concat!(
+                    "CDC80D6F DDF18CAB DD77D693 B7F9AC00 26A3AD7D EC1FD440 1BC8C7EC 98D09BE9",
+                    "E0F7D120 11D06AD9 1775E17E 15321703 A3227525 EDDF0579 61A7F86C 183FEFEC",
+                ),
+            ),
+            (
+                64,
+                get_nist_test_key_256(),
+                concat!(
+                    "DC7E84BF DA79164B 5354B112 8A039EC7 506B65DA 6782CDFA 2EB7F571 1565FC14",
+                    "19345A7D 5EED1880 8BE1D386 4AE3E0DC F435AE89 1B303283 4EE359D4 0E86AF01",
+                ),
+            ),
+            (
+                100,
+                get_nist_test_key_128(),
+                concat!(
+                    "3B3FD92E B72DAD20 333449F8 EC52B6DA 6036FD2B C3448984 24B0A87E E6B247AE",
+                    "C4F9E111 902F4116 68DC94A3 2062B7C3 3C2A0665 478C19F5 1B50B5DD 709AACF1",
+                ),
+            ),
+            (
+                100,
+                get_nist_test_key_192(),
+                concat!(
+                    "CDC80D6F DDF18CAB 34C25909 C9E1D8D0 E218A2CC 8BFE0737 EA9B8DE4 7658268B",
+                    "F9FA3E31 DFB487E7 F10B3656 B2CB6576 9AE56C98 A1A35B77 568EC77E 9E1B9739",
+                ),
+            ),
+            (
+                100,
+                get_nist_test_key_256(),
+                concat!(
+                    "DC7E84BF DA79164B 7ECD8486 9235536F BA127574 30DC8A06 9BADBDC3 870FF17F",
+                    "38FA01DB E7588B07 88B92655 34D91F9B A3D954D5 08367E07 F1DBB281 8A18128A",
+                ),
+            ),
+        ];
+
+        for (bits, key, expected_ciphertext) in test_cases {
+            let expected_ciphertext = string_to_vec(expected_ciphertext.to_string());
+            run_cfb_segments(expected_ciphertext, key, CFBSegmentSize::Bits(bits));
+        }
+
+        // the named segment sizes are the same as the corresponding Bits
+        let expected_ciphertext = string_to_vec("3B79424C 9C0DD436 BACE9E0E D4586A4F".to_string());
+        run_cfb_segments(
+            expected_ciphertext,
+            get_nist_test_key_128(),
+            CFBSegmentSize::Bits(8),
+        );
+        let expected_ciphertext = string_to_vec("3B3FD92E B72DAD20 333449F8 E83CFB4A".to_string());
+        run_cfb_segments(
+            expected_ciphertext,
+            get_nist_test_key_128(),
+            CFBSegmentSize::Bits(128),
+        );
+    }
+
+    #[test]
+    fn test_partial_cfb_segments() {
+        // the last segment is shorter than s, 37 bytes are 2 segments of 100 bits and 96 bits
+        let expected_ciphertext = string_to_vec(
+            concat!(
+                "3B3FD92E B72DAD20 333449F8 EC52B6DA 6036FD2B C3448984 24B0A87E E6B247AE",
+                "C4F9E111 90",
+            )
+            .to_string(),
+        );
+        run_cfb_segments(
+            expected_ciphertext,
+            get_nist_test_key_128(),
+            CFBSegmentSize::Bits(100),
         );
 
-        let mut ciphertext = Vec::new();
-        cfb::encrypt(&mut plaintext.as_slice(), &mut ciphertext, &config).unwrap();
-        assert_eq!(ciphertext, expected);
+        let expected_ciphertext = string_to_vec("68B3A264 F8".to_string());
+        run_cfb_segments(
+            expected_ciphertext,
+            get_nist_test_key_128(),
+            CFBSegmentSize::Bit1,
+        );
+    }
 
-        let mut decrypted = Vec::new();
-        cfb::decrypt(&mut ciphertext.as_slice(), &mut decrypted, &config).unwrap();
-        assert_eq!(decrypted, plaintext);
+    #[test]
+    fn test_cfb_cavp_known_answers() {
+        struct TestCase {
+            key: &'static str,
+            iv: &'static str,
+            // CFB128 ciphertext of the zero block
+            ciphertext: &'static str,
+        }
+
+        // COUNT = 0 of the CAVP CFB128 GFSbox, KeySbox, VarKey and VarTxt known answer
+        // tests for each key size. The plaintext is zero, so the ciphertext is E(IV), and
+        // the CFB8 and CFB1 files list its first byte and first bit for the same key and IV.
+        let test_cases = [
+            TestCase {
+                key: "00000000000000000000000000000000",
+                iv: "f34481ec3cc627bacd5dc3fb08f273e6",
+                ciphertext: "0336763e966d92595a567cc9ce537f5e",
+            },
+            TestCase {
+                key: "10a58869d74be5a374cf867cfb473859",
+                iv: "00000000000000000000000000000000",
+                ciphertext: "6d251e6944b051e04eaa6fb4dbf78465",
+            },
+            TestCase {
+                key: "80000000000000000000000000000000",
+                iv: "00000000000000000000000000000000",
+                ciphertext: "0edd33d3c621e546455bd8ba1418bec8",
+            },
+            TestCase {
+                key: "00000000000000000000000000000000",
+                iv: "80000000000000000000000000000000",
+                ciphertext: "3ad78e726c1ec02b7ebfe92b23d9ec34",
+            },
+            TestCase {
+                key: "000000000000000000000000000000000000000000000000",
+                iv: "1b077a6af4b7f98229de786d7516b639",
+                ciphertext: "275cfc0413d8ccb70513c3859b1d0f72",
+            },
+            TestCase {
+                key: "e9f065d7c13573587f7875357dfbb16c53489f6a4bd0f7cd",
+                iv: "00000000000000000000000000000000",
+                ciphertext: "0956259c9cd5cfd0181cca53380cde06",
+            },
+            TestCase {
+                key: "800000000000000000000000000000000000000000000000",
+                iv: "00000000000000000000000000000000",
+                ciphertext: "de885dc87f5a92594082d02cc1e1b42c",
+            },
+            TestCase {
+                key: "000000000000000000000000000000000000000000000000",
+                iv: "80000000000000000000000000000000",
+                ciphertext: "6cd02513e8d4dc986b4afe087a60bd0c",
+            },
+            TestCase {
+                key: "0000000000000000000000000000000000000000000000000000000000000000",
+                iv: "014730f80ac625fe84f026c60bfd547d",
+                ciphertext: "5c9d844ed46f9885085e5d6a4f94c7d7",
+            },
+            TestCase {
+                key: "c47b0294dbbbee0fec4757f22ffeee3587ca4730c3d33b691df38bab076bc558",
+                iv: "00000000000000000000000000000000",
+                ciphertext: "46f2fb342d6f0ab477476fc501242c5f",
+            },
+            TestCase {
+                key: "8000000000000000000000000000000000000000000000000000000000000000",
+                iv: "00000000000000000000000000000000",
+                ciphertext: "e35a6dcb19b201a01ebcfa8aa22b5759",
+            },
+            TestCase {
+                key: "0000000000000000000000000000000000000000000000000000000000000000",
+                iv: "80000000000000000000000000000000",
+                ciphertext: "ddc6bf790c15760d8d9aeb6f9a75fd4e",
+            },
+        ];
+
+        for test_case in test_cases {
+            let key = Key::try_from(hex_decode(test_case.key).unwrap().as_slice()).unwrap();
+            let iv: [u8; BLOCK_SIZE] = hex_decode(test_case.iv).unwrap().try_into().unwrap();
+            let expected = hex_decode(test_case.ciphertext).unwrap();
+
+            for backend in BACKENDS {
+                let key = key.clone().with_backend(backend);
+
+                for (seg_size, len) in [
+                    (CFBSegmentSize::Bit128, BLOCK_SIZE),
+                    (CFBSegmentSize::Bit8, 1),
+                    (CFBSegmentSize::Bit1, 1),
+                ] {
+                    let config = AESConfig::new(key.clone(), OperationMode::CFB { iv, seg_size });
+
+                    let mut ciphertext = Vec::new();
+                    cfb::encrypt(&mut vec![0; len].as_slice(), &mut ciphertext, &config).unwrap();
+
+                    // the CFB1 answer is a single bit, the first bit of the first byte
+                    if let CFBSegmentSize::Bit1 = seg_size {
+                        assert_eq!(ciphertext[0] >> 7, expected[0] >> 7, "{:?}", test_case.key);
+                    } else {
+                        assert_eq!(ciphertext, expected[..len], "{:?}", test_case.key);
+                    }
+
+                    let mut decrypted = Vec::new();
+                    cfb::decrypt(&mut ciphertext.as_slice(), &mut decrypted, &config).unwrap();
+                    assert_eq!(decrypted, vec![0; len]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_cfb_invalid_segment_size() {
+        for bits in [0, 129] {
+            let config = AESConfig::new(
+                get_nist_test_key_128(),
+                OperationMode::CFB {
+                    iv: get_nist_test_iv(),
+                    seg_size: CFBSegmentSize::Bits(bits),
+                },
+            );
+
+            let plaintext = get_nist_test_plaintext();
+            assert!(cfb::encrypt(&mut plaintext.as_slice(), &mut Vec::new(), &config).is_err());
+            assert!(cfb::decrypt(&mut plaintext.as_slice(), &mut Vec::new(), &config).is_err());
+        }
+    }
+
+    fn run_cfb_segments(expected: Vec<u8>, key: Key, seg_size: CFBSegmentSize) {
+        let mut plaintext = get_nist_test_plaintext();
+        plaintext.truncate(expected.len());
+
+        let iv = get_nist_test_iv();
+        for backend in BACKENDS {
+            let key = key.clone().with_backend(backend);
+            let config = AESConfig::new(key, OperationMode::CFB { iv, seg_size });
+
+            let mut ciphertext = Vec::new();
+            cfb::encrypt(&mut plaintext.as_slice(), &mut ciphertext, &config).unwrap();
+            assert_eq!(ciphertext, expected, "{:?}", seg_size);
+
+            let mut decrypted = Vec::new();
+            cfb::decrypt(&mut ciphertext.as_slice(), &mut decrypted, &config).unwrap();
+            assert_eq!(decrypted, plaintext);
+        }
     }
 
     #[test]
@@ -448,15 +820,18 @@ mod test {
     fn run_ctr(expected: Vec<u8>, key: Key) {
         let plaintext = get_nist_test_plaintext();
         let iv = get_nist_initial_counter();
-        let config = AESConfig::new(key, OperationMode::CTR { iv });
+        for backend in BACKENDS {
+            let key = key.clone().with_backend(backend);
+            let config = AESConfig::new(key, OperationMode::CTR { iv });
 
-        let mut ciphertext = Vec::new();
-        ctr::encrypt(&mut plaintext.as_slice(), &mut ciphertext, &config).unwrap();
-        assert_eq!(ciphertext, expected);
+            let mut ciphertext = Vec::new();
+            ctr::encrypt(&mut plaintext.as_slice(), &mut ciphertext, &config).unwrap();
+            assert_eq!(ciphertext, expected);
 
-        let mut decrypted = Vec::new();
-        ctr::decrypt(&mut ciphertext.as_slice(), &mut decrypted, &config).unwrap();
-        assert_eq!(decrypted, plaintext);
+            let mut decrypted = Vec::new();
+            ctr::decrypt(&mut ciphertext.as_slice(), &mut decrypted, &config).unwrap();
+            assert_eq!(decrypted, plaintext);
+        }
     }
 
     fn run_partial_ctr(mut expected: Vec<u8>, key: Key) {
@@ -466,15 +841,18 @@ mod test {
         expected.pop();
         expected.pop();
         let iv = get_nist_initial_counter();
-        let config = AESConfig::new(key, OperationMode::CTR { iv });
+        for backend in BACKENDS {
+            let key = key.clone().with_backend(backend);
+            let config = AESConfig::new(key, OperationMode::CTR { iv });
 
-        let mut ciphertext = Vec::new();
-        ctr::encrypt(&mut plaintext.as_slice(), &mut ciphertext, &config).unwrap();
-        assert_eq!(ciphertext, expected);
+            let mut ciphertext = Vec::new();
+            ctr::encrypt(&mut plaintext.as_slice(), &mut ciphertext, &config).unwrap();
+            assert_eq!(ciphertext, expected);
 
-        let mut decrypted = Vec::new();
-        ctr::decrypt(&mut ciphertext.as_slice(), &mut decrypted, &config).unwrap();
-        assert_eq!(decrypted, plaintext);
+            let mut decrypted = Vec::new();
+            ctr::decrypt(&mut ciphertext.as_slice(), &mut decrypted, &config).unwrap();
+            assert_eq!(decrypted, plaintext);
+        }
     }
 
     #[test]
@@ -869,13 +1247,16 @@ mod test {
         ];
 
         for test_case in test_cases {
-            run_gcm(
-                &test_case.plaintext,
-                &test_case.expected_cipher,
-                &test_case.aad,
-                &test_case.expected_tag,
-                test_case.key_size,
-            )
+            for backend in BACKENDS {
+                run_gcm(
+                    &test_case.plaintext,
+                    &test_case.expected_cipher,
+                    &test_case.aad,
+                    &test_case.expected_tag,
+                    test_case.key_size,
+                    backend,
+                )
+            }
         }
     }
 
@@ -885,6 +1266,7 @@ mod test {
         aad: &[u8],
         expected_tag: &[u8],
         key_size: KeySize,
+        backend: Backend,
     ) {
         let key = match key_size {
             KeySize::AES128 => Key::from([
@@ -900,7 +1282,9 @@ mod test {
                 0x83, 0x08, 0xFE, 0xFF, 0xE9, 0x92, 0x86, 0x65, 0x73, 0x1C, 0x6D, 0x6A, 0x8F, 0x94,
                 0x67, 0x30, 0x83, 0x08,
             ]),
-        };
+            _ => panic!("the GCM test vectors only cover the standard key sizes"),
+        }
+        .with_backend(backend);
 
         let iv = vec![
             0xCA, 0xFE, 0xBA, 0xBE, 0xFA, 0xCE, 0xDB, 0xAD, 0xDE, 0xCA, 0xF8, 0x88,
@@ -911,7 +1295,8 @@ mod test {
         let initial_plaintext = plaintext;
 
         let (_, tag) =
-            gcm::authenticated_encrypt(&mut plaintext, &mut ciphertext, &key, &iv, aad).unwrap();
+            gcm::authenticated_encrypt(&mut plaintext, &mut ciphertext, &key, backend, &iv, aad)
+                .unwrap();
         assert_eq!(ciphertext, expected_cipher);
 
         let mut tag_data = tag.bytes().to_vec();
@@ -922,6 +1307,7 @@ mod test {
             &mut ciphertext.as_slice(),
             &mut decrypted,
             &key,
+            backend,
             &iv,
             aad,
             tag,
@@ -939,6 +1325,7 @@ mod test {
             &mut ciphertext.as_slice(),
             &mut decrypted,
             &key,
+            backend,
             &iv,
             aad,
             tag,
@@ -948,39 +1335,1814 @@ mod test {
         );
     }
 
-    fn get_nist_test_plaintext() -> Vec<u8> {
-        string_to_vec(
-            concat!(
-                "6BC1BEE2 2E409F96 E93D7E11 7393172A",
-                "AE2D8A57 1E03AC9C 9EB76FAC 45AF8E51",
-                "30C81C46 A35CE411 E5FBC119 1A0A52EF",
-                "F69F2445 DF4F9B17 AD2B417B E66C3710"
-            )
-            .to_string(),
-        )
-    }
+    #[test]
+    fn test_aes_ccm() {
+        struct TestCase {
+            key: &'static str,
+            nonce: &'static str,
+            aad: Vec<u8>,
+            plaintext: Vec<u8>,
+            // the ciphertext followed by the tag
+            expected: Vec<u8>,
+            tag_size: usize,
+        }
 
-    fn get_nist_test_iv() -> [u8; BLOCK_SIZE] {
-        string_to_vec(concat!("00010203 04050607 08090A0B 0C0D0E0F").to_string())
-            .try_into()
-            .unwrap()
+        let test_cases = vec![
+            // SP 800-38C C.1 to C.4
+            TestCase {
+                key: "404142434445464748494A4B4C4D4E4F",
+                nonce: "10111213141516",
+                aad: string_to_vec("00010203 04050607".to_string()),
+                plaintext: string_to_vec("20212223".to_string()),
+                expected: string_to_vec("7162015B 4DAC255D".to_string()),
+                tag_size: 4,
+            },
+            TestCase {
+                key: "404142434445464748494A4B4C4D4E4F",
+                nonce: "1011121314151617",
+                aad: string_to_vec("00010203 04050607 08090A0B 0C0D0E0F".to_string()),
+                plaintext: string_to_vec("20212223 24252627 28292A2B 2C2D2E2F".to_string()),
+                expected: string_to_vec("D2A1F0E0 51EA5F62 081A7792 073D593D 1FC64FBF ACCD".to_string()),
+                tag_size: 6,
+            },
+            TestCase {
+                key: "404142434445464748494A4B4C4D4E4F",
+                nonce: "101112131415161718191A1B",
+                aad: string_to_vec("00010203 04050607 08090A0B 0C0D0E0F 10111213".to_string()),
+                plaintext: string_to_vec("20212223 24252627 28292A2B 2C2D2E2F 30313233 34353637".to_string()),
+                expected: string_to_vec("E3B201A9 F5B71A7A 9B1CEAEC CD97E70B 6176AAD9 A4428AA5 484392FB C1B09951".to_string()),
+                tag_size: 8,
+            },
+            // 2^16 bytes of AAD, the length takes 6 bytes
+            TestCase {
+                key: "404142434445464748494A4B4C4D4E4F",
+                nonce: "101112131415161718191A1B1C",
+                aad: (0..65536).map(|i| i as u8).collect(),
+                plaintext: string_to_vec("20212223 24252627 28292A2B 2C2D2E2F 30313233 34353637 38393A3B 3C3D3E3F".to_string()),
+                expected: string_to_vec("69915DAD 1E84C637 6A68C296 7E4DAB61 5AE0FD1F AEC44CC4 84828529 463CCF72 B4AC6BEC 93E8598E 7F0DADBC EA5B".to_string()),
+                tag_size: 14,
+            },
+            // RFC 3610 packet vector #1
+            TestCase {
+                key: "C0C1C2C3C4C5C6C7C8C9CACBCCCDCECF",
+                nonce: "00000003020100A0A1A2A3A4A5",
+                aad: string_to_vec("00010203 04050607".to_string()),
+                plaintext: string_to_vec("08090A0B 0C0D0E0F 10111213 14151617 18191A1B 1C1D1E".to_string()),
+                expected: string_to_vec("588C979A 61C663D2 F066D0C2 C0F98980 6D5F6B61 DAC38417 E8D12CFD F926E0".to_string()),
+                tag_size: 8,
+            },
+            TestCase {
+                key: "C0C1C2C3C4C5C6C7C8C9CACBCCCDCECF",
+                nonce: "00000004030201A0A1A2A3A4A5",
+                aad: string_to_vec("00010203 04050607".to_string()),
+                plaintext: string_to_vec("08090A0B 0C0D0E0F 10111213 14151617 18191A1B 1C1D1E1F".to_string()),
+                expected: string_to_vec("72C91A36 E135F8CF 291CA894 085C87E3 CC15C439 C9E43A3B A091D56E 10400916".to_string()),
+                tag_size: 8,
+            },
+            TestCase {
+                key: "C0C1C2C3C4C5C6C7C8C9CACBCCCDCECF",
+                nonce: "00000005040302A0A1A2A3A4A5",
+                aad: string_to_vec("00010203 04050607".to_string()),
+                plaintext: string_to_vec("08090A0B 0C0D0E0F 10111213 14151617 18191A1B 1C1D1E1F 20".to_string()),
+                expected: string_to_vec("51B1E5F4 4A197D1D A46B0F8E 2D282AE8 71E838BB 64DA8596 574ADAA7 6FBD9FB0 C5".to_string()),
+                tag_size: 8,
+            },
+            TestCase {
+                key: "C0C1C2C3C4C5C6C7C8C9CACBCCCDCECF",
+                nonce: "00000006050403A0A1A2A3A4A5",
+                aad: string_to_vec("00010203 04050607 08090A0B".to_string()),
+                plaintext: string_to_vec("0C0D0E0F 10111213 14151617 18191A1B 1C1D1E".to_string()),
+                expected: string_to_vec("A28C6865 939A9A79 FAAA5C4C 2A9D4A91 CDAC8C96 C861B9C9 E61EF1".to_string()),
+                tag_size: 8,
+            },
+            TestCase {
+                key: "C0C1C2C3C4C5C6C7C8C9CACBCCCDCECF",
+                nonce: "00000007060504A0A1A2A3A4A5",
+                aad: string_to_vec("00010203 04050607 08090A0B".to_string()),
+                plaintext: string_to_vec("0C0D0E0F 10111213 14151617 18191A1B 1C1D1E1F".to_string()),
+                expected: string_to_vec("DCF1FB7B 5D9E23FB 9D4E1312 53658AD8 6EBDCA3E 51E83F07 7D9C2D93".to_string()),
+                tag_size: 8,
+            },
+            TestCase {
+                key: "C0C1C2C3C4C5C6C7C8C9CACBCCCDCECF",
+                nonce: "00000008070605A0A1A2A3A4A5",
+                aad: string_to_vec("00010203 04050607 08090A0B".to_string()),
+                plaintext: string_to_vec("0C0D0E0F 10111213 14151617 18191A1B 1C1D1E1F 20".to_string()),
+                expected: string_to_vec("6FC1B011 F006568B 5171A42D 953D469B 2570A4BD 87405A04 43AC91CB 94".to_string()),
+                tag_size: 8,
+            },
+            TestCase {
+                key: "C0C1C2C3C4C5C6C7C8C9CACBCCCDCECF",
+                nonce: "00000009080706A0A1A2A3A4A5",
+                aad: string_to_vec("00010203 04050607".to_string()),
+                plaintext: string_to_vec("08090A0B 0C0D0E0F 10111213 14151617 18191A1B 1C1D1E".to_string()),
+                expected: string_to_vec("0135D1B2 C95F41D5 D1D4FEC1 85D166B8 094E999D FED96C04 8C56602C 97ACBB74 90".to_string()),
+                tag_size: 10,
+            },
+            TestCase {
+                key: "C0C1C2C3C4C5C6C7C8C9CACBCCCDCECF",
+                nonce: "0000000A090807A0A1A2A3A4A5",
+                aad: string_to_vec("00010203 04050607".to_string()),
+                plaintext: string_to_vec("08090A0B 0C0D0E0F 10111213 14151617 18191A1B 1C1D1E1F".to_string()),
+                expected: string_to_vec("7B75399A C0831DD2 F0BBD758 79A2FD8F 6CAE6B6C D9B7DB24 C17B4433 F434963F 34B4".to_string()),
+                tag_size: 10,
+            },
+            TestCase {
+                key: "C0C1C2C3C4C5C6C7C8C9CACBCCCDCECF",
+                nonce: "0000000B0A0908A0A1A2A3A4A5",
+                aad: string_to_vec("00010203 04050607".to_string()),
+                plaintext: string_to_vec("08090A0B 0C0D0E0F 10111213 14151617 18191A1B 1C1D1E1F 20".to_string()),
+                expected: string_to_vec("82531A60 CC24945A 4B827918 1AB5C84D F21CE7F9 B73F42E1 97EA9C07 E56B5EB1 7E5F4E".to_string()),
+                tag_size: 10,
+            },
+            TestCase {
+                key: "C0C1C2C3C4C5C6C7C8C9CACBCCCDCECF",
+                nonce: "0000000C0B0A09A0A1A2A3A4A5",
+                aad: string_to_vec("00010203 04050607 08090A0B".to_string()),
+                plaintext: string_to_vec("0C0D0E0F 10111213 14151617 18191A1B 1C1D1E".to_string()),
+                expected: string_to_vec("07342594 15778515 2B074098 330ABB14 1B947B56 6AA9406B 4D999988 DD".to_string()),
+                tag_size: 10,
+            },
+            TestCase {
+                key: "C0C1C2C3C4C5C6C7C8C9CACBCCCDCECF",
+                nonce: "0000000D0C0B0AA0A1A2A3A4A5",
+                aad: string_to_vec("00010203 04050607 08090A0B".to_string()),
+                plaintext: string_to_vec("0C0D0E0F 10111213 14151617 18191A1B 1C1D1E1F".to_string()),
+                expected: string_to_vec("676BB203 80B0E301 E8AB7959 0A396DA7 8B834934 F53AA2E9 107A8B6C 022C".to_string()),
+                tag_size: 10,
+            },
+            TestCase {
+                key: "C0C1C2C3C4C5C6C7C8C9CACBCCCDCECF",
+                nonce: "0000000E0D0C0BA0A1A2A3A4A5",
+                aad: string_to_vec("00010203 04050607 08090A0B".to_string()),
+                plaintext: string_to_vec("0C0D0E0F 10111213 14151617 18191A1B 1C1D1E1F 20".to_string()),
+                expected: string_to_vec("C0FFA0D6 F05BDB67 F24D43A4 338D2AA4 BED7B20E 43CD1AA3 1662E7AD 65D6DB".to_string()),
+                tag_size: 10,
+            },
+            // no AAD and no payload
+            TestCase {
+                key: "404142434445464748494A4B4C4D4E4F",
+                nonce: "10111213141516171819",
+                aad: vec![],
+                plaintext: string_to_vec("".to_string()),
+                expected: string_to_vec("D42F7246 4C483343 B33A13D6 7C9EA081".to_string()),
+                tag_size: 16,
+            },
+            TestCase {
+                key: "404142434445464748494A4B4C4D4E4F",
+                nonce: "10111213141516171819",
+                aad: string_to_vec("00010203 04".to_string()),
+                plaintext: string_to_vec("".to_string()),
+                expected: string_to_vec("C227805D".to_string()),
+                tag_size: 4,
+            },
+
+        ];
+
+        for test_case in test_cases {
+            let key = Key::try_from(string_to_vec(test_case.key.to_string()).as_slice()).unwrap();
+            let nonce = string_to_vec(test_case.nonce.to_string());
+            let (expected_cipher, expected_tag) = test_case
+                .expected
+                .split_at(test_case.expected.len() - test_case.tag_size);
+
+            for backend in BACKENDS {
+                let key = key.clone().with_backend(backend);
+                run_ccm(
+                    &test_case.plaintext,
+                    expected_cipher,
+                    expected_tag,
+                    &test_case.aad,
+                    &key,
+                    &nonce,
+                );
+            }
+        }
     }
 
-    fn get_nist_initial_counter() -> [u8; BLOCK_SIZE] {
-        string_to_vec("F0F1F2F3 F4F5F6F7 F8F9FAFB FCFDFEFF".to_string())
-            .try_into()
-            .unwrap()
+    #[test]
+    fn test_ccm_invalid_parameters() {
+        let key = get_nist_test_key_128();
+        let plaintext = get_nist_test_plaintext();
+        let nonce = [0x10; 13];
+
+        // tag sizes have to be even, nonces 7 to 13 bytes long
+        for tag_size in [0, 2, 5, 15, 18] {
+            let result = ccm::authenticated_encrypt(
+                &mut plaintext.as_slice(),
+                &mut vec![],
+                &key,
+                &nonce,
+                &[],
+                tag_size,
+            );
+            assert!(result.is_err(), "{}", tag_size);
+        }
+        for nonce_size in [0, 6, 14, 16] {
+            let result = ccm::authenticated_encrypt(
+                &mut plaintext.as_slice(),
+                &mut vec![],
+                &key,
+                &vec![0x10; nonce_size],
+                &[],
+                16,
+            );
+            assert!(result.is_err(), "{}", nonce_size);
+        }
+        assert!(ccm::authenticated_decrypt(
+            &mut plaintext.as_slice(),
+            &mut vec![],
+            &key,
+            &nonce,
+            &[],
+            &[0; 3]
+        )
+        .is_err());
+
+        // a 13-byte nonce leaves 2 bytes for the payload length
+        let too_long = vec![0; 1 << 16];
+        let result = ccm::authenticated_encrypt(
+            &mut too_long.as_slice(),
+            &mut vec![],
+            &key,
+            &nonce,
+            &[],
+            16,
+        );
+        assert!(result.is_err());
+        let result =
+            ccm::authenticated_encrypt(&mut &too_long[1..], &mut vec![], &key, &nonce, &[], 16);
+        assert!(result.is_ok());
     }
 
-    fn get_nist_test_key_128() -> Key {
-        let key_data = string_to_vec(concat!("2B7E1516 28AED2A6 ABF71588 09CF4F3C").to_string());
-        key_data.as_slice().try_into().unwrap()
+    #[test]
+    fn test_ccm_aad_length_encoding() {
+        // the largest AAD with a 2-byte length, and the smallest one with a 6-byte length
+        // (generated with an independent implementation)
+        let key = Key::try_from(
+            string_to_vec("40414243 44454647 48494A4B 4C4D4E4F".to_string()).as_slice(),
+        )
+        .unwrap();
+        let nonce = string_to_vec("10111213 141516".to_string());
+        let plaintext = string_to_vec("20212223 24".to_string());
+
+        let test_cases = [
+            (0xfeff, "7162015B C0", "63054449 33D4419B EDFE1ACA 6B5CC118"),
+            (0xff00, "7162015B C0", "69A18EDA 0CC191C7 D3350F50 CB922849"),
+        ];
+        for (aad_len, expected_cipher, expected_tag) in test_cases {
+            let aad: Vec<u8> = (0..aad_len).map(|i| i as u8).collect();
+            run_ccm(
+                &plaintext,
+                &string_to_vec(expected_cipher.to_string()),
+                &string_to_vec(expected_tag.to_string()),
+                &aad,
+                &key,
+                &nonce,
+            );
+        }
     }
 
-    fn get_nist_test_key_192() -> Key {
-        let key_data = string_to_vec(
-            concat!("8E73B0F7 DA0E6452 C810F32B 809079E5 62F8EAD2 522C6B7B").to_string(),
+    #[test]
+    fn test_ccm_multi_buffer_payload() {
+        // longer than the stream buffer, generated with an independent implementation
+        let key = Key::try_from(
+            string_to_vec("40414243 44454647 48494A4B 4C4D4E4F".to_string()).as_slice(),
+        )
+        .unwrap();
+        let nonce = string_to_vec("10111213 14151617 18191A1B".to_string());
+        let plaintext: Vec<u8> = (0..70000).map(|i| (i % 251) as u8).collect();
+
+        let mut ciphertext = vec![];
+        let (bytes_written, tag) = ccm::authenticated_encrypt(
+            &mut plaintext.as_slice(),
+            &mut ciphertext,
+            &key,
+            &nonce,
+            &[],
+            16,
+        )
+        .unwrap();
+        assert_eq!(bytes_written, plaintext.len());
+        assert_eq!(
+            ciphertext[..16],
+            string_to_vec("C3922189 D5973A5A BB3CCACC EDB7C72B".to_string())
+        );
+        assert_eq!(
+            ciphertext[ciphertext.len() - 16..],
+            string_to_vec("B261044B 19B97A9F 2BAF9083 F1022A27".to_string())
         );
+        assert_eq!(
+            tag,
+            string_to_vec("41727014 0E40E6CB 3994C50D F251C334".to_string())
+        );
+
+        let mut decrypted = vec![];
+        ccm::authenticated_decrypt(
+            &mut ciphertext.as_slice(),
+            &mut decrypted,
+            &key,
+            &nonce,
+            &[],
+            &tag,
+        )
+        .unwrap();
+        assert_eq!(decrypted, plaintext);
+    }
+
+    fn run_ccm(
+        mut plaintext: &[u8],
+        expected_cipher: &[u8],
+        expected_tag: &[u8],
+        aad: &[u8],
+        key: &Key,
+        nonce: &[u8],
+    ) {
+        let initial_plaintext = plaintext;
+        let mut ciphertext = vec![];
+        let (bytes_written, tag) = ccm::authenticated_encrypt(
+            &mut plaintext,
+            &mut ciphertext,
+            key,
+            nonce,
+            aad,
+            expected_tag.len(),
+        )
+        .unwrap();
+        assert_eq!(bytes_written, expected_cipher.len());
+        assert_eq!(ciphertext, expected_cipher);
+        assert_eq!(tag, expected_tag);
+
+        let mut decrypted = vec![];
+        ccm::authenticated_decrypt(
+            &mut ciphertext.as_slice(),
+            &mut decrypted,
+            key,
+            nonce,
+            aad,
+            &tag,
+        )
+        .expect("decryption could not be authenticated");
+        assert_eq!(decrypted, initial_plaintext);
+
+        // tamper with the tag, the AAD and the ciphertext (if any), nothing may be written
+        let mut tampered_tag = tag.clone();
+        tampered_tag[0] ^= 1;
+        let mut tampered_aad = aad.to_vec();
+        tampered_aad.push(0);
+        let mut tampered_cipher = ciphertext.clone();
+
+        let mut tampered = vec![
+            (ciphertext.as_slice(), aad, tampered_tag.as_slice()),
+            (
+                ciphertext.as_slice(),
+                tampered_aad.as_slice(),
+                tag.as_slice(),
+            ),
+        ];
+        if let Some(byte) = tampered_cipher.last_mut() {
+            *byte ^= 0x80;
+            tampered.push((tampered_cipher.as_slice(), aad, tag.as_slice()));
+        }
+
+        for (mut ciphertext, aad, tag) in tampered {
+            let mut decrypted = vec![];
+            let err = ccm::authenticated_decrypt(
+                &mut ciphertext,
+                &mut decrypted,
+                key,
+                nonce,
+                aad,
+                tag,
+            )
+            .expect_err(
+                "the input has been tampered with, the authentication tags should not match up.",
+            );
+            // the error must not reveal the computed tag
+            assert_eq!(err, "authentication failed");
+            assert!(decrypted.is_empty());
+        }
+    }
+
+    // RFC 8452, Appendix C
+    #[test]
+    fn test_aes_gcm_siv() {
+        struct TestCase {
+            key: &'static str,
+            nonce: &'static str,
+            aad: &'static str,
+            plaintext: &'static str,
+            expected_cipher: &'static str,
+            expected_tag: &'static str,
+        }
+
+        let test_cases = vec![
+            // C.1 AEAD_AES_128_GCM_SIV
+            TestCase {
+                key: "01000000 00000000 00000000 00000000",
+                nonce: "03000000 00000000 00000000",
+                aad: "",
+                plaintext: "",
+                expected_cipher: "",
+                expected_tag: "DC20E2D8 3F25705B B49E439E CA56DE25",
+            },
+            TestCase {
+                key: "01000000 00000000 00000000 00000000",
+                nonce: "03000000 00000000 00000000",
+                aad: "",
+                plaintext: "01000000 00000000",
+                expected_cipher: "B5D83933 0AC7B786",
+                expected_tag: "578782FF F6013B81 5B287C22 493A364C",
+            },
+            TestCase {
+                key: "01000000 00000000 00000000 00000000",
+                nonce: "03000000 00000000 00000000",
+                aad: "",
+                plaintext: "01000000 00000000 00000000",
+                expected_cipher: "7323EA61 D0593226 0047D942",
+                expected_tag: "A4978DB3 57391A0B C4FDEC8B 0D106639",
+            },
+            TestCase {
+                key: "01000000 00000000 00000000 00000000",
+                nonce: "03000000 00000000 00000000",
+                aad: "",
+                plaintext: "01000000 00000000 00000000 00000000",
+                expected_cipher: "743F7C80 77AB25F8 624E2E94 8579CF77",
+                expected_tag: "303AAF90 F6FE2119 9C606857 7437A0C4",
+            },
+            TestCase {
+                key: "01000000 00000000 00000000 00000000",
+                nonce: "03000000 00000000 00000000",
+                aad: "",
+                plaintext: "01000000 00000000 00000000 00000000 02000000 00000000 00000000 00000000",
+                expected_cipher: "84E07E62 BA83A658 5417245D 7EC413A9 FE427D63 15C09B57 CE45F2E3 936A9445",
+                expected_tag: "1A8E45DC D4578C66 7CD86847 BF6155FF",
+            },
+            TestCase {
+                key: "01000000 00000000 00000000 00000000",
+                nonce: "03000000 00000000 00000000",
+                aad: "",
+                plaintext: "01000000 00000000 00000000 00000000 02000000 00000000 00000000 00000000 03000000 00000000 00000000 00000000",
+                expected_cipher: "3FD24CE1 F5A67B75 BF2351F1 81A475C7 B800A5B4 D3DCF701 06B1EEA8 2FA1D64D F42BF722 6122FA92 E17A40EE AAC1201B",
+                expected_tag: "5E6E311D BF395D35 B0FE39C2 714388F8",
+            },
+            TestCase {
+                key: "01000000 00000000 00000000 00000000",
+                nonce: "03000000 00000000 00000000",
+                aad: "",
+                plaintext: "01000000 00000000 00000000 00000000 02000000 00000000 00000000 00000000 03000000 00000000 00000000 00000000 04000000 00000000 00000000 00000000",
+                expected_cipher: "2433668F 1058190F 6D43E360 F4F35CD8 E475127C FCA7028E A8AB5C20 F7AB2AF0 2516A2BD CBC08D52 1BE37FF2 8C152BBA 36697F25 B4CD169C 6590D1DD 39566D3F",
+                expected_tag: "8A263DD3 17AA88D5 6BDF3936 DBA75BB8",
+            },
+            TestCase {
+                key: "01000000 00000000 00000000 00000000",
+                nonce: "03000000 00000000 00000000",
+                aad: "01",
+                plaintext: "02000000 00000000",
+                expected_cipher: "1E6DABA3 5669F427",
+                expected_tag: "3B0A1A25 60969CDF 790D9975 9ABD1508",
+            },
+            TestCase {
+                key: "01000000 00000000 00000000 00000000",
+                nonce: "03000000 00000000 00000000",
+                aad: "01",
+                plaintext: "02000000 00000000 00000000",
+                expected_cipher: "296C7889 FD99F419 17F44620",
+                expected_tag: "08299C51 02745AAA 3A0C469F AD9E075A",
+            },
+            TestCase {
+                key: "01000000 00000000 00000000 00000000",
+                nonce: "03000000 00000000 00000000",
+                aad: "01",
+                plaintext: "02000000 00000000 00000000 00000000",
+                expected_cipher: "E2B0C5DA 79A901C1 745F7005 25CB335B",
+                expected_tag: "8F8936EC 039E4E4B B97EBD8C 4457441F",
+            },
+            TestCase {
+                key: "01000000 00000000 00000000 00000000",
+                nonce: "03000000 00000000 00000000",
+                aad: "01",
+                plaintext: "02000000 00000000 00000000 00000000 03000000 00000000 00000000 00000000",
+                expected_cipher: "620048EF 3C1E73E5 7E02BB85 62C416A3 19E73E4C AAC8E96A 1ECB2933 145A1D71",
+                expected_tag: "E6AF6A7F 87287DA0 59A71684 ED3498E1",
+            },
+            TestCase {
+                key: "01000000 00000000 00000000 00000000",
+                nonce: "03000000 00000000 00000000",
+                aad: "01",
+                plaintext: "02000000 00000000 00000000 00000000 03000000 00000000 00000000 00000000 04000000 00000000 00000000 00000000",
+                expected_cipher: "50C8303E A93925D6 4090D07B D109DFD9 515A5A33 431019C1 7D934659 99A8B005 3201D723 120A8562 B838CDFF 25BF9D1E",
+                expected_tag: "6A8CC386 5F76897C 2E4B245C F31C51F2",
+            },
+            TestCase {
+                key: "01000000 00000000 00000000 00000000",
+                nonce: "03000000 00000000 00000000",
+                aad: "01",
+                plaintext: "02000000 00000000 00000000 00000000 03000000 00000000 00000000 00000000 04000000 00000000 00000000 00000000 05000000 00000000 00000000 00000000",
+                expected_cipher: "2F5C6405 9DB55EE0 FB847ED5 13003746 ACA4E61C 711B5DE2 E7A77FFD 02DA42FE EC601910 D3467BB8 B36EBBAE BCE5FBA3 0D36C95F 48A3E798 0F0E7AC2 99332A80",
+                expected_tag: "CDC46AE4 75563DE0 37001EF8 4AE21744",
+            },
+            TestCase {
+                key: "01000000 00000000 00000000 00000000",
+                nonce: "03000000 00000000 00000000",
+                aad: "01000000 00000000 00000000",
+                plaintext: "02000000",
+                expected_cipher: "A8FE3E87",
+                expected_tag: "07EB1F84 FB28F8CB 73DE8E99 E2F48A14",
+            },
+            TestCase {
+                key: "01000000 00000000 00000000 00000000",
+                nonce: "03000000 00000000 00000000",
+                aad: "01000000 00000000 00000000 00000000 0200",
+                plaintext: "03000000 00000000 00000000 00000000 04000000",
+                expected_cipher: "6BB0FECF 5DED9B77 F902C7D5 DA236A43 91DD0297",
+                expected_tag: "24AFC980 5E976F45 1E6D87F6 FE106514",
+            },
+            TestCase {
+                key: "01000000 00000000 00000000 00000000",
+                nonce: "03000000 00000000 00000000",
+                aad: "01000000 00000000 00000000 00000000 02000000",
+                plaintext: "03000000 00000000 00000000 00000000 0400",
+                expected_cipher: "44D0AAF6 FB2F1F34 ADD5E806 4E83E12A 2ADA",
+                expected_tag: "BFF9B2EF 00FB4792 0CC72A0C 0F13B9FD",
+            },
+            // C.2 AEAD_AES_256_GCM_SIV
+            TestCase {
+                key: "01000000 00000000 00000000 00000000 00000000 00000000 00000000 00000000",
+                nonce: "03000000 00000000 00000000",
+                aad: "",
+                plaintext: "",
+                expected_cipher: "",
+                expected_tag: "07F5F416 9BBF55A8 400CD47E A6FD400F",
+            },
+            TestCase {
+                key: "01000000 00000000 00000000 00000000 00000000 00000000 00000000 00000000",
+                nonce: "03000000 00000000 00000000",
+                aad: "",
+                plaintext: "01000000 00000000",
+                expected_cipher: "C2EF328E 5C71C83B",
+                expected_tag: "84312213 0F7364B7 61E0B974 27E3DF28",
+            },
+            TestCase {
+                key: "01000000 00000000 00000000 00000000 00000000 00000000 00000000 00000000",
+                nonce: "03000000 00000000 00000000",
+                aad: "",
+                plaintext: "01000000 00000000 00000000",
+                expected_cipher: "9AAB2AEB 3FAA0A34 AEA8E2B1",
+                expected_tag: "8CA50DA9 AE6559E4 8FD10F6E 5C9CA17E",
+            },
+            TestCase {
+                key: "01000000 00000000 00000000 00000000 00000000 00000000 00000000 00000000",
+                nonce: "03000000 00000000 00000000",
+                aad: "",
+                plaintext: "01000000 00000000 00000000 00000000",
+                expected_cipher: "85A01B63 025BA19B 7FD3DDFC 033B3E76",
+                expected_tag: "C9EAC6FA 70094270 2E908623 83C6C366",
+            },
+            TestCase {
+                key: "01000000 00000000 00000000 00000000 00000000 00000000 00000000 00000000",
+                nonce: "03000000 00000000 00000000",
+                aad: "",
+                plaintext: "01000000 00000000 00000000 00000000 02000000 00000000 00000000 00000000",
+                expected_cipher: "4A6A9DB4 C8C65492 01B9EDB5 3006CBA8 21EC9CF8 50948A7C 86C68AC7 539D027F",
+                expected_tag: "E819E63A BCD020B0 06A97639 7632EB5D",
+            },
+            TestCase {
+                key: "01000000 00000000 00000000 00000000 00000000 00000000 00000000 00000000",
+                nonce: "03000000 00000000 00000000",
+                aad: "",
+                plaintext: "01000000 00000000 00000000 00000000 02000000 00000000 00000000 00000000 03000000 00000000 00000000 00000000",
+                expected_cipher: "C00D1218 93A9FA60 3F48CCC1 CA3C57CE 7499245E A0046DB1 6C53C7C6 6FE717E3 9CF6C748 837B61F6 EE3ADCEE 17534ED5",
+                expected_tag: "790BC968 80A99BA8 04BD12C0 E6A22CC4",
+            },
+            TestCase {
+                key: "01000000 00000000 00000000 00000000 00000000 00000000 00000000 00000000",
+                nonce: "03000000 00000000 00000000",
+                aad: "",
+                plaintext: "01000000 00000000 00000000 00000000 02000000 00000000 00000000 00000000 03000000 00000000 00000000 00000000 04000000 00000000 00000000 00000000",
+                expected_cipher: "C2D5160A 1F868383 4910ACDA FC41FBB1 632D4A35 3E8B905E C9A5499A C34F96C7 E1049EB0 80883891 A4DB8CAA A1F99DD0 04D80487 54073523 4E374451 2C6F90CE",
+                expected_tag: "112864C2 69FC0D9D 88C61FA4 7E39AA08",
+            },
+            TestCase {
+                key: "01000000 00000000 00000000 00000000 00000000 00000000 00000000 00000000",
+                nonce: "03000000 00000000 00000000",
+                aad: "01",
+                plaintext: "02000000 00000000",
+                expected_cipher: "1DE22967 237A8132",
+                expected_tag: "91213F26 7E3B452F 02D01AE3 3E4EC854",
+            },
+            TestCase {
+                key: "01000000 00000000 00000000 00000000 00000000 00000000 00000000 00000000",
+                nonce: "03000000 00000000 00000000",
+                aad: "01",
+                plaintext: "02000000 00000000 00000000",
+                expected_cipher: "163D6F9C C1B346CD 453A2E4C",
+                expected_tag: "C1A4A19A E800941C CDC57CC8 413C277F",
+            },
+            TestCase {
+                key: "01000000 00000000 00000000 00000000 00000000 00000000 00000000 00000000",
+                nonce: "03000000 00000000 00000000",
+                aad: "01",
+                plaintext: "02000000 00000000 00000000 00000000",
+                expected_cipher: "C9154582 3CC24F17 DBB0E9E8 07D5EC17",
+                expected_tag: "B292D28F F61189E8 E49F3875 EF91AFF7",
+            },
+            TestCase {
+                key: "01000000 00000000 00000000 00000000 00000000 00000000 00000000 00000000",
+                nonce: "03000000 00000000 00000000",
+                aad: "01",
+                plaintext: "02000000 00000000 00000000 00000000 03000000 00000000 00000000 00000000",
+                expected_cipher: "07DAD364 BFC2B9DA 89116D7B EF6DAAAF 6F255510 AA654F92 0AC81B94 E8BAD365",
+                expected_tag: "AEA1BAD1 2702E196 5604374A AB96DBBC",
+            },
+            TestCase {
+                key: "01000000 00000000 00000000 00000000 00000000 00000000 00000000 00000000",
+                nonce: "03000000 00000000 00000000",
+                aad: "01",
+                plaintext: "02000000 00000000 00000000 00000000 03000000 00000000 00000000 00000000 04000000 00000000 00000000 00000000",
+                expected_cipher: "C67A1F0F 567A5198 AA1FCC8E 3F213143 36F7F51C A8B1AF61 FEAC35A8 6416FA47 FBCA3B5F 749CDF56 4527F231 4F42FE25",
+                expected_tag: "03332742 B228C647 173616CF D44C54EB",
+            },
+            TestCase {
+                key: "01000000 00000000 00000000 00000000 00000000 00000000 00000000 00000000",
+                nonce: "03000000 00000000 00000000",
+                aad: "01",
+                plaintext: "02000000 00000000 00000000 00000000 03000000 00000000 00000000 00000000 04000000 00000000 00000000 00000000 05000000 00000000 00000000 00000000",
+                expected_cipher: "67FD45E1 26BFB9A7 9930C43A AD2D3696 7D3F0E4D 217C1E55 1F597278 70BEEFC9 8CB933A8 FCE9DE88 7B1E4079 9988DB1F C3F91880 ED405B2D D2983188 58467C89",
+                expected_tag: "5BDE0285 037C5DE8 1E5B570A 049B62A0",
+            },
+            TestCase {
+                key: "01000000 00000000 00000000 00000000 00000000 00000000 00000000 00000000",
+                nonce: "03000000 00000000 00000000",
+                aad: "01000000 00000000 00000000",
+                plaintext: "02000000",
+                expected_cipher: "22B3F4CD",
+                expected_tag: "1835E517 741DFDDC CFA07FA4 661B74CF",
+            },
+            TestCase {
+                key: "01000000 00000000 00000000 00000000 00000000 00000000 00000000 00000000",
+                nonce: "03000000 00000000 00000000",
+                aad: "01000000 00000000 00000000 00000000 0200",
+                plaintext: "03000000 00000000 00000000 00000000 04000000",
+                expected_cipher: "43DD0163 CDB48F9F E3212BF6 1B201976 067F342B",
+                expected_tag: "B879AD97 6D8242AC C188AB59 CABFE307",
+            },
+            TestCase {
+                key: "01000000 00000000 00000000 00000000 00000000 00000000 00000000 00000000",
+                nonce: "03000000 00000000 00000000",
+                aad: "01000000 00000000 00000000 00000000 02000000",
+                plaintext: "03000000 00000000 00000000 00000000 0400",
+                expected_cipher: "46240172 4B5CE658 8D5A54AA E5375513 A075",
+                expected_tag: "CFCDF504 2112AA29 685C912F C2056543",
+            },
+            // C.3 counter wrap
+            TestCase {
+                key: "00000000 00000000 00000000 00000000 00000000 00000000 00000000 00000000",
+                nonce: "00000000 00000000 00000000",
+                aad: "",
+                plaintext: "00000000 00000000 00000000 00000000 4DB923DC 793EE649 7C76DCC0 3A98E108",
+                expected_cipher: "F3F80F2C F0CB2DD9 C5984FCD A908456C C537703B 5BA70324 A6793A7B F218D3EA",
+                expected_tag: "FFFFFFFF 00000000 00000000 00000000",
+            },
+            TestCase {
+                key: "00000000 00000000 00000000 00000000 00000000 00000000 00000000 00000000",
+                nonce: "00000000 00000000 00000000",
+                aad: "",
+                plaintext: "EB364027 7C7FFD13 03C7A542 D02D3E4C 00000000 00000000",
+                expected_cipher: "18CE4F0B 8CB4D0CA C65FEA8F 79257B20 888E53E7 2299E56D",
+                expected_tag: "FFFFFFFF 00000000 00000000 00000000",
+            },
+
+        ];
+
+        for test_case in test_cases {
+            let key = Key::try_from(string_to_vec(test_case.key.to_string()).as_slice()).unwrap();
+            let nonce = string_to_vec(test_case.nonce.to_string());
+            let aad = string_to_vec(test_case.aad.to_string());
+            let plaintext = string_to_vec(test_case.plaintext.to_string());
+            let expected_cipher = string_to_vec(test_case.expected_cipher.to_string());
+            let expected_tag =
+                Block::try_from(string_to_vec(test_case.expected_tag.to_string())).unwrap();
+
+            for backend in BACKENDS {
+                let key = key.clone().with_backend(backend);
+
+                let mut ciphertext = vec![];
+                let (bytes_written, tag) = gcm_siv::authenticated_encrypt(
+                    &mut plaintext.as_slice(),
+                    &mut ciphertext,
+                    &key,
+                    &nonce,
+                    &aad,
+                )
+                .unwrap();
+                assert_eq!(bytes_written, plaintext.len());
+                assert_eq!(ciphertext, expected_cipher);
+                assert_eq!(tag, expected_tag);
+
+                let mut decrypted = vec![];
+                gcm_siv::authenticated_decrypt(
+                    &mut ciphertext.as_slice(),
+                    &mut decrypted,
+                    &key,
+                    &nonce,
+                    &aad,
+                    tag,
+                )
+                .expect("decryption could not be authenticated");
+                assert_eq!(decrypted, plaintext);
+
+                // a tampered tag fails before anything is written, without revealing the tag
+                let mut decrypted = vec![];
+                let err = gcm_siv::authenticated_decrypt(
+                    &mut ciphertext.as_slice(),
+                    &mut decrypted,
+                    &key,
+                    &nonce,
+                    &aad,
+                    tag ^ Block::new(1),
+                )
+                .expect_err(
+                    "the tag has been tampered with, the authentication tags should not match up.",
+                );
+                assert_eq!(err, "authentication failed");
+                assert!(decrypted.is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn test_gcm_siv_nonce_reuse_and_invalid_parameters() {
+        let key = get_nist_test_key_256();
+        let nonce = [0x42; 12];
+        let encrypt = |plaintext: &[u8], aad: &[u8]| {
+            let mut ciphertext = vec![];
+            let (_, tag) = gcm_siv::authenticated_encrypt(
+                &mut &plaintext[..],
+                &mut ciphertext,
+                &key,
+                &nonce,
+                aad,
+            )
+            .unwrap();
+            (ciphertext, tag)
+        };
+
+        // with a reused nonce, only identical messages produce identical ciphertexts
+        let plaintext = get_nist_test_plaintext();
+        let (ciphertext, tag) = encrypt(&plaintext, b"header");
+        assert_eq!(encrypt(&plaintext, b"header"), (ciphertext.clone(), tag));
+        let (other_ciphertext, other_tag) = encrypt(&plaintext[1..], b"header");
+        assert_ne!(other_tag, tag);
+        assert_ne!(other_ciphertext[..16], ciphertext[1..17]);
+        assert_ne!(encrypt(&plaintext, b"other header").1, tag);
+
+        // tampering with the ciphertext or the AAD is detected
+        let mut tampered = ciphertext.clone();
+        tampered[20] ^= 4;
+        for (ciphertext, aad) in [
+            (tampered.as_slice(), &b"header"[..]),
+            (&ciphertext, b"Header"),
+        ] {
+            let mut decrypted = vec![];
+            let result = gcm_siv::authenticated_decrypt(
+                &mut &ciphertext[..],
+                &mut decrypted,
+                &key,
+                &nonce,
+                aad,
+                tag,
+            );
+            assert!(result.is_err());
+            assert!(decrypted.is_empty());
+        }
+
+        // 12-byte nonces and 128 or 256-bit keys only
+        for nonce in [&[0; 8][..], &[0; 13][..], &[0; 16][..]] {
+            let result =
+                gcm_siv::authenticated_encrypt(&mut &plaintext[..], &mut vec![], &key, nonce, &[]);
+            assert!(result.is_err());
+        }
+        for key in [
+            get_nist_test_key_192(),
+            Key::new_rijndael(&[0; 20]).unwrap(),
+        ] {
+            // the key is rejected before the input is read
+            let bytes_read = AtomicUsize::new(0);
+            let mut reader = CountingReader {
+                data: plaintext.as_slice(),
+                bytes_read: &bytes_read,
+            };
+            let result =
+                gcm_siv::authenticated_encrypt(&mut reader, &mut vec![], &key, &nonce, &[]);
+            assert!(result.unwrap_err().contains("128 and 256-bit keys"));
+            assert_eq!(bytes_read.load(Ordering::SeqCst), 0);
+        }
+    }
+
+    #[test]
+    fn test_tags_equal() {
+        let tag = [0x5a; 16];
+        assert!(tags_equal(&tag, &tag));
+        assert!(tags_equal(&[], &[]));
+        for index in 0..tag.len() {
+            let mut tampered = tag;
+            tampered[index] ^= 0x01;
+            assert!(!tags_equal(&tag, &tampered));
+        }
+        // a prefix of the tag isn't the tag
+        assert!(!tags_equal(&tag, &tag[..8]));
+        assert!(!tags_equal(&tag[..8], &tag));
+    }
+
+    // NIST SP 800-38B, Appendix D.1
+    #[test]
+    fn test_aes128_cmac() {
+        let plaintext = get_nist_test_plaintext();
+        let expected_macs = [
+            (0, 0xbb1d6929e95937287fa37d129b756746),
+            (16, 0x070a16b46b4d4144f79bdd9dd04a287c),
+            (20, 0x7d85449ea6ea19c823a7bf78837dfade),
+            (40, 0xdfa66747de9ae63030ca32611497c827),
+            (64, 0x51f0bebf7e3b9d92fc49741779363cfe),
+        ];
+
+        // subkeys K1 and K2
+        let l = get_nist_test_key_128().encrypt_block(Block::new(0));
+        assert_eq!(cmac::dbl(l), Block::new(0xfbeed618357133667c85e08f7236a8de));
+        assert_eq!(
+            cmac::dbl(cmac::dbl(l)),
+            Block::new(0xf7ddac306ae266ccf90bc11ee46d513b)
+        );
+
+        for backend in BACKENDS {
+            let key = get_nist_test_key_128().with_backend(backend);
+
+            for (len, expected_mac) in expected_macs {
+                assert_eq!(
+                    cmac::cmac(&key, &plaintext[..len]),
+                    Block::new(expected_mac)
+                );
+
+                // the result doesn't depend on how the data is split up
+                let mut mac = cmac::Cmac::new(&key);
+                for chunk in plaintext[..len].chunks(7) {
+                    mac.update(chunk);
+                    mac.update(&[]);
+                }
+                assert_eq!(mac.finalize(), Block::new(expected_mac));
+            }
+        }
+    }
+
+    // RFC 5297, Appendix A and generated vectors for every key size
+    #[test]
+    fn test_aes_siv() {
+        struct TestCase {
+            key: &'static str,
+            aad: Vec<&'static str>,
+            plaintext: &'static str,
+            expected_cipher: &'static str,
+            expected_siv: &'static str,
+        }
+
+        let test_cases = vec![
+            TestCase {
+                key: "FF FE FD FC FB FA F9 F8 F7 F6 F5 F4 F3 F2 F1 F0 F0 F1 F2 F3 F4 F5 F6 F7 F8 F9 FA FB FC FD FE FF",
+                aad: vec!["10 11 12 13 14 15 16 17 18 19 1A 1B 1C 1D 1E 1F 20 21 22 23 24 25 26 27"],
+                plaintext: "11 22 33 44 55 66 77 88 99 AA BB CC DD EE",
+                expected_cipher: "40 C0 2B 96 90 C4 DC 04 DA EF 7F 6A FE 5C",
+                expected_siv: "85 63 2D 07 C6 E8 F3 7F 95 0A CD 32 0A 2E CC 93",
+            },
+            TestCase {
+                key: "7F 7E 7D 7C 7B 7A 79 78 77 76 75 74 73 72 71 70 40 41 42 43 44 45 46 47 48 49 4A 4B 4C 4D 4E 4F",
+                aad: vec!["00 11 22 33 44 55 66 77 88 99 AA BB CC DD EE FF DE AD DA DA DE AD DA DA FF EE DD CC BB AA 99 88 77 66 55 44 33 22 11 00", "10 20 30 40 50 60 70 80 90 A0", "09 F9 11 02 9D 74 E3 5B D8 41 56 C5 63 56 88 C0"],
+                plaintext: "74 68 69 73 20 69 73 20 73 6F 6D 65 20 70 6C 61 69 6E 74 65 78 74 20 74 6F 20 65 6E 63 72 79 70 74 20 75 73 69 6E 67 20 53 49 56 2D 41 45 53",
+                expected_cipher: "CB 90 0F 2F DD BE 40 43 26 60 19 65 C8 89 BF 17 DB A7 7C EB 09 4F A6 63 B7 A3 F7 48 BA 8A F8 29 EA 64 AD 54 4A 27 2E 9C 48 5B 62 A3 FD 5C 0D",
+                expected_siv: "7B DB 6E 3B 43 26 67 EB 06 F4 D1 4B FF 2F BD 0F",
+            },
+            TestCase {
+                key: "09 ED 57 A1 49 36 73 EA AC DF 51 F8 81 31 37 4D F2 0A 30 1C 05 9C B3 EB B4 53 D1 E1 DC 0F 41 E4",
+                aad: vec![],
+                plaintext: "",
+                expected_cipher: "",
+                expected_siv: "5F E5 69 2C E4 97 99 84 26 1B 47 51 1E B2 9A CF",
+            },
+            TestCase {
+                key: "7C 17 B6 2D 79 19 49 06 15 57 7B BC 74 67 E9 F5 EB A6 C0 30 A7 60 6B 57 C2 9F 33 B1 6F 5F 9C 4A",
+                aad: vec![""],
+                plaintext: "",
+                expected_cipher: "",
+                expected_siv: "09 B1 04 1C 26 BB A7 E4 CB BC 2F DA 85 E9 C8 BF",
+            },
+            TestCase {
+                key: "A3 EE 0C 6B A8 75 3F A4 C0 7A C2 41 A3 AD 49 44 5C F7 B1 AE D6 F4 DB 8F A1 65 EA DC E3 D0 BD A1",
+                aad: vec![],
+                plaintext: "63",
+                expected_cipher: "C9",
+                expected_siv: "EF 4E F6 CA 07 80 96 B7 4A E7 9C 5C 3D 0A 5F 85",
+            },
+            TestCase {
+                key: "56 B8 DB B3 25 E8 1E 91 A5 09 7B 24 14 C8 58 D6 68 C7 AD 1B FA B3 1A C7 76 AE A1 DC 43 89 01 35",
+                aad: vec![""],
+                plaintext: "0E 5A 3E B4 89 37 FA 97 73 8B 00 A7 1A F8 B0",
+                expected_cipher: "A8 3C 4A 96 30 33 DE 10 B8 88 71 7C 2E DF 48",
+                expected_siv: "A9 6C C3 2B 48 A0 E6 3E FB 3B 52 71 7D C2 9C 61",
+            },
+            TestCase {
+                key: "CC D6 2C 44 D5 6C CF 44 83 41 5C 0F DF 90 7A 5F AD F0 67 A1 F5 54 E0 B6 6C B3 53 C1 B0 21 91 76",
+                aad: vec!["8F 04 E4 7E 07 DF D3 67 B1 52 14 D7 E0 C4 DD 85", "AE E7 82 E6 82"],
+                plaintext: "C9 4C 4C 4F A3 94 DA 3A A2 3D 45 0E C9 D0 1B 25",
+                expected_cipher: "CD 83 63 C7 B6 BF 58 5F 85 E8 35 42 03 59 17 D6",
+                expected_siv: "03 93 26 A6 9E 4F 77 A3 93 22 10 A1 0C 66 E1 FA",
+            },
+            TestCase {
+                key: "A4 90 DE 4C B4 C5 0B 2B 81 6C 1E 3F 8F AF 46 7E B8 C2 50 78 28 62 85 F1 33 C2 CF B6 B5 63 CF 7E",
+                aad: vec!["7E 74 94 3A DE E3 4F 1B 0C 41 10 AA 76 C1 36 EF"],
+                plaintext: "95 15 0B 80 B9 8D 2B 99 74 12 43 95 C4 B8 5A 97 CA",
+                expected_cipher: "D8 63 C4 D6 CB 41 C7 D2 C7 8E A8 F7 67 D5 75 94 FC",
+                expected_siv: "76 9D CB 39 D6 2B 4B D4 CC 2E F1 DA 98 58 A2 A9",
+            },
+            TestCase {
+                key: "61 16 7A B7 9B 93 D8 78 18 CD 51 2A 86 94 42 98 CA 47 92 AF 58 DE 80 BB 2D 56 9D A9 9B C3 EB 51",
+                aad: vec![],
+                plaintext: "F7 90 AD 70 9A F0 26 16 EB 7D C8 3C C6 AD 30 66 3E A4 09 08 30 CD 80 68 17 19 D2 F1 82 33 41 4E",
+                expected_cipher: "F3 21 8F 8F 86 48 FB FA 45 68 48 56 08 1D 40 CE F8 B0 E6 67 78 D2 FF 11 1E FF 8D 28 94 73 BF 12",
+                expected_siv: "56 E5 C7 0C 08 BB B4 5E BF F7 23 BF E6 1B AC 04",
+            },
+            TestCase {
+                key: "E8 FE 1A 8B 16 AC 54 DD 3F 1F 29 28 50 99 1C 11 9E 8F 7D B4 1C B9 95 4C 2B BC AB 1D AD 94 19 16",
+                aad: vec!["5E FA 77 C3 60", "0F 3D 96 01 3E", "37 68 FE AA C3 B9 19 EF E3 56 11 38 81 0D E9 60 57 BC B9 74 D9 2B BB"],
+                plaintext: "A6 3B 6E D4 52 FA 6E 31 08 CC BA 3F 75 FC DE 1C A0 A0 A0 91 C9 72 7F 60 6C CC 85 A4 7C 68 08 32 70 99 42 51 9C 7D A9 49 02 DA ED 1F A0 21 52",
+                expected_cipher: "4E F6 DF D3 79 CA 80 C1 0C 86 41 23 03 A3 55 A8 52 19 5C 8B 47 22 E7 65 B9 4F 26 1D 30 A1 E1 C8 1E E7 23 DE AE A3 C1 7D 3E 8F 5B AC 7C 40 D0",
+                expected_siv: "17 A6 45 18 07 B5 9C DD 4B C1 89 1E 7B C1 38 D3",
+            },
+            TestCase {
+                key: "64 5E 87 BA 10 BF 1C 61 38 F4 5D 26 D7 EE F5 7E 38 C5 72 6A 17 8F C6 90 87 DC FD 69 DC 02 A7 AF",
+                aad: vec![""],
+                plaintext: "89 92 82 C0 DC A1 F1 41 70 3F 85 E8 14 69 28 6B 72 83 A4 2A 3E D5 A7 A6 B7 63 CC B3 C1 69 C1 36 F8 4B 39 E2 74 FB F8 C7 F2 95 0A E9 39 BF BD 4B 84 1B 37 48 DE 2C D7 94 14 CC 5A F0 55 79 97 F9",
+                expected_cipher: "F8 6D E1 40 69 AB 24 41 C0 00 60 78 D1 6B 0A B2 EE 4D A8 05 A5 8B BA FE EF C8 0D 4E 1D 50 75 E7 54 A7 06 DB DA AB 2F 14 B6 D2 56 95 99 D0 81 68 70 88 CC 70 B3 78 40 D6 B7 87 C5 AB 63 8A E5 D0",
+                expected_siv: "17 8E 41 8A 3F 07 35 E7 61 2E E6 50 A3 50 61 85",
+            },
+            TestCase {
+                key: "EC C8 8D 63 C6 9C A9 58 B5 68 A1 CE 0D 96 F9 F8 04 9A 16 7F 28 54 00 BD 85 36 66 0D B2 AB CF 90 C4 30 CA 22 4A 35 D3 2C 72 A9 0B 91 BB A9 5D C1",
+                aad: vec![],
+                plaintext: "",
+                expected_cipher: "",
+                expected_siv: "76 09 DD 45 2C DD 10 DB EE 14 74 6C 66 4F FB 4D",
+            },
+            TestCase {
+                key: "6C 35 ED F7 F0 5A 94 43 54 75 4C 4D 5A C3 01 41 83 7A 0C 52 42 5B 7B C7 36 1D D8 1A 8C 27 1D 1B C4 3A 2F 70 8C 86 D6 F5 7B F1 77 29 F9 D0 E6 89",
+                aad: vec!["35 DC 73 85 7F"],
+                plaintext: "",
+                expected_cipher: "",
+                expected_siv: "B8 06 18 5F 40 56 91 30 27 25 BA 66 18 A8 B0 EF",
+            },
+            TestCase {
+                key: "8D AE CC B3 6B 32 95 FA 5F 7B 1A D6 90 B4 DC B8 8E 4B A7 26 B6 3A 75 2C 94 70 40 8C F2 4A 11 7E 91 8F 79 1D 59 1B 08 6F 54 8C 9C 01 0E AC 54 FB",
+                aad: vec![],
+                plaintext: "AB",
+                expected_cipher: "6B",
+                expected_siv: "5A A5 F8 3F 6F 50 DD 00 C0 01 FA A6 4E D7 73 15",
+            },
+            TestCase {
+                key: "63 21 03 A3 79 CE 9D A5 89 A4 D9 FF 83 3C 44 5E 9D 69 9A 2C 06 F2 72 8F 2D 03 2E 41 33 80 E5 31 29 7C 08 1E 6D 3A 30 59 9A 2E 9F 8A 21 24 98 11",
+                aad: vec![""],
+                plaintext: "B7 E9 C8 FD 32 5A 56 AE 15 28 89 2B D2 6A FF",
+                expected_cipher: "B3 38 DD 9B B9 17 7A 5A 9D D0 14 8A B7 1E 1D",
+                expected_siv: "4A 86 30 79 D0 A9 1F 70 4F 2F 04 2F 02 AB 3D 11",
+            },
+            TestCase {
+                key: "B3 7D AA 14 18 48 D9 F2 DE 28 6B 67 ED 64 32 A1 4A 7B 1C F1 40 89 12 90 0E A8 6F 85 6E 0F F8 8B 17 8B 36 A1 65 2A F3 E0 C8 8E E0 86 72 D4 64 77",
+                aad: vec!["8D 60 6F 8F 2B 27 DF 35 42 87 5E F9 1D 94 CB EB 59 14 90 BA D0 72 3D", ""],
+                plaintext: "67 CF 3F 30 0C 0A B4 00 AF 15 AB EA 4A 46 D2 64",
+                expected_cipher: "03 F8 87 DD 3F 66 7F 88 79 4A 8F E2 BC F8 CD 80",
+                expected_siv: "D5 91 BB 1B 37 1F E5 F9 BB CF A0 59 72 D3 77 DC",
+            },
+            TestCase {
+                key: "0F 9F 9D 9B DA 11 F6 0A 3C D2 E5 BE DB B7 0B 53 E0 C8 93 08 9C 7D 78 A6 2A 88 3B 99 7B C3 33 14 2C BE F6 3E 44 0F 7A 46 EF 78 65 BB FC 58 77 C1",
+                aad: vec![""],
+                plaintext: "DE 03 66 71 57 6E 87 A4 86 24 FB 67 71 75 F8 EE 11",
+                expected_cipher: "D5 92 FE A2 6A 10 C9 B3 68 63 46 AE 69 10 EC EE F7",
+                expected_siv: "62 BC AB C7 0C F3 00 B6 BE 84 84 12 17 22 18 09",
+            },
+            TestCase {
+                key: "57 5F 64 E3 91 6D 81 70 75 97 21 8B E3 0E 5F 6C 59 78 0A 20 0F FA E5 96 15 C0 A4 0D D4 2A B9 1C 0A 96 42 B0 7F 02 B3 CB 2E 41 35 C6 EC D1 15 28",
+                aad: vec![],
+                plaintext: "62 93 45 81 05 59 02 53 D6 84 F4 A3 60 82 90 86 3D FC 6E 5C 1C 8E A3 BF 5F EE 5F C2 E7 9F 63 A9",
+                expected_cipher: "A6 1B B0 E2 FC 61 6D 0D 2B 62 8F A4 1D FA 7B C9 24 EC FB 17 7A 79 36 A3 26 76 E0 12 74 4E 51 CA",
+                expected_siv: "CA D7 CC 0A B5 67 C5 1B A3 3A 47 D0 59 E3 65 88",
+            },
+            TestCase {
+                key: "99 D8 71 B6 EE BC 79 FD EB 78 81 74 CF EF EE 9C B4 00 F0 A3 F9 62 CA FE DB 0F AB 65 A8 39 75 E3 0E 73 50 1E DA F0 0F C6 9D 7A 3C FC 91 77 EC 1E",
+                aad: vec!["57 F8 4A 52 49", "DE 11 D3 98 B9 DC B8 49 1F 82 C0 AE D4 D5 62 44", "49 04 24 7E 60"],
+                plaintext: "C5 D9 A3 2A 1C 97 7B 10 0B 85 E6 3A A2 21 E4 B9 3D 8E 77 0B 41 64 40 B2 2A 3C 91 A7 F1 D1 FB 7E BC 1B A6 03 7E E1 F6 95 ED F6 BE 1C DF A9 8F",
+                expected_cipher: "28 EB 5C AA 81 54 B5 0C 31 3A 2A BE F5 F2 7E 1E B1 4C 19 B5 2B 5A 77 CE F8 11 27 22 69 A2 C5 3C 09 43 7C 02 C3 DB A4 B4 A0 2E B3 7B 4F A4 B1",
+                expected_siv: "31 2C 91 8A B6 89 F6 A5 E5 D6 1C B1 62 AF 6B 1B",
+            },
+            TestCase {
+                key: "44 5C EF 3A EA DC ED EB AC EF 35 51 FC 09 23 5A E5 92 80 40 B0 19 E0 C4 1C E1 46 1A A7 57 AB F2 92 DC 39 1A EE F4 A6 FF 0E 4A DF 50 A9 BE 69 67",
+                aad: vec!["B7 B6 60 FF 21"],
+                plaintext: "76 AF E4 1C 32 3E 0C C8 38 F9 ED F1 C1 F8 54 11 59 04 FD 42 AD 1E 33 B7 BF 71 16 33 2E E5 16 AE 47 A0 DD E1 E8 8A E9 1E 52 2A B1 79 F3 F8 C6 66 07 20 37 6F 91 A4 9C B3 4C F3 92 37 2B DB F0 F8",
+                expected_cipher: "10 08 DA 36 04 90 E8 CC 9D 0B BD 01 F1 24 B3 ED C2 CC 2C 05 34 DB B7 D1 99 53 9D 69 C9 16 7D 18 91 9B 08 89 2F 8E 47 09 4A ED 90 D8 FA F8 F5 75 88 E3 9A 1B 74 FC 8D 28 8F A8 44 3A AE 4B 28 33",
+                expected_siv: "9F 2F 69 B3 6F 2C E3 25 38 66 E5 7A B3 3F 48 77",
+            },
+            TestCase {
+                key: "7A E5 CE 2A 0E 03 9E 91 DA 28 B5 CB A8 5F 01 83 5C 71 B4 1A 46 B9 C0 AC C0 FF 90 10 05 B0 C0 64 81 34 6E 88 D4 BA 92 E6 5D 7F 0C 1F 9A 1A BC 13 B2 6C E6 31 B3 1D 33 46 B9 89 43 19 DD 00 30 5D",
+                aad: vec![],
+                plaintext: "",
+                expected_cipher: "",
+                expected_siv: "02 C9 68 61 25 62 B1 DD 7E DD C7 AC AA 7B C5 7F",
+            },
+            TestCase {
+                key: "37 10 74 89 26 E5 89 85 74 A0 CF F2 41 2D 4F 12 B3 87 87 A2 4F E7 20 96 4B 55 5B E9 29 31 D1 A7 A3 50 E7 8F 0C FF D7 D1 1C B5 9F 3F 20 3F 28 10 EF 0A 5E 86 F3 C1 AB 21 A0 75 9E 92 14 C2 B3 DE",
+                aad: vec!["47 9A E1 84 BF 44 4F DA DA D7 8D 69 DE B7 1E 66 C6 01 DB E8 AF A2 DF"],
+                plaintext: "",
+                expected_cipher: "",
+                expected_siv: "43 24 9B 79 4F 88 A1 69 76 DE 5E 37 A9 E2 E5 DB",
+            },
+            TestCase {
+                key: "FB 6F D7 AA F9 EA E1 7A 85 E2 0A 93 D9 27 E7 1A 26 A6 B2 A9 EB 76 5A C0 32 AB 06 E8 ED 57 64 D4 F3 05 6F 2F 4C D5 49 4D 06 87 E1 21 14 6E 14 72 07 5E 1E E8 07 FE 6F E4 26 A7 23 0B B2 D2 6D 16",
+                aad: vec![],
+                plaintext: "39",
+                expected_cipher: "2C",
+                expected_siv: "17 33 B6 D7 9A 7B 66 DA 2D 38 31 2C 96 C6 10 37",
+            },
+            TestCase {
+                key: "57 1C F0 3F D4 53 F6 99 8B 98 AC DD B3 84 E3 B7 9C 3C B1 CA 07 0A 43 1C 7E E7 7E 7B 11 CC 67 5B 72 34 67 7E 22 13 07 10 CF AC 69 68 5F 72 8B 75 9A 48 6F A6 5C 09 A7 47 F8 1B FF 78 F7 B3 DC 22",
+                aad: vec!["2F AE AD 37 65 60 0E 20 73 4E 99 AC 28 9B 5B E0"],
+                plaintext: "9A 60 CC FF 9E FC 70 69 FC 18 7D 8C 4A 7B A7",
+                expected_cipher: "53 0C CF 27 70 63 E9 0E 81 FD 60 17 93 97 E9",
+                expected_siv: "70 09 F0 3A E5 97 36 5F 75 C0 55 86 3A 89 89 48",
+            },
+            TestCase {
+                key: "C0 78 FE DB CB F4 E8 B8 6D 00 FA FF A5 89 0D 51 C5 26 41 DD 0B EC 5D 6C 4E 6A 81 79 8A 2E DB A3 FA 38 32 37 D2 AC FF 1D D0 DE 75 0A 0F 1A 15 D9 F1 20 CD D6 4B 0C F4 B5 DF FC 87 D6 8E 34 45 6D",
+                aad: vec!["45 D0 0D 40 04", ""],
+                plaintext: "AA 4C FF BF 92 FB 10 E0 C6 98 DA 86 7C C9 94 1F",
+                expected_cipher: "78 58 9D 9A A3 12 0B BA 17 FF 95 D6 CA A0 B9 A1",
+                expected_siv: "9B 25 A0 4A B8 3A 32 2D 99 2D 12 95 0E 04 07 C3",
+            },
+            TestCase {
+                key: "5D 7F DF 44 BB 16 30 41 79 D2 54 46 01 D4 E4 6A 3D AF 83 CF 1D 76 6C AE 66 18 A0 72 1B CC 0B 99 F0 09 2B E4 B6 C5 86 DC 60 4B D3 4C 8E 7A E6 F6 BA D8 52 98 22 67 F7 2D 7D 87 9B EC 54 95 52 93",
+                aad: vec!["50 7A E0 2E 77"],
+                plaintext: "D0 77 44 34 80 52 4B BE CA F5 44 74 4A 18 44 89 EA",
+                expected_cipher: "CA 82 AF DD B0 A3 AA A2 A3 1A FB DB EA B4 7C 59 D0",
+                expected_siv: "AD 3F 30 8C 3D 75 74 0C 29 86 F6 B3 88 B7 B5 16",
+            },
+            TestCase {
+                key: "3E 28 13 B1 36 9C 49 F0 00 FF 88 36 FB 8A 52 D7 6E 65 4A E4 E7 0F 91 86 91 94 AE 3D D7 21 92 42 5F 4D 1B E7 B7 24 C9 56 AF FE 30 52 12 32 86 2A 34 B8 6E AD 2F F7 06 C8 80 54 C5 27 34 3E 5E BD",
+                aad: vec![],
+                plaintext: "FB 94 5B 31 00 2B 70 86 52 14 62 F6 A1 B1 37 9A 57 AB 5E 46 F1 9A 3C B5 DC 8D 28 73 E2 5C 25 58",
+                expected_cipher: "6D AE 0A 2D 7F E0 59 6E DC F3 13 D5 0B F3 EF 43 D2 C2 81 B6 4E 78 EA 89 50 99 B0 4B 79 97 29 A9",
+                expected_siv: "0F 29 0D 3D 04 47 2A 33 95 C2 E7 A5 81 CF 96 68",
+            },
+            TestCase {
+                key: "54 B8 E2 E7 2E 3A 9F 04 BA AD 06 FF FB FC 7D C4 24 D9 40 C9 00 F9 8D DA 4E C3 EF 84 AE 40 45 F9 07 EE A0 A5 19 36 D4 BB EC 7C 14 1A 5D 26 B5 D4 25 74 83 63 5F 19 A9 B9 50 D0 1C E8 1B A9 22 AE",
+                aad: vec!["", "AD ED 32 CA 2A 69 00 63 15 DF 25 5D 97 53 25 B5 3F BE 63 EA 68 7A 11", ""],
+                plaintext: "7B A1 AB A9 80 08 EE 38 92 33 E5 08 F2 58 CB E9 71 18 07 CB 50 DC 7D 07 07 C4 1A 82 42 21 E5 A3 DD BC 37 74 3A FE 9C 79 F6 66 5C AA 53 F7 9F",
+                expected_cipher: "C0 9D B5 AA 34 A1 61 AA 8E 32 15 BE 8B 26 A6 35 FC AB 19 36 C3 A6 16 F2 74 71 7A 3A BE 06 F8 7D FC AC FD 5C 10 6E C1 52 7B 3D 93 A0 21 93 D2",
+                expected_siv: "DE AA D9 6E 4F 9F 80 B3 F1 7D 42 8E B8 14 48 B3",
+            },
+            TestCase {
+                key: "58 67 4D 8F BB B7 12 7F 0C 39 E2 F5 A8 3E 8C 0D CE 9C 59 EF F2 3A 8D 6F 3A 83 40 5D 03 B1 B1 2C 83 FB EF C0 5D BD 5C DC 4D E9 21 B3 B7 F0 9E 52 62 1C CE 82 5B 66 28 7E 1B 01 CB 4A DB 05 09 B0",
+                aad: vec!["FF 76 90 50 B7 49 DA 87 5E 2F 67 75 81 2D CB 0F 12 9E FD F9 3E 3F F0"],
+                plaintext: "E7 F1 FB B4 4E 4F 1F 8E BF 14 95 EF 1F 00 61 03 CE D7 9C 52 52 76 E3 D6 55 F0 52 3A 4B 23 5F B2 E4 1A EA F6 F1 93 32 B0 23 3F 38 32 AE 46 8E 99 40 EB CE 22 BF C9 2D 8E E4 8B 76 F1 88 47 B6 EE",
+                expected_cipher: "18 98 49 29 26 DF A8 F2 A8 4D 0A 02 94 8D 93 67 12 6E 21 01 1A B4 5D F7 CA 02 BD 64 11 E9 67 A9 73 CD 8A C4 E9 10 F5 32 5F 0D 66 E2 C9 9B 42 DA 90 C9 E8 C5 2E 6A 9F E1 1F E9 FB 52 47 34 8D 63",
+                expected_siv: "0F 3B 7E A9 9E E3 E1 31 09 1D 0E 85 91 D5 78 1F",
+            },
+
+        ];
+
+        for test_case in test_cases {
+            let key_data = string_to_vec(test_case.key.to_string());
+            let aad: Vec<Vec<u8>> = test_case
+                .aad
+                .iter()
+                .map(|component| string_to_vec(component.to_string()))
+                .collect();
+            let aad: Vec<&[u8]> = aad.iter().map(Vec::as_slice).collect();
+            let plaintext = string_to_vec(test_case.plaintext.to_string());
+            let expected_cipher = string_to_vec(test_case.expected_cipher.to_string());
+            let expected_siv =
+                Block::try_from(string_to_vec(test_case.expected_siv.to_string())).unwrap();
+
+            for backend in BACKENDS {
+                let key = KeyPair::new_siv(key_data.as_slice()).unwrap();
+                let key = KeyPair::new(
+                    key.first.with_backend(backend),
+                    key.second.with_backend(backend),
+                );
+
+                let mut ciphertext = vec![];
+                let (bytes_written, siv) = siv::authenticated_encrypt(
+                    &mut plaintext.as_slice(),
+                    &mut ciphertext,
+                    &key,
+                    &aad,
+                )
+                .unwrap();
+                assert_eq!(bytes_written, plaintext.len());
+                assert_eq!(ciphertext, expected_cipher);
+                assert_eq!(siv, expected_siv);
+
+                let mut decrypted = vec![];
+                siv::authenticated_decrypt(
+                    &mut ciphertext.as_slice(),
+                    &mut decrypted,
+                    &key,
+                    &aad,
+                    siv,
+                )
+                .expect("decryption could not be authenticated");
+                assert_eq!(decrypted, plaintext);
+
+                // a tampered synthetic IV fails before anything is written
+                let mut decrypted = vec![];
+                let err = siv::authenticated_decrypt(
+                    &mut ciphertext.as_slice(),
+                    &mut decrypted,
+                    &key,
+                    &aad,
+                    siv ^ Block::new(1 << 127),
+                )
+                .expect_err("the synthetic IV has been tampered with, decryption should fail.");
+                assert_eq!(err, "authentication failed");
+                assert!(decrypted.is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn test_siv_invalid_parameters() {
+        let key_data: Vec<u8> = (0..64).collect();
+        let key = KeyPair::new_siv(key_data.as_slice()).unwrap();
+        let plaintext = get_nist_test_plaintext();
+        let encrypt = |aad: &[&[u8]]| {
+            let mut ciphertext = vec![];
+            let (_, siv) =
+                siv::authenticated_encrypt(&mut plaintext.as_slice(), &mut ciphertext, &key, aad)
+                    .unwrap();
+            (ciphertext, siv)
+        };
+
+        // deterministic, but the AAD components are authenticated separately and in order
+        let (ciphertext, siv) = encrypt(&[b"header", b"nonce"]);
+        assert_eq!(encrypt(&[b"header", b"nonce"]), (ciphertext.clone(), siv));
+        assert_ne!(encrypt(&[b"nonce", b"header"]).1, siv);
+        assert_ne!(encrypt(&[b"headernonce"]).1, siv);
+        assert_ne!(encrypt(&[b"header", b"nonce", b""]).1, siv);
+
+        // tampering with the ciphertext or the AAD is detected
+        let mut tampered = ciphertext.clone();
+        tampered[33] ^= 0x10;
+        let tampered_aad: [&[u8]; 2] = [b"header", b"Nonce"];
+        for (ciphertext, aad) in [
+            (tampered.as_slice(), &[&b"header"[..], &b"nonce"[..]][..]),
+            (ciphertext.as_slice(), &tampered_aad[..]),
+        ] {
+            let mut decrypted = vec![];
+            let result =
+                siv::authenticated_decrypt(&mut &ciphertext[..], &mut decrypted, &key, aad, siv);
+            assert!(result.is_err());
+            assert!(decrypted.is_empty());
+        }
+
+        // at most 126 AAD components
+        let aad = vec![&b"component"[..]; 127];
+        let result = siv::authenticated_encrypt(&mut plaintext.as_slice(), &mut vec![], &key, &aad);
+        assert!(result.is_err());
+        let result =
+            siv::authenticated_encrypt(&mut plaintext.as_slice(), &mut vec![], &key, &aad[1..]);
+        assert!(result.is_ok());
+
+        // 256, 384 or 512-bit keys
+        for len in [0, 16, 31, 40, 63, 65, 128] {
+            assert!(KeyPair::new_siv(&key_data.repeat(2)[..len]).is_err());
+        }
+    }
+
+    // IEEE 1619 Annex B vectors 1-4, 10 and 15-19
+    #[test]
+    fn test_aes_xts() {
+        struct TestCase {
+            data_key: &'static str,
+            tweak_key: &'static str,
+            tweak: u128,
+            plaintext: Vec<u8>,
+            expected_ciphertext: Vec<u8>,
+        }
+
+        let key_128 = (
+            "27182818284590452353602874713526",
+            "31415926535897932384626433832795",
+        );
+        let key_256 = (
+            "2718281828459045235360287471352662497757247093699959574966967627",
+            "3141592653589793238462643383279502884197169399375105820974944592",
+        );
+        let key_cts = (
+            "FFFEFDFCFBFAF9F8F7F6F5F4F3F2F1F0",
+            "BFBEBDBCBBBAB9B8B7B6B5B4B3B2B1B0",
+        );
+        let counting_bytes = |len: usize| (0..len).map(|i| i as u8).collect::<Vec<u8>>();
+
+        let test_cases = vec![
+            TestCase {
+                data_key: "00000000000000000000000000000000",
+                tweak_key: "00000000000000000000000000000000",
+                tweak: 0,
+                plaintext: vec![0; 32],
+                expected_ciphertext: string_to_vec(
+                    "917CF69E BD68B2EC 9B9FE9A3 EADDA692 CD43D2F5 9598ED85 8C02C265 2FBF922E"
+                        .to_string(),
+                ),
+            },
+            TestCase {
+                data_key: "11111111111111111111111111111111",
+                tweak_key: "22222222222222222222222222222222",
+                tweak: 0x3333333333,
+                plaintext: vec![0x44; 32],
+                expected_ciphertext: string_to_vec(
+                    "C454185E 6A16936E 39334038 ACEF838B FB186FFF 7480ADC4 289382EC D6D394F0"
+                        .to_string(),
+                ),
+            },
+            TestCase {
+                data_key: "FFFEFDFCFBFAF9F8F7F6F5F4F3F2F1F0",
+                tweak_key: "22222222222222222222222222222222",
+                tweak: 0x3333333333,
+                plaintext: vec![0x44; 32],
+                expected_ciphertext: string_to_vec(
+                    "AF85336B 597AFC1A 900B2EB2 1EC949D2 92DF4C04 7E0B2153 2186A597 1A227A89"
+                        .to_string(),
+                ),
+            },
+            TestCase {
+                data_key: key_128.0,
+                tweak_key: key_128.1,
+                tweak: 0,
+                plaintext: [counting_bytes(256), counting_bytes(256)].concat(),
+                expected_ciphertext: string_to_vec(
+                    concat!(
+                        "27A7479B EFA1D476 489F308C D4CFA6E2 A96E4BBE 3208FF25 287DD381 9616E89C",
+                        "C78CF7F5 E543445F 8333D8FA 7F560000 05279FA5 D8B5E4AD 40E736DD B4D35412",
+                        "328063FD 2AAB53E5 EA1E0A9F 332500A5 DF9487D0 7A5C92CC 512C8866 C7E860CE",
+                        "93FDF166 A24912B4 22976146 AE20CE84 6BB7DC9B A94A767A AEF20C0D 61AD0265",
+                        "5EA92DC4 C4E41A89 52C651D3 3174BE51 A10C4211 10E6D815 88EDE821 03A252D8",
+                        "A750E876 8DEFFFED 9122810A AEB99F91 72AF82B6 04DC4B8E 51BCB082 35A6F434",
+                        "1332E4CA 60482A4B A1A03B3E 65008FC5 DA76B70B F1690DB4 EAE29C5F 1BADD03C",
+                        "5CCF2A55 D705DDCD 86D44951 1CEB7EC3 0BF12B1F A35B913F 9F747A8A FD1B130E",
+                        "94BFF94E FFD01A91 735CA172 6ACD0B19 7C4E5B03 393697E1 26826FB6 BBDE8ECC",
+                        "1E082985 16E2C9ED 03FF3C1B 7860F6DE 76D4CECD 94C81198 55EF5297 CA67E9F3",
+                        "E7FF72B1 E99785CA 0A7E7720 C5B36DC6 D72CAC95 74C8CBBC 2F801E23 E56FD344",
+                        "B07F2215 4BEBA0F0 8CE8891E 643ED995 C94D9A69 C9F1B5F4 99027A78 572AEEBD",
+                        "74D20CC3 9881C213 EE770B10 10E4BEA7 18846977 AE119F7A 023AB58C CA0AD752",
+                        "AFE656BB 3C17256A 9F6E9BF1 9FDD5A38 FC82BBE8 72C5539E DB609EF4 F79C203E",
+                        "BB140F2E 583CB2AD 15B4AA5B 655016A8 449277DB D477EF2C 8D6C017D B738B18D",
+                        "EB4A427D 1923CE3F F2627357 79A418F2 0A282DF9 20147BEA BE421EE5 319D0568",
+                    )
+                    .to_string(),
+                ),
+            },
+            TestCase {
+                data_key: key_256.0,
+                tweak_key: key_256.1,
+                tweak: 0xff,
+                plaintext: [counting_bytes(256), counting_bytes(256)].concat(),
+                expected_ciphertext: string_to_vec(
+                    concat!(
+                        "1C3B3A10 2F770386 E4836C99 E370CF9B EA00803F 5E482357 A4AE12D4 14A3E63B",
+                        "5D31E276 F8FE4A8D 66B317F9 AC683F44 680A86AC 35ADFC33 45BEFECB 4BB188FD",
+                        "5776926C 49A3095E B108FD10 98BAEC70 AAA66999 A72A82F2 7D848B21 D4A741B0",
+                        "C5CD4D5F FF9DAC89 AEBA1229 61D03A75 7123E987 0F8ACF10 00020887 891429CA",
+                        "2A3E7A7D 7DF7B103 55165C8B 9A6D0A7D E8B062C4 500DC4CD 120C0F74 18DAE3D0",
+                        "B5781C34 803FA754 21C790DF E1DE1834 F280D766 7B327F6C 8CD7557E 12AC3A0F",
+                        "93EC05C5 2E0493EF 31A12D3D 9260F79A 289D6A37 9BC70C50 841473D1 A8CC81EC",
+                        "583E9645 E07B8D96 70655BA5 BBCFECC6 DC396638 0AD8FECB 17B6BA02 469A020A",
+                        "84E18E8F 84252070 C13E9F1F 289BE54F BC481457 778F6160 15E1327A 02B140F1",
+                        "505EB309 326D6837 8F837459 5C849D84 F4C333EC 44238851 43CB47BD 71C5EDAE",
+                        "9BE69A2F FECEB1BE C9DE244F BE15992B 11B77C04 0F12BD8F 6A975A44 A0F90C29",
+                        "A9ABC3D4 D8939272 84C58754 CCE29452 9F8614DC D2ABA991 925FEDC4 AE74FFAC",
+                        "6E333B93 EB4AFF04 79DA9A41 0E4450E0 DD7AE4C6 E2910900 575DA401 FC07059F",
+                        "645E8B7E 9BFDEF33 943054FF 84011493 C27B3429 EAEDB4ED 5376441A 77ED4385",
+                        "1AD77F16 F541DFD2 69D50D6A 5F14FB0A AB1CBB4C 1550BE97 F7AB4066 193C4CAA",
+                        "773DAD38 014BD209 2FA755C8 24BB5E54 C4F36FFD A9FCEA70 B9C6E693 E148C151",
+                    )
+                    .to_string(),
+                ),
+            },
+            // ciphertext stealing
+            TestCase {
+                data_key: key_cts.0,
+                tweak_key: key_cts.1,
+                tweak: 0x123456789a,
+                plaintext: counting_bytes(17),
+                expected_ciphertext: string_to_vec(
+                    "6C1625DB 4671522D 3D759960 1DE7CA09 ED".to_string(),
+                ),
+            },
+            TestCase {
+                data_key: key_cts.0,
+                tweak_key: key_cts.1,
+                tweak: 0x123456789a,
+                plaintext: counting_bytes(18),
+                expected_ciphertext: string_to_vec(
+                    "D069444B 7A7E0CAB 09E24447 D24DEB1F EDBF".to_string(),
+                ),
+            },
+            TestCase {
+                data_key: key_cts.0,
+                tweak_key: key_cts.1,
+                tweak: 0x123456789a,
+                plaintext: counting_bytes(19),
+                expected_ciphertext: string_to_vec(
+                    "E5DF1351 C0544BA1 350B3363 CD8EF4BE EDBF9D".to_string(),
+                ),
+            },
+            TestCase {
+                data_key: key_cts.0,
+                tweak_key: key_cts.1,
+                tweak: 0x123456789a,
+                plaintext: counting_bytes(20),
+                expected_ciphertext: string_to_vec(
+                    "9D84C813 F719AA2C 7BE3F661 71C7C5C2 EDBF9DAC".to_string(),
+                ),
+            },
+            TestCase {
+                data_key: key_cts.0,
+                tweak_key: key_cts.1,
+                tweak: 0x123456789a,
+                plaintext: counting_bytes(31),
+                expected_ciphertext: string_to_vec(
+                    "D05BC090 A8E04F1B 3D3ECDD5 BAEC0FD4 EDBF9DAC E45D6F6A 7306E64B E5DD82"
+                        .to_string(),
+                ),
+            },
+        ];
+
+        for test_case in test_cases {
+            for backend in BACKENDS {
+                let key = KeyPair::new(
+                    Key::try_from(string_to_vec(test_case.data_key.to_string()).as_slice())
+                        .unwrap()
+                        .with_backend(backend),
+                    Key::try_from(string_to_vec(test_case.tweak_key.to_string()).as_slice())
+                        .unwrap()
+                        .with_backend(backend),
+                );
+
+                let mut data = test_case.plaintext.clone();
+                xts::encrypt_data_unit(&mut data, &key, test_case.tweak).unwrap();
+                assert_eq!(data, test_case.expected_ciphertext);
+
+                xts::decrypt_data_unit(&mut data, &key, test_case.tweak).unwrap();
+                assert_eq!(data, test_case.plaintext);
+            }
+        }
+    }
+
+    #[test]
+    fn test_xts_sectors() {
+        let key_data = string_to_vec(
+            "27182818 28459045 23536028 74713526 31415926 53589793 23846264 33832795".to_string(),
+        );
+        let key = KeyPair::new_xts(key_data.as_slice()).unwrap();
+
+        // every sector is a data unit with its own tweak, the last one is shorter
+        let plaintext: Vec<u8> = (0..3 * 512 + 100).map(|i| (i * 7) as u8).collect();
+        let mut data = plaintext.clone();
+        xts::encrypt_sectors(&mut data, 512, 41, &key).unwrap();
+
+        for (index, (sector, ciphertext)) in plaintext.chunks(512).zip(data.chunks(512)).enumerate()
+        {
+            let mut expected = sector.to_vec();
+            xts::encrypt_data_unit(&mut expected, &key, 41 + index as u128).unwrap();
+            assert_eq!(ciphertext, expected);
+        }
+
+        xts::decrypt_sectors(&mut data, 512, 41, &key).unwrap();
+        assert_eq!(data, plaintext);
+
+        // a sector can't be shorter than a block, invalid input is rejected before any
+        // sector is processed
+        let mut data = vec![0; 512 + 15];
+        assert!(xts::encrypt_sectors(&mut data, 512, 0, &key).is_err());
+        assert!(xts::decrypt_sectors(&mut data, 512, 0, &key).is_err());
+        assert!(xts::encrypt_sectors(&mut data, 8, 0, &key).is_err());
+        assert!(xts::decrypt_data_unit(&mut data[..15], &key, 0).is_err());
+        assert_eq!(data, vec![0; 512 + 15]);
+
+        // SP 800-38E requires different data and tweak keys of 128 or 256 bits
+        assert!(KeyPair::new_xts(&key_data[..16]).is_err());
+        assert!(KeyPair::new_xts(&key_data[..24]).is_err());
+        assert!(KeyPair::new_xts([0x11; 32].as_slice()).is_err());
+    }
+
+    #[test]
+    fn test_xts_mul_alpha() {
+        // the bits move towards the last byte, the carry out of its MSB is reduced into the
+        // first byte
+        let block = Block::from([0x80, 0x01, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x80]);
+        assert_eq!(
+            xts::mul_alpha(block),
+            Block::from([0x87, 0x03, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0])
+        );
+    }
+
+    #[test]
+    fn test_read_all() {
+        let lengths = [
+            0,
+            1,
+            STREAM_BUFFER_SIZE - 1,
+            STREAM_BUFFER_SIZE,
+            STREAM_BUFFER_SIZE + 1,
+            5 * STREAM_BUFFER_SIZE + 17,
+        ];
+        for len in lengths {
+            let input: Vec<u8> = (0..len).map(|i| (i * 7 % 256) as u8).collect();
+
+            let data = read_all(&mut ShortReader(&input), len as u64).unwrap();
+            assert_eq!(*data, input);
+            // the buffer grows by doubling its initial size
+            assert!((data.capacity() / STREAM_BUFFER_SIZE).is_power_of_two());
+
+            if len > 0 {
+                match read_all(&mut ShortReader(&input), len as u64 - 1) {
+                    Err(err) => assert!(err.contains("too long")),
+                    Ok(_) => panic!("{} bytes accepted with a limit of {}", len, len - 1),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_multi_buffer_inputs() {
+        // lengths around the buffer size, read through a source that returns short reads
+        let lengths = [
+            0,
+            15,
+            16,
+            STREAM_BUFFER_SIZE - 1,
+            STREAM_BUFFER_SIZE,
+            2 * STREAM_BUFFER_SIZE + 17,
+        ];
+        let iv = get_nist_test_iv();
+
+        for backend in BACKENDS {
+            let key = get_nist_test_key_128().with_backend(backend);
+
+            for len in lengths {
+                let plaintext: Vec<u8> = (0..len).map(|i| (i * 7 % 256) as u8).collect();
+
+                // encrypt every block on its own as a reference
+                let mut padded = plaintext.clone();
+                padded.push(0x80);
+                padded.resize(len - len % BLOCK_SIZE + BLOCK_SIZE, 0);
+                let mut previous_block = Block::from(iv);
+                let mut expected_ecb = Vec::new();
+                let mut expected_cbc = Vec::new();
+                for chunk in padded.chunks(BLOCK_SIZE) {
+                    let block = Block::try_from(chunk).unwrap();
+                    expected_ecb.extend(encrypt_block(block, &key).bytes());
+                    previous_block = encrypt_block(block ^ previous_block, &key);
+                    expected_cbc.extend(previous_block.bytes());
+                }
+
+                let mut expected_ctr = plaintext.clone();
+                let initial_counter = u128::from(Block::from(get_nist_initial_counter()));
+                for (counter, mut chunk) in
+                    (initial_counter..).zip(expected_ctr.chunks_mut(BLOCK_SIZE))
+                {
+                    chunk ^= encrypt_block(Block::new(counter), &key);
+                }
+
+                for (mode, expected) in [
+                    (OperationMode::ECB, expected_ecb),
+                    (OperationMode::CBC { iv }, expected_cbc),
+                    (
+                        OperationMode::CTR {
+                            iv: get_nist_initial_counter(),
+                        },
+                        expected_ctr,
+                    ),
+                ] {
+                    let config = AESConfig::new(key.clone(), mode);
+                    let mut ciphertext = Vec::new();
+                    crate::aes::encrypt(&mut ShortReader(&plaintext), &mut ciphertext, &config)
+                        .unwrap();
+                    assert_eq!(ciphertext, expected);
+
+                    let mut decrypted = Vec::new();
+                    crate::aes::decrypt(&mut ShortReader(&ciphertext), &mut decrypted, &config)
+                        .unwrap();
+                    assert_eq!(decrypted, plaintext);
+                }
+
+                // GCM with a 96 bit IV encrypts like CTR mode starting at IV || 2
+                let gcm_iv = [0xca; 12];
+                let mut ctr_iv = [0; BLOCK_SIZE];
+                ctr_iv[..12].copy_from_slice(&gcm_iv);
+                ctr_iv[BLOCK_SIZE - 1] = 2;
+                let ctr_config = AESConfig::new(key.clone(), OperationMode::CTR { iv: ctr_iv });
+                let mut expected = Vec::new();
+                ctr::encrypt(&mut plaintext.as_slice(), &mut expected, &ctr_config).unwrap();
+
+                let mut ciphertext = Vec::new();
+                let (_, tag) = gcm::authenticated_encrypt(
+                    &mut ShortReader(&plaintext),
+                    &mut ciphertext,
+                    &key,
+                    backend,
+                    &gcm_iv,
+                    &[1, 2, 3],
+                )
+                .unwrap();
+                assert_eq!(ciphertext, expected);
+
+                let mut decrypted = Vec::new();
+                gcm::authenticated_decrypt(
+                    &mut ShortReader(&ciphertext),
+                    &mut decrypted,
+                    &key,
+                    backend,
+                    &gcm_iv,
+                    &[1, 2, 3],
+                    tag,
+                )
+                .unwrap();
+                assert_eq!(decrypted, plaintext);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parallel_matches_sequential() {
+        let lengths = [
+            0,
+            17,
+            PARALLEL_CHUNK_SIZE - 1,
+            PARALLEL_CHUNK_SIZE,
+            3 * PARALLEL_CHUNK_SIZE + 5,
+        ];
+        let key = get_nist_test_key_128().with_backend(Backend::AesNi);
+
+        for len in lengths {
+            let plaintext: Vec<u8> = (0..len).map(|i| (i * 13 % 256) as u8).collect();
+
+            for mode in [
+                OperationMode::ECB,
+                OperationMode::CBC {
+                    iv: get_nist_test_iv(),
+                },
+                OperationMode::CTR {
+                    iv: [0xff; BLOCK_SIZE],
+                },
+            ] {
+                let config = AESConfig::new(key.clone(), mode.clone());
+                let mut expected = Vec::new();
+                crate::aes::encrypt(&mut plaintext.as_slice(), &mut expected, &config).unwrap();
+
+                for threads in [1, 3] {
+                    let config = AESConfig::new(key.clone(), mode.clone()).with_threads(threads);
+
+                    if !matches!(config.mode, OperationMode::CBC { iv: _ }) {
+                        let mut ciphertext = Vec::new();
+                        parallel::encrypt(&mut plaintext.as_slice(), &mut ciphertext, &config)
+                            .unwrap();
+                        assert_eq!(ciphertext, expected);
+                    }
+
+                    let mut decrypted = Vec::new();
+                    parallel::decrypt(&mut expected.as_slice(), &mut decrypted, &config).unwrap();
+                    assert_eq!(decrypted, plaintext);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_parallel_invalid_input() {
+        let key = get_nist_test_key_128();
+        let config = AESConfig::new(key.clone(), OperationMode::ECB).with_threads(2);
+
+        // the error is only detected in the last of several chunks
+        let ciphertext = vec![0; 2 * PARALLEL_CHUNK_SIZE + 3];
+        parallel::decrypt(&mut ciphertext.as_slice(), &mut Vec::new(), &config)
+            .expect_err("the ciphertext length is not a multiple of the block size");
+        parallel::decrypt(&mut [].as_slice(), &mut Vec::new(), &config)
+            .expect_err("an empty ciphertext has no padding");
+
+        let config = AESConfig::new(
+            key,
+            OperationMode::OFB {
+                iv: [0; BLOCK_SIZE],
+            },
+        );
+        parallel::encrypt(&mut [].as_slice(), &mut Vec::new(), &config)
+            .expect_err("OFB mode cannot be parallelized");
+    }
+
+    #[test]
+    fn test_parallel_memory_bound() {
+        let threads = 2;
+        let chunks = 12 * threads;
+        let mut plaintext = vec![0; chunks * PARALLEL_CHUNK_SIZE];
+        plaintext[..BLOCK_SIZE].copy_from_slice(&SLOW_BLOCK.to_be_bytes());
+
+        let bytes_read = AtomicUsize::new(0);
+        let cipher = SlowFirstChunkCipher {
+            key: get_nist_test_key_128(),
+            bytes_read: &bytes_read,
+            total_bytes: plaintext.len(),
+            bytes_read_while_stalled: AtomicUsize::new(0),
+        };
+        let config = AESConfig::new(cipher, OperationMode::ECB).with_threads(threads);
+
+        let mut reader = CountingReader {
+            data: plaintext.as_slice(),
+            bytes_read: &bytes_read,
+        };
+        let mut ciphertext = Vec::new();
+        parallel::encrypt(&mut reader, &mut ciphertext, &config).unwrap();
+
+        // while the first chunk is processed, at most two chunks per thread are read
+        let bytes_read_while_stalled = config.key.bytes_read_while_stalled.load(Ordering::SeqCst);
+        assert!(bytes_read_while_stalled <= 2 * threads * PARALLEL_CHUNK_SIZE);
+
+        let mut expected = Vec::new();
+        let config = AESConfig::new(get_nist_test_key_128(), OperationMode::ECB);
+        crate::aes::encrypt(&mut plaintext.as_slice(), &mut expected, &config).unwrap();
+        assert_eq!(ciphertext, expected);
+    }
+
+    #[test]
+    fn test_modes_are_generic_over_the_cipher() {
+        let plaintext = get_nist_test_plaintext();
+        let iv = get_nist_test_iv();
+        let cipher = RecordingCipher::new(get_nist_test_key_128());
+
+        // CBC feeds the plaintext XOR the previous ciphertext block to the cipher
+        let config = AESConfig::new(cipher, OperationMode::CBC { iv });
+        let mut ciphertext = Vec::new();
+        cbc::encrypt(&mut plaintext.as_slice(), &mut ciphertext, &config).unwrap();
+        let mut previous_block = Block::from(iv);
+        for (i, chunk) in plaintext.chunks(BLOCK_SIZE).enumerate() {
+            let input = Block::try_from(chunk).unwrap() ^ previous_block;
+            assert_eq!(config.key.inputs.borrow()[i], input);
+            previous_block =
+                Block::try_from(&ciphertext[i * BLOCK_SIZE..(i + 1) * BLOCK_SIZE]).unwrap();
+        }
+
+        // CTR feeds the counter blocks to the cipher
+        let cipher = RecordingCipher::new(get_nist_test_key_128());
+        let initial_counter = get_nist_initial_counter();
+        let config = AESConfig::new(
+            cipher,
+            OperationMode::CTR {
+                iv: initial_counter,
+            },
+        );
+        ctr::encrypt(&mut plaintext.as_slice(), &mut Vec::new(), &config).unwrap();
+        let expected: Vec<Block> = (0..4)
+            .map(|i| Block::new(u128::from(Block::from(initial_counter)) + i))
+            .collect();
+        assert_eq!(*config.key.inputs.borrow(), expected);
+
+        // GCM derives its hash subkey from the zero block
+        let cipher = RecordingCipher::new(get_nist_test_key_128());
+        gcm::authenticated_encrypt(
+            &mut plaintext.as_slice(),
+            &mut Vec::new(),
+            &cipher,
+            Backend::Auto,
+            &[0; 12],
+            &[],
+        )
+        .unwrap();
+        assert_eq!(cipher.inputs.borrow()[0], Block::default());
+    }
+
+    // AES test double that records every block passed to the encryption function
+    struct RecordingCipher {
+        key: Key,
+        inputs: RefCell<Vec<Block>>,
+    }
+
+    impl RecordingCipher {
+        fn new(key: Key) -> Self {
+            Self {
+                key,
+                inputs: RefCell::new(Vec::new()),
+            }
+        }
+    }
+
+    impl BlockCipher for RecordingCipher {
+        fn encrypt_block(&self, block: Block) -> Block {
+            self.inputs.borrow_mut().push(block);
+            self.key.encrypt_block(block)
+        }
+
+        fn decrypt_block(&self, block: Block) -> Block {
+            self.key.decrypt_block(block)
+        }
+    }
+
+    const SLOW_BLOCK: u128 = u128::MAX;
+
+    // holds back the chunk starting with SLOW_BLOCK until the reader has stopped reading
+    // (or read everything) and records how much has been read up to then
+    struct SlowFirstChunkCipher<'a> {
+        key: Key,
+        bytes_read: &'a AtomicUsize,
+        total_bytes: usize,
+        bytes_read_while_stalled: AtomicUsize,
+    }
+
+    impl BlockCipher for SlowFirstChunkCipher<'_> {
+        fn encrypt_block(&self, block: Block) -> Block {
+            self.key.encrypt_block(block)
+        }
+
+        fn decrypt_block(&self, block: Block) -> Block {
+            self.key.decrypt_block(block)
+        }
+
+        fn encrypt_blocks(&self, blocks: &mut [Block]) {
+            if blocks.first() == Some(&Block::new(SLOW_BLOCK)) {
+                loop {
+                    let before = self.bytes_read.load(Ordering::SeqCst);
+                    std::thread::sleep(std::time::Duration::from_millis(100));
+                    let after = self.bytes_read.load(Ordering::SeqCst);
+                    if after == before || after == self.total_bytes {
+                        self.bytes_read_while_stalled.store(after, Ordering::SeqCst);
+                        break;
+                    }
+                }
+            }
+
+            self.key.encrypt_blocks(blocks);
+        }
+    }
+
+    struct CountingReader<'a> {
+        data: &'a [u8],
+        bytes_read: &'a AtomicUsize,
+    }
+
+    impl std::io::Read for CountingReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let len = self.data.read(buf)?;
+            self.bytes_read.fetch_add(len, Ordering::SeqCst);
+            Ok(len)
+        }
+    }
+
+    // returns at most 7 bytes per read call
+    struct ShortReader<'a>(&'a [u8]);
+
+    impl std::io::Read for ShortReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let len = buf.len().min(self.0.len()).min(7);
+            buf[..len].copy_from_slice(&self.0[..len]);
+            self.0 = &self.0[len..];
+            Ok(len)
+        }
+    }
+
+    fn get_nist_test_plaintext() -> Vec<u8> {
+        string_to_vec(
+            concat!(
+                "6BC1BEE2 2E409F96 E93D7E11 7393172A",
+                "AE2D8A57 1E03AC9C 9EB76FAC 45AF8E51",
+                "30C81C46 A35CE411 E5FBC119 1A0A52EF",
+                "F69F2445 DF4F9B17 AD2B417B E66C3710"
+            )
+            .to_string(),
+        )
+    }
+
+    fn get_nist_test_iv() -> [u8; BLOCK_SIZE] {
+        string_to_vec("00010203 04050607 08090A0B 0C0D0E0F".to_string())
+            .try_into()
+            .unwrap()
+    }
+
+    fn get_nist_initial_counter() -> [u8; BLOCK_SIZE] {
+        string_to_vec("F0F1F2F3 F4F5F6F7 F8F9FAFB FCFDFEFF".to_string())
+            .try_into()
+            .unwrap()
+    }
+
+    fn get_nist_test_key_128() -> Key {
+        let key_data = string_to_vec("2B7E1516 28AED2A6 ABF71588 09CF4F3C".to_string());
+        key_data.as_slice().try_into().unwrap()
+    }
+
+    fn get_nist_test_key_192() -> Key {
+        let key_data =
+            string_to_vec("8E73B0F7 DA0E6452 C810F32B 809079E5 62F8EAD2 522C6B7B".to_string());
         key_data.as_slice().try_into().unwrap()
     }
 
@@ -995,12 +3157,9 @@ mod test {
         key_data.as_slice().try_into().unwrap()
     }
 
+    // the test vectors are grouped with spaces and line breaks
     fn string_to_vec(mut str: String) -> Vec<u8> {
-        str = str.replace(' ', "");
-        str = str.replace('\n', "");
-        (0..str.len())
-            .step_by(2)
-            .map(|i| u8::from_str_radix(&str[i..i + 2], 16).unwrap())
-            .collect()
+        str.retain(|c| !c.is_whitespace());
+        hex_decode(&str).unwrap()
     }
 }
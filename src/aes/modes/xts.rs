@@ -0,0 +1,189 @@
+use crate::aes::{
+    cipher::BlockCipher,
+    config::KeyPair,
+    constants::{BLOCK_SIZE, XTS_MAX_DATA_UNIT_BLOCKS},
+    datastructures::block::Block,
+    modes::common::{load_blocks, store_blocks},
+    zeroize::Zeroizing,
+};
+
+// Encrypt a single data unit (e.g. a disk sector) in place. The tweak is the data unit's
+// sequence number, a data unit that isn't a multiple of 16 bytes is handled with
+// ciphertext stealing, so the ciphertext is exactly as long as the plaintext.
+pub fn encrypt_data_unit<C: BlockCipher>(
+    data_unit: &mut [u8],
+    key: &KeyPair<C>,
+    tweak: u128,
+) -> Result<(), String> {
+    run_data_unit(data_unit, key, tweak, false)
+}
+
+pub fn decrypt_data_unit<C: BlockCipher>(
+    data_unit: &mut [u8],
+    key: &KeyPair<C>,
+    tweak: u128,
+) -> Result<(), String> {
+    run_data_unit(data_unit, key, tweak, true)
+}
+
+// Split the data into data units of sector_size bytes, numbered from first_sector on.
+// The last sector may be shorter, but has to be at least one block long.
+pub fn encrypt_sectors<C: BlockCipher>(
+    data: &mut [u8],
+    sector_size: usize,
+    first_sector: u128,
+    key: &KeyPair<C>,
+) -> Result<(), String> {
+    run_sectors(data, sector_size, first_sector, key, false)
+}
+
+pub fn decrypt_sectors<C: BlockCipher>(
+    data: &mut [u8],
+    sector_size: usize,
+    first_sector: u128,
+    key: &KeyPair<C>,
+) -> Result<(), String> {
+    run_sectors(data, sector_size, first_sector, key, true)
+}
+
+fn run_sectors<C: BlockCipher>(
+    data: &mut [u8],
+    sector_size: usize,
+    first_sector: u128,
+    key: &KeyPair<C>,
+    decrypting: bool,
+) -> Result<(), String> {
+    // everything is checked up front, so an error never leaves the data partly processed
+    if !(BLOCK_SIZE..=XTS_MAX_DATA_UNIT_BLOCKS * BLOCK_SIZE).contains(&sector_size) {
+        return Err(format!(
+            "invalid sector size {}, expected 16 bytes (block size) to 2^20 blocks",
+            sector_size
+        ));
+    }
+
+    let last_sector_len = data.len() % sector_size;
+    if last_sector_len != 0 && last_sector_len < BLOCK_SIZE {
+        return Err(format!(
+            "invalid last sector length {}, expected at least 16 bytes (block size)",
+            last_sector_len
+        ));
+    }
+
+    let mut sector = first_sector;
+    for data_unit in data.chunks_mut(sector_size) {
+        run_data_unit(data_unit, key, sector, decrypting)?;
+        sector = sector.wrapping_add(1);
+    }
+
+    Ok(())
+}
+
+// XTS core operation (IEEE 1619 5.3 and 5.4), each block is XOR'd with its tweak value
+// before and after the block cipher operation
+fn run_data_unit<C: BlockCipher>(
+    data_unit: &mut [u8],
+    key: &KeyPair<C>,
+    tweak: u128,
+    decrypting: bool,
+) -> Result<(), String> {
+    if data_unit.len() < BLOCK_SIZE {
+        return Err(format!(
+            "invalid data unit length {}, expected at least 16 bytes (block size)",
+            data_unit.len()
+        ));
+    } else if data_unit.len() > XTS_MAX_DATA_UNIT_BLOCKS * BLOCK_SIZE {
+        return Err(format!(
+            "invalid data unit length {}, expected at most 2^20 blocks",
+            data_unit.len()
+        ));
+    }
+
+    let partial_len = data_unit.len() % BLOCK_SIZE;
+    // with ciphertext stealing, the last full block is processed together with the partial one
+    let regular_blocks = if partial_len == 0 {
+        data_unit.len() / BLOCK_SIZE
+    } else {
+        data_unit.len() / BLOCK_SIZE - 1
+    };
+    let (regular, stolen) = data_unit.split_at_mut(regular_blocks * BLOCK_SIZE);
+
+    // the tweak value of block j is E_K2(tweak) * alpha^j, K2 is the second key of the pair
+    let mut tweak_value = key.second.encrypt_block(Block::from(tweak.to_le_bytes()));
+    let mut tweak_values = Zeroizing::new(Vec::with_capacity(regular_blocks));
+    let mut blocks = Zeroizing::new(Vec::with_capacity(regular_blocks));
+
+    load_blocks(regular, &mut blocks);
+    for block in blocks.iter_mut() {
+        *block ^= tweak_value;
+        tweak_values.push(tweak_value);
+        tweak_value = mul_alpha(tweak_value);
+    }
+
+    // the first key of the pair is the data key
+    if decrypting {
+        key.first.decrypt_blocks(&mut blocks);
+    } else {
+        key.first.encrypt_blocks(&mut blocks);
+    }
+
+    for (block, tweak_value) in blocks.iter_mut().zip(tweak_values.iter()) {
+        *block ^= tweak_value;
+    }
+    store_blocks(&blocks, regular);
+
+    if partial_len != 0 {
+        // the decryption processes the last two blocks in reverse order, so the last full
+        // block uses the tweak value of the partial block and vice versa
+        let (last_tweak_value, partial_tweak_value) = if decrypting {
+            (mul_alpha(tweak_value), tweak_value)
+        } else {
+            (tweak_value, mul_alpha(tweak_value))
+        };
+        let (last_block, partial_block) = stolen.split_at_mut(BLOCK_SIZE);
+
+        // the partial block is padded with the tail of the processed last block, whose
+        // head becomes the (shortened) output of the partial block
+        let processed = Zeroizing::new(
+            process_block(
+                key,
+                (&*last_block).try_into()?,
+                last_tweak_value,
+                decrypting,
+            )
+            .bytes(),
+        );
+        let mut padded = Zeroizing::new(*processed);
+        padded[..partial_len].copy_from_slice(partial_block);
+        partial_block.copy_from_slice(&processed[..partial_len]);
+
+        let output = process_block(key, Block::from(*padded), partial_tweak_value, decrypting);
+        last_block.copy_from_slice(&output.bytes());
+    }
+
+    Ok(())
+}
+
+fn process_block<C: BlockCipher>(
+    key: &KeyPair<C>,
+    block: Block,
+    tweak_value: Block,
+    decrypting: bool,
+) -> Block {
+    let input_block = block ^ tweak_value;
+    let output_block = if decrypting {
+        key.first.decrypt_block(input_block)
+    } else {
+        key.first.encrypt_block(input_block)
+    };
+    output_block ^ tweak_value
+}
+
+// Multiply by the primitive element alpha (x) in GF(2^128) with the polynomial
+// x^128 + x^7 + x^2 + x + 1. Unlike GCM, XTS reads the block as a little-endian number:
+// the shift moves each bit towards the last byte and the carry out of byte 15 is
+// reduced into byte 0.
+pub fn mul_alpha(block: Block) -> Block {
+    let value = u128::from_le_bytes(block.bytes());
+    let carry = value >> 127;
+    Block::from(((value << 1) ^ (carry * 0x87)).to_le_bytes())
+}
@@ -0,0 +1,124 @@
+mod tests;
+
+use crate::aes::constants::BLOCK_SIZE;
+use std::io::Read;
+
+// =================================================================
+//                   operating system RNG
+// =================================================================
+
+// Fill the buffer with random bytes from the operating system. On x86_64 Linux the
+// getrandom syscall is used, it blocks until the kernel's pool has been initialized once.
+// Other systems (and kernels older than 3.17) read /dev/urandom instead.
+pub fn fill_random(buf: &mut [u8]) -> Result<(), String> {
+    #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+    match fill_from_getrandom(buf) {
+        Err(GetrandomError::Unsupported) => {}
+        result => return result.map_err(|err| err.to_string()),
+    }
+
+    fill_from_urandom(buf)
+}
+
+// a fresh IV for the modes of operation, see OperationMode::new_cbc etc.
+pub fn generate_iv() -> Result<[u8; BLOCK_SIZE], String> {
+    generate_nonce()
+}
+
+// random bytes of any fixed length, e.g. the 96-bit IVs recommended for GCM
+pub fn generate_nonce<const N: usize>() -> Result<[u8; N], String> {
+    let mut nonce = [0; N];
+    fill_random(&mut nonce)?;
+    Ok(nonce)
+}
+
+fn fill_from_urandom(buf: &mut [u8]) -> Result<(), String> {
+    let mut urandom = std::fs::File::open("/dev/urandom")
+        .map_err(|err| format!("couldn't open /dev/urandom: {}", err))?;
+    urandom
+        .read_exact(buf)
+        .map_err(|err| format!("couldn't read from /dev/urandom: {}", err))
+}
+
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+enum GetrandomError {
+    Unsupported,
+    Failed(i32),
+}
+
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+impl std::fmt::Display for GetrandomError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GetrandomError::Unsupported => write!(f, "getrandom is not supported"),
+            GetrandomError::Failed(errno) => write!(
+                f,
+                "getrandom failed: {}",
+                std::io::Error::from_raw_os_error(*errno)
+            ),
+        }
+    }
+}
+
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+fn fill_from_getrandom(mut buf: &mut [u8]) -> Result<(), GetrandomError> {
+    const EINTR: isize = 4;
+    const ENOSYS: isize = 38;
+
+    // requests above 256 bytes may be filled partially, or interrupted by a signal
+    while !buf.is_empty() {
+        match getrandom_syscall(buf) {
+            read if read > 0 => buf = &mut buf[read as usize..],
+            errno if errno == -EINTR => continue,
+            errno if errno == -ENOSYS => return Err(GetrandomError::Unsupported),
+            errno => return Err(GetrandomError::Failed(-errno as i32)),
+        }
+    }
+
+    Ok(())
+}
+
+// the number of bytes written, or the negated errno
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+fn getrandom_syscall(buf: &mut [u8]) -> isize {
+    const SYS_GETRANDOM: isize = 318;
+    let result: isize;
+
+    // SAFETY: the kernel writes at most buf.len() bytes to the buffer, flags = 0
+    unsafe {
+        std::arch::asm!(
+            "syscall",
+            inlateout("rax") SYS_GETRANDOM => result,
+            in("rdi") buf.as_mut_ptr(),
+            in("rsi") buf.len(),
+            in("rdx") 0,
+            lateout("rcx") _,
+            lateout("r11") _,
+            options(nostack),
+        );
+    }
+
+    result
+}
+
+// =================================================================
+//                   deterministic test RNG
+// =================================================================
+
+// small deterministic PRNG shared by the tests, they should not depend on external crates
+#[cfg(test)]
+pub(crate) struct XorShift(pub u64);
+
+#[cfg(test)]
+impl XorShift {
+    pub fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    pub fn next_u128(&mut self) -> u128 {
+        (self.next() as u128) << 64 | self.next() as u128
+    }
+}
@@ -0,0 +1,57 @@
+#[cfg(test)]
+mod test {
+    use crate::aes::{
+        config::{CFBSegmentSize, OperationMode},
+        random::{fill_from_urandom, fill_random, generate_iv, generate_nonce},
+    };
+
+    #[test]
+    fn test_fill_random() {
+        let mut empty = [];
+        fill_random(&mut empty).unwrap();
+
+        // larger than a single getrandom call is guaranteed to return
+        let mut first = vec![0u8; 4096];
+        let mut second = vec![0u8; 4096];
+        fill_random(&mut first).unwrap();
+        fill_random(&mut second).unwrap();
+        assert_ne!(first, second);
+        // the chance of a zeroed 16-byte chunk in random data is negligible
+        assert!(first.chunks(16).all(|chunk| chunk != [0; 16]));
+
+        let mut urandom = [0u8; 64];
+        fill_from_urandom(&mut urandom).unwrap();
+        assert_ne!(urandom, [0; 64]);
+    }
+
+    #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+    #[test]
+    fn test_getrandom_syscall() {
+        use crate::aes::random::fill_from_getrandom;
+
+        let mut buf = [0u8; 1024];
+        assert!(fill_from_getrandom(&mut buf).is_ok());
+        assert!(buf.chunks(16).all(|chunk| chunk != [0; 16]));
+    }
+
+    #[test]
+    fn test_generate_iv() {
+        assert_ne!(generate_iv().unwrap(), generate_iv().unwrap());
+        assert_ne!(generate_nonce::<12>().unwrap(), [0; 12]);
+
+        let modes = [
+            OperationMode::new_cbc().unwrap(),
+            OperationMode::new_cfb(CFBSegmentSize::Bit8).unwrap(),
+            OperationMode::new_ofb().unwrap(),
+            OperationMode::new_ctr().unwrap(),
+        ];
+        let ivs: Vec<[u8; 16]> = modes.iter().map(|mode| *mode.iv().unwrap()).collect();
+        for (index, iv) in ivs.iter().enumerate() {
+            assert!(!ivs[index + 1..].contains(iv));
+        }
+
+        // the CTR counter starts at zero so that 2^32 blocks can be encrypted
+        assert_eq!(ivs[3][12..], [0; 4]);
+        assert!(OperationMode::ECB.iv().is_none());
+    }
+}
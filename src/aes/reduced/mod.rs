@@ -0,0 +1,109 @@
+mod tests;
+
+use crate::aes::{
+    cipher::BlockCipher,
+    constants::ENCRYPTION_ROUNDS_AES256,
+    datastructures::{block::Block, colmat::ColMatrix},
+    key::{size::KeySize, Key},
+};
+
+// AES with a custom number of rounds (1 to 14, for any key length) for cryptanalysis
+// exercises such as square, impossible-differential or meet-in-the-middle attacks.
+// Unlike AES, the final round can keep its MixColumns step, which many attacks on
+// reduced-round variants assume. Never use it to protect actual data.
+#[derive(Clone)]
+pub struct ReducedAes {
+    key: Key,
+    final_mix_columns: bool,
+}
+
+impl ReducedAes {
+    pub fn new(key_data: &[u8], rounds: usize) -> Result<Self, String> {
+        if !(1..=ENCRYPTION_ROUNDS_AES256).contains(&rounds) {
+            return Err(format!(
+                "invalid number of rounds: expected 1 to {}, got {}",
+                ENCRYPTION_ROUNDS_AES256, rounds
+            ));
+        }
+
+        let key_size = KeySize::from_byte_size(key_data.len(), false)?;
+        Ok(ReducedAes {
+            key: Key::with_rounds(key_data, key_size, rounds)?,
+            final_mix_columns: false,
+        })
+    }
+
+    // apply MixColumns in the last round as well, like in every other round
+    pub fn with_final_mix_columns(mut self, final_mix_columns: bool) -> Self {
+        self.final_mix_columns = final_mix_columns;
+        self
+    }
+
+    pub fn rounds(&self) -> usize {
+        self.key.rounds()
+    }
+
+    pub fn final_mix_columns(&self) -> bool {
+        self.final_mix_columns
+    }
+
+    // the shortened key schedule, rounds() + 1 round keys
+    pub fn key(&self) -> &Key {
+        &self.key
+    }
+}
+
+impl BlockCipher for ReducedAes {
+    fn encrypt_block(&self, block: Block) -> Block {
+        let rounds = self.rounds();
+        let round_key = |round| *self.key.get_round_key(round).unwrap();
+
+        let mut colmat = ColMatrix::new(block);
+
+        // "0"th round: just apply the first block of the inital key
+        colmat += round_key(0);
+
+        for round in 1..rounds {
+            colmat.sub_bytes();
+            colmat.shift_rows();
+            colmat.mix_columns();
+            colmat += round_key(round);
+        }
+
+        // last round: mix columns only if requested
+        colmat.sub_bytes();
+        colmat.shift_rows();
+        if self.final_mix_columns {
+            colmat.mix_columns();
+        }
+        colmat += round_key(rounds);
+
+        colmat.block()
+    }
+
+    fn decrypt_block(&self, block: Block) -> Block {
+        let rounds = self.rounds();
+        let round_key = |round| *self.key.get_round_key(round).unwrap();
+
+        let mut colmat = ColMatrix::new(block);
+
+        // first round: undo the final mix columns only if it was applied
+        colmat += round_key(rounds);
+        if self.final_mix_columns {
+            colmat.inv_mix_columns();
+        }
+        colmat.inv_shift_rows();
+        colmat.inv_sub_bytes();
+
+        for round in (1..rounds).rev() {
+            colmat += round_key(round);
+            colmat.inv_mix_columns();
+            colmat.inv_shift_rows();
+            colmat.inv_sub_bytes();
+        }
+
+        colmat += round_key(0);
+
+        colmat.block()
+    }
+}
@@ -0,0 +1,170 @@
+#[cfg(test)]
+mod test {
+    use crate::aes::{
+        self,
+        backend::Backend,
+        cipher::BlockCipher,
+        config::{AESConfig, OperationMode},
+        datastructures::{block::Block, colmat::ColMatrix},
+        key::{size::KeySize, Key},
+        reduced::ReducedAes,
+        trace::{self, RoundObserver, Step},
+    };
+
+    // the FIPS-197 Appendix C key and plaintext
+    const KEY_128: [u8; 16] = [
+        0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e,
+        0x0f,
+    ];
+    const PLAINTEXT: u128 = 0x00112233445566778899aabbccddeeff;
+
+    // the states of a full encryption, as printed by the Fips197Tracer
+    #[derive(Default)]
+    struct RoundStates {
+        // round[r].s_row
+        shift_rows: Vec<Block>,
+        // round[r].k_sch
+        round_keys: Vec<Block>,
+        // the state after the key addition, round[r + 1].start
+        key_added: Vec<Block>,
+    }
+
+    impl RoundObserver for RoundStates {
+        fn observe(&mut self, round: usize, step: Step, state: &ColMatrix) {
+            match step {
+                Step::ShiftRows => {
+                    self.shift_rows.resize(round + 1, Block::default());
+                    self.shift_rows[round] = state.block();
+                }
+                Step::AddRoundKey(round_key) => {
+                    self.round_keys.push(round_key);
+                    self.key_added.push(state.block());
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // The first r rounds of AES are the reduced cipher with the final MixColumns, without it
+    // the last round ends with the key addition after ShiftRows.
+    fn expected_ciphertext(key_data: &[u8], rounds: usize, final_mix_columns: bool) -> Block {
+        let mut states = RoundStates::default();
+        trace::encrypt_block(
+            Block::new(PLAINTEXT),
+            &Key::try_from(key_data).unwrap(),
+            &mut states,
+        );
+
+        if final_mix_columns {
+            states.key_added[rounds]
+        } else {
+            states.shift_rows[rounds] ^ states.round_keys[rounds]
+        }
+    }
+
+    #[test]
+    fn test_reduced_round_vectors() {
+        // one round with the final MixColumns is FIPS-197 Appendix C.1's round[2].start
+        let cipher = ReducedAes::new(&KEY_128, 1)
+            .unwrap()
+            .with_final_mix_columns(true);
+        assert_eq!(
+            cipher.encrypt_block(Block::new(PLAINTEXT)),
+            Block::new(0x89d810e8855ace682d1843d8cb128fe4)
+        );
+
+        let key_256: Vec<u8> = (0..32).collect();
+        let test_cases = [
+            (&KEY_128[..], 1, false),
+            (&KEY_128[..], 1, true),
+            (&KEY_128[..], 4, true),
+            (&KEY_128[..], 5, false),
+            (&KEY_128[..], 9, true),
+            (&KEY_128[..], 10, false),
+            (&key_256[..], 3, false),
+            (&key_256[..], 13, true),
+        ];
+
+        for (key_data, rounds, final_mix_columns) in test_cases {
+            let cipher = ReducedAes::new(key_data, rounds)
+                .unwrap()
+                .with_final_mix_columns(final_mix_columns);
+            assert_eq!(cipher.rounds(), rounds);
+            assert_eq!(cipher.final_mix_columns(), final_mix_columns);
+
+            let ciphertext = cipher.encrypt_block(Block::new(PLAINTEXT));
+            assert_eq!(
+                ciphertext,
+                expected_ciphertext(key_data, rounds, final_mix_columns)
+            );
+            assert_eq!(cipher.decrypt_block(ciphertext), Block::new(PLAINTEXT));
+        }
+    }
+
+    #[test]
+    fn test_reduced_key_schedule() {
+        let key = Key::from(KEY_128);
+
+        for rounds in 1..=14 {
+            let cipher = ReducedAes::new(&KEY_128, rounds).unwrap();
+            assert!(cipher.key().get_round_key(rounds).is_some());
+            assert!(cipher.key().get_round_key(rounds + 1).is_none());
+
+            // the shortened schedule is a prefix of the full one
+            for round in 0..=rounds.min(key.rounds()) {
+                assert_eq!(cipher.key()[round], key[round]);
+            }
+
+            // with all rounds and without the final MixColumns, it is AES
+            if rounds == key.rounds() {
+                let block = Block::new(PLAINTEXT);
+                assert_eq!(cipher.encrypt_block(block), key.encrypt_block(block));
+            }
+
+            // the backends run the same number of rounds with the shortened key
+            let expected = cipher.encrypt_block(Block::new(PLAINTEXT));
+            for backend in Backend::ALL {
+                let key = Key::with_rounds(&KEY_128, KeySize::AES128, rounds)
+                    .unwrap()
+                    .with_backend(backend);
+                assert_eq!(key.encrypt_block(Block::new(PLAINTEXT)), expected);
+                assert_eq!(key.decrypt_block(expected), Block::new(PLAINTEXT));
+            }
+        }
+
+        assert!(ReducedAes::new(&KEY_128, 0).is_err());
+        assert!(ReducedAes::new(&KEY_128, 15).is_err());
+        assert!(ReducedAes::new(&KEY_128[..10], 4).is_err());
+    }
+
+    #[test]
+    fn test_reduced_round_modes() {
+        let plaintext: Vec<u8> = (0..100).collect();
+        let iv = [0x42; 16];
+
+        for mode in [OperationMode::CBC { iv }, OperationMode::CTR { iv }] {
+            for final_mix_columns in [false, true] {
+                let cipher = ReducedAes::new(&KEY_128, 2)
+                    .unwrap()
+                    .with_final_mix_columns(final_mix_columns);
+                let config = AESConfig::new(cipher.clone(), mode.clone());
+
+                let mut ciphertext = Vec::new();
+                aes::encrypt(&mut plaintext.as_slice(), &mut ciphertext, &config).unwrap();
+
+                // the first block is processed by the reduced cipher
+                let first_block = Block::try_from(&ciphertext[..16]).unwrap();
+                let input = Block::try_from(&plaintext[..16]).unwrap();
+                let expected = match mode {
+                    OperationMode::CBC { iv } => cipher.encrypt_block(input ^ Block::from(iv)),
+                    _ => cipher.encrypt_block(Block::from(iv)) ^ input,
+                };
+                assert_eq!(first_block, expected);
+
+                let mut decrypted = Vec::new();
+                aes::decrypt(&mut ciphertext.as_slice(), &mut decrypted, &config).unwrap();
+                assert_eq!(decrypted, plaintext);
+            }
+        }
+    }
+}
@@ -0,0 +1,241 @@
+mod tests;
+
+use crate::aes::{
+    constants::{
+        INV_MIX_COL_MATRIX, MIX_COL_MATRIX, RIJNDAEL_MAX_WORDS, RIJNDAEL_MIN_WORDS,
+        RIJNDAEL_SHIFT_OFFSETS, WORD_SIZE,
+    },
+    datastructures::{gf256::Gf256, word::Word},
+    zeroize::Zeroize,
+};
+use std::fmt::Debug;
+
+// the state holds up to 8 columns, only the first block_words are used
+type State = [Word; RIJNDAEL_MAX_WORDS];
+
+// Rijndael as originally submitted: block and key lengths are independently chosen from
+// 128, 160, 192, 224 and 256 bits. AES is the special case with a 128-bit block.
+// Like Key, the key schedule is zeroized on drop and Debug doesn't show it
+#[derive(Clone)]
+pub struct Rijndael {
+    // Nb, the number of columns of the state
+    block_words: usize,
+    // Nk, the number of words of the cipher key
+    key_words: usize,
+    rounds: usize,
+    round_keys: Vec<Word>,
+}
+
+impl Rijndael {
+    // key.len() and block_size are given in bytes
+    pub fn new(key: &[u8], block_size: usize) -> Result<Self, String> {
+        let block_words = words(block_size, "block")?;
+        let key_words = words(key.len(), "key")?;
+        let rounds = block_words.max(key_words) + 6;
+
+        Ok(Rijndael {
+            block_words,
+            key_words,
+            rounds,
+            round_keys: expand_key(key, block_words * (rounds + 1)),
+        })
+    }
+
+    pub fn block_size(&self) -> usize {
+        self.block_words * WORD_SIZE
+    }
+
+    pub fn key_size(&self) -> usize {
+        self.key_words * WORD_SIZE
+    }
+
+    pub fn rounds(&self) -> usize {
+        self.rounds
+    }
+
+    // the block_words columns of the given round's key
+    pub fn round_key(&self, round: usize) -> Option<&[Word]> {
+        let start = round * self.block_words;
+        self.round_keys.get(start..start + self.block_words)
+    }
+
+    pub fn encrypt_block(&self, block: &mut [u8]) -> Result<(), String> {
+        let mut state = self.load(block)?;
+
+        // "0"th round: just apply the first round key
+        self.add_round_key(&mut state, 0);
+
+        for round in 1..self.rounds {
+            self.sub_bytes(&mut state);
+            self.shift_rows(&mut state, false);
+            self.mix_columns(&mut state, MIX_COL_MATRIX);
+            self.add_round_key(&mut state, round);
+        }
+
+        // last round: do not mix columns
+        self.sub_bytes(&mut state);
+        self.shift_rows(&mut state, false);
+        self.add_round_key(&mut state, self.rounds);
+
+        self.store(&state, block);
+        Ok(())
+    }
+
+    pub fn decrypt_block(&self, block: &mut [u8]) -> Result<(), String> {
+        let mut state = self.load(block)?;
+
+        // first round: do not apply inverse mix columns
+        self.add_round_key(&mut state, self.rounds);
+        self.shift_rows(&mut state, true);
+        self.inv_sub_bytes(&mut state);
+
+        for round in (1..self.rounds).rev() {
+            self.add_round_key(&mut state, round);
+            self.mix_columns(&mut state, INV_MIX_COL_MATRIX);
+            self.shift_rows(&mut state, true);
+            self.inv_sub_bytes(&mut state);
+        }
+
+        self.add_round_key(&mut state, 0);
+
+        self.store(&state, block);
+        Ok(())
+    }
+
+    // =================================================================
+    //                     Rijndael operations
+    // =================================================================
+
+    fn add_round_key(&self, state: &mut State, round: usize) {
+        let round_key = &self.round_keys[round * self.block_words..];
+        state
+            .iter_mut()
+            .zip(round_key)
+            .take(self.block_words)
+            .for_each(|(col, key_col)| *col = *col ^ *key_col);
+    }
+
+    fn sub_bytes(&self, state: &mut State) {
+        state[..self.block_words]
+            .iter_mut()
+            .for_each(|col| *col = col.sub_word());
+    }
+
+    fn inv_sub_bytes(&self, state: &mut State) {
+        state[..self.block_words]
+            .iter_mut()
+            .for_each(|col| *col = col.inv_sub_word());
+    }
+
+    // row r is rotated to the left by C_r columns, the offsets depend on the block length.
+    // inverse rotates to the right instead
+    fn shift_rows(&self, state: &mut State, inverse: bool) {
+        let nb = self.block_words;
+        let offsets = RIJNDAEL_SHIFT_OFFSETS[nb - RIJNDAEL_MIN_WORDS];
+        let source = *state;
+
+        for (col, word) in state[..nb].iter_mut().enumerate() {
+            let mut bytes = word.bytes();
+            for (row, offset) in offsets.iter().enumerate() {
+                let source_col = if inverse {
+                    (col + nb - offset) % nb
+                } else {
+                    (col + offset) % nb
+                };
+                bytes[row + 1] = source[source_col].bytes()[row + 1];
+            }
+            *word = bytes.into();
+        }
+    }
+
+    // every column is multiplied by the (inverse) MixColumn matrix, as in AES
+    fn mix_columns(&self, state: &mut State, matrix: [Word; 4]) {
+        state[..self.block_words].iter_mut().for_each(|col| {
+            *col = matrix.map(|row| row * *col).into();
+        });
+    }
+
+    // =================================================================
+    //                      helper functions
+    // =================================================================
+
+    // the bytes fill the state column by column, like the AES state
+    fn load(&self, block: &[u8]) -> Result<State, String> {
+        if block.len() != self.block_size() {
+            return Err(format!(
+                "invalid block size: expected {} bytes, got {}",
+                self.block_size(),
+                block.len()
+            ));
+        }
+
+        let mut state = [Word::default(); RIJNDAEL_MAX_WORDS];
+        for (col, bytes) in state.iter_mut().zip(block.chunks_exact(WORD_SIZE)) {
+            *col = bytes.try_into()?;
+        }
+        Ok(state)
+    }
+
+    fn store(&self, state: &State, block: &mut [u8]) {
+        for (bytes, col) in block.chunks_exact_mut(WORD_SIZE).zip(state) {
+            bytes.copy_from_slice(&col.bytes());
+        }
+    }
+}
+
+impl Debug for Rijndael {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Rijndael")
+            .field("block_size", &self.block_size())
+            .field("key_size", &self.key_size())
+            .field("rounds", &self.rounds)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for Rijndael {
+    fn drop(&mut self) {
+        self.round_keys.zeroize();
+    }
+}
+
+// the key schedule of FIPS-197, Section 5.2, with Nb words per round key and the
+// round constants computed on the fly (a 128-bit key with a 256-bit block needs 29 of them)
+fn expand_key(key: &[u8], expanded_words: usize) -> Vec<Word> {
+    let key_words = key.len() / WORD_SIZE;
+
+    // allocated with its final size, a reallocation would leave the cipher key in the freed buffer
+    let mut expanded: Vec<Word> = Vec::with_capacity(expanded_words);
+    expanded.extend(
+        key.chunks_exact(WORD_SIZE)
+            .map(|bytes| Word::try_from(bytes).unwrap()),
+    );
+
+    for i in key_words..expanded_words {
+        let previous = expanded[i - 1];
+        let temp = if i % key_words == 0 {
+            let rcon = Gf256::new(0x02).pow((i / key_words - 1) as u32).value();
+            previous.rot_word().sub_word() ^ Word::new((rcon as u32) << 24)
+        } else if key_words > 6 && i % key_words == 4 {
+            previous.sub_word()
+        } else {
+            previous
+        };
+        expanded.push(expanded[i - key_words] ^ temp);
+    }
+
+    expanded
+}
+
+fn words(size: usize, name: &str) -> Result<usize, String> {
+    let min = RIJNDAEL_MIN_WORDS * WORD_SIZE;
+    let max = RIJNDAEL_MAX_WORDS * WORD_SIZE;
+    if !(min..=max).contains(&size) || !size.is_multiple_of(WORD_SIZE) {
+        return Err(format!(
+            "invalid {} size: expected 16, 20, 24, 28 or 32 bytes, got {}",
+            name, size
+        ));
+    }
+
+    Ok(size / WORD_SIZE)
+}
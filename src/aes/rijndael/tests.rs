@@ -0,0 +1,142 @@
+#[cfg(test)]
+mod test {
+    use crate::aes::{
+        datastructures::block::Block,
+        encoding::hex_decode,
+        key::{size::KeySize, Key},
+        modes::common::encrypt_block,
+        rijndael::Rijndael,
+    };
+
+    // the reference vectors of the Rijndael submission: the key and the plaintext are
+    // truncated to the key and block length, the rows are ordered by block length (128 to 256 bits),
+    // then by key length (128 to 256 bits)
+    const KEY: &str = "2b7e151628aed2a6abf7158809cf4f3c762e7160f38b4da56a784d9045190cfe";
+    const PLAINTEXT: &str = "3243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c8";
+    const CIPHERTEXTS: [[&str; 5]; 5] = [
+        [
+            "3925841d02dc09fbdc118597196a0b32",
+            "231d844639b31b412211cfe93712b880",
+            "f9fb29aefc384a250340d833b87ebc00",
+            "8faa8fe4dee9eb17caa4797502fc9d3f",
+            "1a6e6c2c662e7da6501ffb62bc9e93f3",
+        ],
+        [
+            "16e73aec921314c29df905432bc8968ab64b1f51",
+            "0553eb691670dd8a5a5b5addf1aa7450f7a0e587",
+            "73cd6f3423036790463aa9e19cfcde894ea16623",
+            "601b5dcd1cf4ece954c740445340bf0afdc048df",
+            "579e930b36c1529aa3e86628bacfe146942882cf",
+        ],
+        [
+            "b24d275489e82bb8f7375e0d5fcdb1f481757c538b65148a",
+            "738dae25620d3d3beff4a037a04290d73eb33521a63ea568",
+            "725ae43b5f3161de806a7c93e0bca93c967ec1ae1b71e1cf",
+            "bbfc14180afbf6a36382a061843f0b63e769acdc98769130",
+            "0ebacf199e3315c2e34b24fcc7c46ef4388aa475d66c194c",
+        ],
+        [
+            "b0a8f78f6b3c66213f792ffd2a61631f79331407a5e5c8d3793aceb1",
+            "08b99944edfce33a2acb131183ab0168446b2d15e958480010f545e3",
+            "be4c597d8f7efe22a2f7e5b1938e2564d452a5bfe72399c7af1101e2",
+            "ef529598ecbce297811b49bbed2c33bbe1241d6e1a833dbe119569e8",
+            "02fafc200176ed05deb8edb82a3555b0b10d47a388dfd59cab2f6c11",
+        ],
+        [
+            "7d15479076b69a46ffb3b3beae97ad8313f622f67fedb487de9f06b9ed9c8f19",
+            "514f93fb296b5ad16aa7df8b577abcbd484decacccc7fb1f18dc567309ceeffd",
+            "5d7101727bb25781bf6715b0e6955282b9610e23a43c2eb062699f0ebf5887b2",
+            "d56c5a63627432579e1dd308b2c8f157b40a4bfb56fea1377b25d3ed3d6dbf80",
+            "a49406115dfb30a40418aafa4869b7c6a886ff31602a7dd19c889dc64f7e4e7a",
+        ],
+    ];
+    const SIZES: [usize; 5] = [16, 20, 24, 28, 32];
+
+    #[test]
+    fn test_rijndael_reference_vectors() {
+        let key = hex_decode(KEY).unwrap();
+        let plaintext = hex_decode(PLAINTEXT).unwrap();
+
+        for (block_size, ciphertexts) in SIZES.into_iter().zip(CIPHERTEXTS) {
+            for (key_size, expected_ciphertext) in SIZES.into_iter().zip(ciphertexts) {
+                let cipher = Rijndael::new(&key[..key_size], block_size).unwrap();
+                assert_eq!(cipher.block_size(), block_size);
+                assert_eq!(cipher.key_size(), key_size);
+                assert_eq!(cipher.rounds(), block_size.max(key_size) / 4 + 6);
+
+                let mut block = plaintext[..block_size].to_vec();
+                cipher.encrypt_block(&mut block).unwrap();
+                assert_eq!(
+                    block,
+                    hex_decode(expected_ciphertext).unwrap(),
+                    "block size {}, key size {}",
+                    block_size * 8,
+                    key_size * 8
+                );
+
+                cipher.decrypt_block(&mut block).unwrap();
+                assert_eq!(block, &plaintext[..block_size]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rijndael_matches_aes() {
+        let key_data = hex_decode(KEY).unwrap();
+        let plaintext = hex_decode(PLAINTEXT).unwrap();
+
+        for key_size in [KeySize::AES128, KeySize::AES192, KeySize::AES256] {
+            let key_data = &key_data[..key_size.byte_size()];
+            let key = Key::try_from(key_data).unwrap();
+            let cipher = Rijndael::new(key_data, 16).unwrap();
+
+            for round in 0..=key_size.encryption_rounds() {
+                let round_key: Vec<u8> = cipher
+                    .round_key(round)
+                    .unwrap()
+                    .iter()
+                    .flat_map(|word| word.bytes())
+                    .collect();
+                assert_eq!(round_key, key[round].bytes());
+            }
+            assert!(cipher.round_key(key_size.encryption_rounds() + 1).is_none());
+
+            let mut block = plaintext[..16].to_vec();
+            cipher.encrypt_block(&mut block).unwrap();
+            let expected = encrypt_block(Block::try_from(&plaintext[..16]).unwrap(), &key);
+            assert_eq!(block, expected.bytes());
+        }
+    }
+
+    #[test]
+    fn test_rijndael_redaction() {
+        let key = hex_decode(KEY).unwrap();
+        let cipher = Rijndael::new(&key[..28], 24).unwrap();
+
+        // no word of the key schedule shows up in the output
+        let debug = format!("{:?}", cipher);
+        assert!(debug.contains("block_size: 24") && debug.contains("key_size: 28"));
+        for round in 0..=cipher.rounds() {
+            for word in cipher.round_key(round).unwrap() {
+                assert!(!debug.contains(&format!("{:08x}", word.0)));
+                assert!(!debug.contains(&word.0.to_string()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_rijndael_invalid_sizes() {
+        let key = hex_decode(KEY).unwrap();
+
+        assert!(Rijndael::new(&key[..12], 16).is_err());
+        assert!(Rijndael::new(&key[..18], 16).is_err());
+        assert!(Rijndael::new(&[0; 36], 16).is_err());
+        assert!(Rijndael::new(&key[..16], 8).is_err());
+        assert!(Rijndael::new(&key[..16], 22).is_err());
+        assert!(Rijndael::new(&key[..16], 40).is_err());
+
+        let cipher = Rijndael::new(&key[..16], 20).unwrap();
+        assert!(cipher.encrypt_block(&mut [0; 16]).is_err());
+        assert!(cipher.decrypt_block(&mut [0; 24]).is_err());
+    }
+}
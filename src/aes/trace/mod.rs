@@ -0,0 +1,164 @@
+mod tests;
+
+use crate::aes::{
+    datastructures::{block::Block, colmat::ColMatrix},
+    key::Key,
+    modes::common::{colmat_decrypt_block_observed, colmat_encrypt_block_observed},
+};
+
+// a single step of the cipher, AddRoundKey carries the round key that was added
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Step {
+    Input,
+    SubBytes,
+    ShiftRows,
+    MixColumns,
+    AddRoundKey(Block),
+    Output,
+    InvInput,
+    InvSubBytes,
+    InvShiftRows,
+    InvMixColumns,
+    InvOutput,
+}
+
+// Called with the state after every step of encrypt_block/decrypt_block. The round index
+// counts the rounds in the order they are executed, as in FIPS-197 Appendix C.
+pub trait RoundObserver {
+    fn observe(&mut self, _round: usize, _step: Step, _state: &ColMatrix) {}
+}
+
+// the default observer, its calls compile to nothing
+pub struct NoObserver;
+
+impl RoundObserver for NoObserver {
+    #[inline(always)]
+    fn observe(&mut self, _round: usize, _step: Step, _state: &ColMatrix) {}
+}
+
+// Encrypt a block step by step on the ColMatrix implementation (regardless of the key's
+// backend) and report every intermediate state to the observer.
+pub fn encrypt_block(block: Block, key: &Key, observer: &mut impl RoundObserver) -> Block {
+    colmat_encrypt_block_observed(block, key, observer)
+}
+
+pub fn decrypt_block(block: Block, key: &Key, observer: &mut impl RoundObserver) -> Block {
+    colmat_decrypt_block_observed(block, key, observer)
+}
+
+// =================================================================
+//                      FIPS-197 tracer
+// =================================================================
+
+// Writes the intermediate states in the format of FIPS-197 Appendix C, e.g.
+// "round[ 1].s_box 63cab7040953d051cd60e0e7ba70e18c", one line per value.
+pub struct Fips197Tracer<W: std::io::Write> {
+    writer: W,
+    decrypting: bool,
+    // the state after AddRoundKey is printed as the start of the next round (or as ik_add
+    // in the decryption) once it is clear that another round follows
+    pending_start: Option<Block>,
+    pending_key_add: Option<(usize, Block)>,
+    error: Option<std::io::Error>,
+}
+
+impl<W: std::io::Write> Fips197Tracer<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            decrypting: false,
+            pending_start: None,
+            pending_key_add: None,
+            error: None,
+        }
+    }
+
+    // the writer, or the first error that occurred while writing the trace
+    pub fn into_inner(self) -> Result<W, String> {
+        match self.error {
+            Some(err) => Err(err.to_string()),
+            None => Ok(self.writer),
+        }
+    }
+
+    fn write(&mut self, round: usize, name: &str, value: Block) {
+        if self.error.is_some() {
+            return;
+        }
+
+        if let Err(err) = writeln!(
+            self.writer,
+            "round[{:2}].{} {:032x}",
+            round,
+            name,
+            u128::from(value)
+        ) {
+            self.error = Some(err);
+        }
+    }
+
+    fn write_start(&mut self, round: usize, name: &str) {
+        if let Some(state) = self.pending_start.take() {
+            self.write(round, name, state);
+        }
+    }
+}
+
+impl<W: std::io::Write> RoundObserver for Fips197Tracer<W> {
+    fn observe(&mut self, round: usize, step: Step, state: &ColMatrix) {
+        let state = state.block();
+
+        match step {
+            Step::Input | Step::InvInput => {
+                self.decrypting = step == Step::InvInput;
+                self.pending_start = None;
+                self.pending_key_add = None;
+                self.write(
+                    round,
+                    if self.decrypting { "iinput" } else { "input" },
+                    state,
+                );
+            }
+            Step::SubBytes => {
+                self.write_start(round, "start");
+                self.write(round, "s_box", state);
+            }
+            Step::ShiftRows => self.write(round, "s_row", state),
+            Step::MixColumns => self.write(round, "m_col", state),
+            Step::AddRoundKey(round_key) => {
+                if self.decrypting {
+                    self.write(round, "ik_sch", round_key);
+                } else {
+                    self.write(round, "k_sch", round_key);
+                }
+
+                // in the decryption, InvMixColumns separates the key addition from the next round
+                if self.decrypting && round > 0 {
+                    self.pending_key_add = Some((round, state));
+                } else {
+                    self.pending_start = Some(state);
+                }
+            }
+            Step::InvShiftRows => {
+                self.write_start(round, "istart");
+                self.write(round, "is_row", state);
+            }
+            Step::InvSubBytes => self.write(round, "is_box", state),
+            Step::InvMixColumns => {
+                if let Some((round, key_added)) = self.pending_key_add.take() {
+                    self.write(round, "ik_add", key_added);
+                }
+                self.pending_start = Some(state);
+            }
+            Step::Output | Step::InvOutput => {
+                self.pending_start = None;
+                self.pending_key_add = None;
+                self.write(
+                    round,
+                    if self.decrypting { "ioutput" } else { "output" },
+                    state,
+                );
+            }
+        }
+    }
+}
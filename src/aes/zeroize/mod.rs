@@ -0,0 +1,101 @@
+mod tests;
+
+use crate::aes::datastructures::{block::Block, gf128::Gf128, word::Word};
+use std::{
+    mem::MaybeUninit,
+    ops::{Deref, DerefMut},
+    sync::atomic::{compiler_fence, Ordering},
+};
+
+// Overwrite secret values with zeros. The writes are volatile, so the compiler can't remove
+// them even though the value is never read again (e.g. right before it is dropped).
+// Copies of Copy values (e.g. a Block passed by value) are not covered.
+pub trait Zeroize {
+    fn zeroize(&mut self);
+}
+
+macro_rules! impl_zeroize_with_default {
+    ($($ty:ty),*) => {
+        $(
+            impl Zeroize for $ty {
+                fn zeroize(&mut self) {
+                    // SAFETY: self is a valid, aligned reference
+                    unsafe { std::ptr::write_volatile(self, <$ty>::default()) };
+                    compiler_fence(Ordering::SeqCst);
+                }
+            }
+        )*
+    };
+}
+
+impl_zeroize_with_default!(u8, u16, u32, u64, u128, usize, bool, Block, Word, Gf128);
+
+impl<T: Zeroize> Zeroize for [T] {
+    fn zeroize(&mut self) {
+        self.iter_mut().for_each(Zeroize::zeroize);
+    }
+}
+
+impl<T: Zeroize, const N: usize> Zeroize for [T; N] {
+    fn zeroize(&mut self) {
+        self.as_mut_slice().zeroize();
+    }
+}
+
+impl<T: Zeroize> Zeroize for Option<T> {
+    fn zeroize(&mut self) {
+        if let Some(value) = self {
+            value.zeroize();
+        }
+    }
+}
+
+// the elements and the unused capacity (e.g. left over from truncate) are wiped,
+// the length is kept
+impl<T: Zeroize> Zeroize for Vec<T> {
+    fn zeroize(&mut self) {
+        self.as_mut_slice().zeroize();
+
+        for slot in self.spare_capacity_mut() {
+            // SAFETY: the slot is valid for writes, MaybeUninit has no invalid bit patterns
+            unsafe { std::ptr::write_volatile(slot, MaybeUninit::zeroed()) };
+        }
+        compiler_fence(Ordering::SeqCst);
+    }
+}
+
+impl Zeroize for String {
+    fn zeroize(&mut self) {
+        // SAFETY: zero bytes are valid UTF-8
+        unsafe { self.as_mut_vec() }.zeroize();
+    }
+}
+
+// A value that is zeroized when it goes out of scope, used for the buffers of the modes.
+pub struct Zeroizing<T: Zeroize>(T);
+
+impl<T: Zeroize> Zeroizing<T> {
+    pub fn new(value: T) -> Self {
+        Zeroizing(value)
+    }
+}
+
+impl<T: Zeroize> Deref for Zeroizing<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: Zeroize> DerefMut for Zeroizing<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T: Zeroize> Drop for Zeroizing<T> {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
@@ -0,0 +1,57 @@
+#[cfg(test)]
+mod test {
+    use crate::aes::{
+        datastructures::block::Block,
+        zeroize::{Zeroize, Zeroizing},
+    };
+    use std::{cell::Cell, rc::Rc};
+
+    #[test]
+    fn test_zeroize() {
+        let mut block = Block::new(0x0123456789abcdef0123456789abcdef);
+        block.zeroize();
+        assert_eq!(block, Block::default());
+
+        let mut array = [[0xffu128; 8]; 3];
+        array.zeroize();
+        assert_eq!(array, [[0; 8]; 3]);
+
+        let mut option = Some(0xdeadbeefu32);
+        option.zeroize();
+        assert_eq!(option, Some(0));
+
+        // the spare capacity is wiped as well, the length stays the same
+        let mut vec = vec![0xaau8; 64];
+        vec.truncate(16);
+        vec.zeroize();
+        assert_eq!(vec, [0; 16]);
+        let spare_capacity = vec.spare_capacity_mut();
+        assert!(spare_capacity.len() >= 48);
+        // SAFETY: zeroize initialized the spare capacity with zeros
+        assert!(spare_capacity
+            .iter()
+            .all(|byte| unsafe { byte.assume_init() } == 0));
+    }
+
+    #[test]
+    fn test_zeroizing_drop() {
+        // records whether it was zeroized
+        struct Probe(Rc<Cell<bool>>);
+
+        impl Zeroize for Probe {
+            fn zeroize(&mut self) {
+                self.0.set(true);
+            }
+        }
+
+        let zeroized = Rc::new(Cell::new(false));
+        let probe = Zeroizing::new(Probe(zeroized.clone()));
+        assert!(!zeroized.get());
+        drop(probe);
+        assert!(zeroized.get());
+
+        let mut buf = Zeroizing::new(vec![0u8; 4]);
+        buf.copy_from_slice(&[1, 2, 3, 4]);
+        assert_eq!(buf.as_slice(), [1, 2, 3, 4]);
+    }
+}
@@ -1,18 +1,14 @@
 use aes_rs::aes::{
     config::{AESConfig, OperationMode},
     decrypt, decrypt_file, encrypt, encrypt_file,
-    key::Key,
+    key::{size::KeySize, Key},
 };
 use std::path::Path;
 
 fn main() -> Result<(), String> {
-    let key = Key::from([
-        0x60, 0x3d, 0xeb, 0x10, 0x15, 0xca, 0x71, 0xbe, 0x2b, 0x73, 0xae, 0xf0, 0x85, 0x7d, 0x77,
-        0x81, 0x1f, 0x35, 0x2c, 0x07, 0x3b, 0x61, 0x08, 0xd7, 0x2d, 0x98, 0x10, 0xa3, 0x09, 0x14,
-        0xdf, 0xf4,
-    ]);
-    let iv = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16];
-    let config = AESConfig::new(key, OperationMode::OFB { iv });
+    let key = Key::generate(KeySize::AES256)?;
+    let config = AESConfig::new(key, OperationMode::new_ofb()?);
+    println!("generated {}", config.key);
 
     encrypt_sample_file(&config)?;
     decrypt_sample_file(&config)?;
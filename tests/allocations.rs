@@ -0,0 +1,63 @@
+// The allocation test swaps the global allocator, so it lives in its own test binary
+// instead of affecting every unit test.
+use aes_rs::aes::{backend::Backend, cipher::BlockCipher, datastructures::block::Block, key::Key};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+// counts the heap allocations of the current thread, the tests run in parallel
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        // the counter may already be gone while a thread shuts down
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn count_allocations<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+    (result, ALLOCATIONS.with(Cell::get) - before)
+}
+
+#[test]
+fn test_block_encryption_does_not_allocate() {
+    let keys = [
+        Key::from([0x2b; 16]),
+        Key::from([0x8e; 24]),
+        Key::from([0x60; 32]),
+    ];
+
+    for backend in Backend::ALL {
+        for key in &keys {
+            let key = key.clone().with_backend(backend);
+            let block = Block::new(0x000102030405060708090a0b0c0d0e0f);
+
+            let (ciphertext, allocations) = count_allocations(|| key.encrypt_block(block));
+            assert_eq!(allocations, 0, "{:?} encryption allocated", backend);
+
+            let (decrypted, allocations) = count_allocations(|| key.decrypt_block(ciphertext));
+            assert_eq!(allocations, 0, "{:?} decryption allocated", backend);
+            assert_eq!(decrypted, block);
+        }
+    }
+}